@@ -0,0 +1,135 @@
+//! Run scenario files headless and write their currency time series.
+//!
+//! ```text
+//! simulate [--ticks N] [--format csv|json] [--out-dir DIR] [--metrics] SCENARIO.json...
+//! ```
+//!
+//! Each scenario is written to `DIR/<scenario file stem>.<format>` (default: next to the
+//! scenario). `--ticks` overrides the tick count stored in every scenario. `--metrics` also
+//! writes the full per-tick metrics (flux, gene states, cell mass) to
+//! `DIR/<stem>.metrics.jsonl`. Scenarios whose
+//! expectations fail are reported and make the run exit with a failure status.
+
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+
+use metabolistic3d::scenario::Scenario;
+
+const USAGE: &str =
+    "usage: simulate [--ticks N] [--format csv|json] [--out-dir DIR] [--metrics] SCENARIO.json...";
+
+struct Options {
+    ticks: Option<u64>,
+    format: String,
+    out_dir: Option<PathBuf>,
+    metrics: bool,
+    scenarios: Vec<PathBuf>,
+}
+
+fn parse_args(mut args: impl Iterator<Item = String>) -> Result<Options, String> {
+    let mut options = Options {
+        ticks: None,
+        format: "csv".to_string(),
+        out_dir: None,
+        metrics: false,
+        scenarios: Vec::new(),
+    };
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--ticks" => {
+                let value = args.next().ok_or("--ticks needs a value")?;
+                let ticks = value
+                    .parse()
+                    .map_err(|_| format!("invalid tick count `{}`", value))?;
+                options.ticks = Some(ticks);
+            }
+            "--format" => {
+                let value = args.next().ok_or("--format needs a value")?;
+                if value != "csv" && value != "json" {
+                    return Err(format!("unknown format `{}` (expected csv or json)", value));
+                }
+                options.format = value;
+            }
+            "--out-dir" => {
+                let value = args.next().ok_or("--out-dir needs a value")?;
+                options.out_dir = Some(PathBuf::from(value));
+            }
+            "--metrics" => options.metrics = true,
+            "-h" | "--help" => return Err(USAGE.to_string()),
+            flag if flag.starts_with("--") => return Err(format!("unknown option `{}`", flag)),
+            path => options.scenarios.push(PathBuf::from(path)),
+        }
+    }
+
+    if options.scenarios.is_empty() {
+        return Err(USAGE.to_string());
+    }
+    Ok(options)
+}
+
+fn output_path(scenario: &Path, options: &Options, extension: &str) -> PathBuf {
+    let stem = scenario.file_stem().unwrap_or_default().to_string_lossy();
+    let dir = match &options.out_dir {
+        Some(dir) => dir.clone(),
+        None => scenario.parent().map(Path::to_path_buf).unwrap_or_default(),
+    };
+    dir.join(format!("{}.{}", stem, extension))
+}
+
+fn run(path: &Path, options: &Options) -> Result<PathBuf, String> {
+    let mut scenario =
+        Scenario::load_from_file(path).map_err(|err| format!("{}: {}", path.display(), err))?;
+    if let Some(ticks) = options.ticks {
+        scenario.ticks = ticks;
+    }
+
+    let (series, metrics) = scenario
+        .run_with_metrics()
+        .map_err(|err| format!("{}: {}", path.display(), err))?;
+    let output = output_path(path, options, &options.format);
+    series
+        .save_to_file(&output)
+        .map_err(|err| format!("{}: {}", output.display(), err))?;
+    if options.metrics {
+        let metrics_output = output_path(path, options, "metrics.jsonl");
+        metrics
+            .save_to_file(&metrics_output)
+            .map_err(|err| format!("{}: {}", metrics_output.display(), err))?;
+    }
+
+    let failures = scenario.evaluate(&series);
+    if !failures.is_empty() {
+        let details: Vec<String> = failures.iter().map(|f| format!("  {}", f)).collect();
+        return Err(format!("{}:\n{}", path.display(), details.join("\n")));
+    }
+    Ok(output)
+}
+
+fn main() -> ExitCode {
+    let options = match parse_args(std::env::args().skip(1)) {
+        Ok(options) => options,
+        Err(message) => {
+            eprintln!("{}", message);
+            return ExitCode::from(2);
+        }
+    };
+
+    let mut failures = 0;
+    for path in &options.scenarios {
+        match run(path, &options) {
+            Ok(output) => eprintln!("{} -> {}", path.display(), output.display()),
+            Err(message) => {
+                eprintln!("error: {}", message);
+                failures += 1;
+            }
+        }
+    }
+
+    if failures > 0 {
+        eprintln!("{} of {} scenarios failed", failures, options.scenarios.len());
+        ExitCode::FAILURE
+    } else {
+        ExitCode::SUCCESS
+    }
+}
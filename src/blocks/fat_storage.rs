@@ -1,25 +1,32 @@
 use bevy::prelude::*;
 use crate::molecules::{Currency, CellMass, PolyMer, LipidToxicityThreshold};
-use crate::metabolism::CurrencyPools;
+use crate::metabolism::cells::{CellMember, CellSchedule, CellScope, CellsPlugin};
+use crate::metabolism::{CurrencyPools, MetabolicSet};
+use crate::player::physics::CellPhysics;
 
 /// Plugin for the Fat Storage block.
 pub struct FatStoragePlugin;
 
 impl Plugin for FatStoragePlugin {
     fn build(&self, app: &mut App) {
-        app.add_systems(Update, (
-            polymerize_beads_system,
-            lipolysis_system,
-        ));
+        CellsPlugin::add_to(app);
+        app.init_resource::<CellPhysics>()
+            .add_systems(CellSchedule, (
+                polymerize_beads_system,
+                lipolysis_system,
+            ).chain().in_set(MetabolicSet::Blocks));
     }
 }
 
-/// System to polymerize FreeFattyAcids into StorageBeads.
+/// System to polymerize FreeFattyAcids into StorageBeads, once per metabolic tick.
 /// This system activates when FreeFattyAcids exceed a LipidToxicityThreshold.
 /// It consumes FFA and ATP, produces StorageBeads, and updates CellMass.
 fn polymerize_beads_system(
     mut currency_pools: ResMut<CurrencyPools>,
     lipid_toxicity_threshold: Res<LipidToxicityThreshold>,
+    physics: Res<CellPhysics>,
+    mut query: Query<(&mut CellMass, Option<&CellMember>)>,
+    scope: CellScope,
 ) {
     let free_fatty_acids = currency_pools.get(Currency::FreeFattyAcids);
     if free_fatty_acids > lipid_toxicity_threshold.0 {
@@ -31,6 +38,11 @@ fn polymerize_beads_system(
         if currency_pools.can_consume(Currency::FreeFattyAcids, ffa_to_polymerize) {
             currency_pools.modify(Currency::FreeFattyAcids, -ffa_to_polymerize);
             currency_pools.modify(Currency::StorageBeads, ffa_to_polymerize);
+            for (mut cell_mass, member) in query.iter_mut() {
+                if scope.contains(member) {
+                    cell_mass.extra += ffa_to_polymerize * physics.mass_per_bead; // Beads make the cell heavier
+                }
+            }
             println!("System: Polymerized {:.2} FFA into storage beads", ffa_to_polymerize);
         }
     }
@@ -41,19 +53,24 @@ fn polymerize_beads_system(
 fn lipolysis_system(
     mut currency_pools: ResMut<CurrencyPools>,
     lipid_toxicity_threshold: Res<LipidToxicityThreshold>,
-    mut query: Query<(&mut CellMass, &PolyMer)>,
+    physics: Res<CellPhysics>,
+    mut query: Query<(&mut CellMass, &PolyMer, Option<&CellMember>)>,
+    scope: CellScope,
 ) {
     let free_fatty_acids = currency_pools.get(Currency::FreeFattyAcids);
     // Only run lipolysis if we're NOT in a toxic state (i.e., when FFA levels are safe)
     // This prevents lipolysis from interfering with toxicity management
     if free_fatty_acids <= lipid_toxicity_threshold.0 {
-        for (mut cell_mass, polymer) in query.iter_mut() {
+        for (mut cell_mass, polymer, member) in query.iter_mut() {
+            if !scope.contains(member) {
+                continue;
+            }
             let storage_beads = currency_pools.get(Currency::StorageBeads);
             let beads_to_mobilize = polymer.lipo_rate.min(storage_beads);
             if beads_to_mobilize > 0.0 {
                 currency_pools.modify(Currency::StorageBeads, -beads_to_mobilize);
                 currency_pools.modify(Currency::FreeFattyAcids, beads_to_mobilize);
-                cell_mass.extra -= beads_to_mobilize; // Decrease cell mass as beads are mobilized
+                cell_mass.extra -= beads_to_mobilize * physics.mass_per_bead; // Decrease cell mass as beads are mobilized
                 currency_pools.modify(Currency::ATP, beads_to_mobilize * 0.05); // Example ATP gain
             }
         }
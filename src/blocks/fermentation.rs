@@ -1,5 +1,6 @@
 use crate::molecules::Currency;
-use crate::metabolism::{CurrencyPools, FluxProfile, MetabolicBlock, MetabolicNode, BlockStatus};
+use crate::metabolism::cells::{CellMember, CellSchedule, CellScope, CellsPlugin};
+use crate::metabolism::{CurrencyPools, FluxProfile, MetabolicBlock, MetabolicNode, MetabolicSet, BlockStatus};
 use crate::blocks::genome::BlockKind;
 use bevy::prelude::*;
 
@@ -13,13 +14,20 @@ pub struct FermentationPlugin;
 
 impl Plugin for FermentationPlugin {
     fn build(&self, app: &mut App) {
+        CellsPlugin::add_to(app);
         app.insert_resource(FermentationRate(1.0)) // Default rate
             .add_systems(Startup, spawn_fermentation_block)
-            .add_systems(FixedUpdate, fermentation_system);
+            .add_systems(CellSchedule, fermentation_system.in_set(MetabolicSet::Blocks));
     }
 }
 
 fn spawn_fermentation_block(mut commands: Commands) {
+    commands.spawn(fermentation_block());
+    println!("FermentationBlock spawned with FluxProfile!");
+}
+
+/// A fermentation block, silent until the genome expresses it.
+pub fn fermentation_block() -> impl Bundle {
     let mut flux_profile = FluxProfile::default();
     // Define the fermentation flux profile: consumes Pyruvate and ReducingPower, produces ATP and OrganicWaste
     flux_profile.0.insert(Currency::Pyruvate, -1.0);       // Consumes 1 unit of Pyruvate
@@ -27,7 +35,7 @@ fn spawn_fermentation_block(mut commands: Commands) {
     flux_profile.0.insert(Currency::ATP, 1.0);             // Produces 1 unit of ATP
     flux_profile.0.insert(Currency::OrganicWaste, 1.0);    // Produces 1 unit of OrganicWaste
     
-    commands.spawn((
+    (
         FermentationBlock,
         MetabolicBlock,
         MetabolicNode {
@@ -35,18 +43,24 @@ fn spawn_fermentation_block(mut commands: Commands) {
             status: BlockStatus::Silent, // Will be updated by genome system
         },
         flux_profile,
-    ));
-    println!("FermentationBlock spawned with FluxProfile!");
+    )
 }
 
+/// Fermentation nodes, of every cell.
+type FermentationNodes = (With<FermentationBlock>, With<MetabolicNode>);
+
 fn fermentation_system(
     fermentation_rate: Res<FermentationRate>,
     currency_pools: Res<CurrencyPools>,
-    mut query_fermentation: Query<&mut FluxProfile, (With<FermentationBlock>, With<MetabolicNode>)>,
+    mut query_fermentation: Query<(&mut FluxProfile, Option<&CellMember>), FermentationNodes>,
+    scope: CellScope,
 ) {
     let rate = fermentation_rate.0;
 
-    for mut flux_profile in query_fermentation.iter_mut() {
+    for (mut flux_profile, member) in query_fermentation.iter_mut() {
+        if !scope.contains(member) {
+            continue;
+        }
         // Check resource availability before setting flux profile
         let pyruvate_available = currency_pools.get(Currency::Pyruvate);
         let reducing_power_available = currency_pools.get(Currency::ReducingPower);
@@ -35,14 +35,24 @@
 //! - Press 'H' to silence the Fermentation gene  
 //! - Press 'J' to add a new Light Capture gene
 //! - Press 'K' to spawn new metabolic block entities
+//! - Press 'Ctrl+Z' to undo the last genome command, 'Ctrl+Y' to redo it
 
+use crate::metabolism::cells::{CellSchedule, CellScope, CellsPlugin, PlayerCell};
+use crate::metabolism::{CurrencyPools, MetabolicSet};
+use crate::molecules::Currency;
 use bevy::prelude::*;
 use rand::prelude::*;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
 /// Represents the different types of metabolic blocks that can be encoded in the genome
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Component, Serialize, Deserialize)]
+///
+/// Serialized as stable snake_case ids; if a variant is renamed, pin its old id
+/// with `#[serde(rename = "...")]` so existing save files keep loading.
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Component, Serialize, Deserialize,
+)]
+#[serde(rename_all = "snake_case")]
 pub enum BlockKind {
     LightCapture,
     SugarCatabolism,
@@ -56,6 +66,8 @@ pub enum BlockKind {
     SecondaryMetabolites,
     AromaticPrecursorSynthesis,
     Polymerization,
+    /// The flagellum; the cell cannot move without it
+    Motility,
 }
 
 impl BlockKind {
@@ -74,18 +86,20 @@ impl BlockKind {
             BlockKind::SecondaryMetabolites => "Produce pigments and toxins",
             BlockKind::AromaticPrecursorSynthesis => "Create aromatic precursors",
             BlockKind::Polymerization => "Polymerize lignin and other biopolymers",
+            BlockKind::Motility => "Drive a flagellum to move the cell",
         }
     }
 }
 
 /// The state of a gene tile in the genome
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
 pub enum GeneState {
     /// Gene is present but not expressed (no enzyme production)
     Silent,
     /// Gene is actively expressed (enzyme is being produced)
     Expressed,
-    /// Gene is mutated and temporarily non-functional
+    /// Gene is mutated and expressed at half strength until repaired
     Mutated,
 }
 
@@ -95,31 +109,81 @@ impl Default for GeneState {
     }
 }
 
-/// Resource containing the entire chromosome of gene tiles
-#[derive(Resource, Default)]
+/// The entire chromosome of gene tiles, a component of every
+/// [`Cell`](crate::metabolism::cells::Cell). A resource only while a cell is simulated.
+#[derive(Resource, Component, Default)]
 pub struct Genome {
     pub table: HashMap<BlockKind, GeneState>,
     /// Track previous state for diff computation
     previous_table: HashMap<BlockKind, GeneState>,
 }
 
+/// Version of the genome save format written by [`GenomeSaveData::to_json`].
+///
+/// Bump this whenever the on-disk layout changes and add a matching step to
+/// [`migrate_genome_save`] so files written by older builds keep loading.
+pub const GENOME_SAVE_VERSION: u32 = 2;
+
 /// Serializable representation of a gene
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct GeneRecord {
     pub kind: BlockKind,
     pub state: GeneState,
+    /// Informational only; regenerated from `kind` on save and ignored on load.
+    #[serde(default)]
     pub description: String,
 }
 
 /// Data format used to save or load a genome in JSON form
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct GenomeSaveData {
+    pub version: u32,
     pub genes: Vec<GeneRecord>,
 }
 
+/// Errors that can occur while loading a saved genome
+#[derive(Debug)]
+pub enum GenomeLoadError {
+    /// The input is not valid JSON or does not match the genome layout
+    MalformedJson(serde_json::Error),
+    /// A gene refers to a block kind this build does not know about
+    UnknownBlockKind(String),
+    /// The file was written by a newer (or otherwise unknown) format version
+    UnsupportedVersion(u32),
+}
+
+impl std::fmt::Display for GenomeLoadError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            GenomeLoadError::MalformedJson(err) => write!(f, "malformed genome JSON: {}", err),
+            GenomeLoadError::UnknownBlockKind(kind) => write!(f, "unknown block kind `{}`", kind),
+            GenomeLoadError::UnsupportedVersion(version) => write!(
+                f,
+                "unsupported genome save version {} (latest supported is {})",
+                version, GENOME_SAVE_VERSION
+            ),
+        }
+    }
+}
+
+impl std::error::Error for GenomeLoadError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            GenomeLoadError::MalformedJson(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl From<serde_json::Error> for GenomeLoadError {
+    fn from(err: serde_json::Error) -> Self {
+        GenomeLoadError::MalformedJson(err)
+    }
+}
+
 impl From<&Genome> for GenomeSaveData {
     fn from(genome: &Genome) -> Self {
-        let genes = genome
+        let mut genes: Vec<GeneRecord> = genome
             .table
             .iter()
             .map(|(kind, state)| GeneRecord {
@@ -128,7 +192,12 @@ impl From<&Genome> for GenomeSaveData {
                 description: kind.description().to_string(),
             })
             .collect();
-        Self { genes }
+        // Stable ordering keeps shared genome files diff-friendly
+        genes.sort_by_key(|record| record.kind);
+        Self {
+            version: GENOME_SAVE_VERSION,
+            genes,
+        }
     }
 }
 
@@ -152,10 +221,128 @@ impl GenomeSaveData {
         serde_json::to_string_pretty(self)
     }
 
-    /// Deserialize a genome from a JSON string
-    pub fn from_json(json: &str) -> Result<Self, serde_json::Error> {
-        serde_json::from_str(json)
+    /// Deserialize a genome from a JSON string, migrating older versions
+    pub fn from_json(json: &str) -> Result<Self, GenomeLoadError> {
+        let value: serde_json::Value = serde_json::from_str(json)?;
+        Self::from_value(value)
+    }
+
+    /// Deserialize a genome from an already parsed JSON value, migrating older versions
+    pub fn from_value(value: serde_json::Value) -> Result<Self, GenomeLoadError> {
+        let value = migrate_genome_save(value)?;
+        check_block_kinds(&value)?;
+        Ok(serde_json::from_value(value)?)
+    }
+}
+
+/// Upgrade a raw genome save to [`GENOME_SAVE_VERSION`], one version at a time.
+///
+/// Files without a `version` field predate versioning and are treated as version 0.
+pub fn migrate_genome_save(
+    mut value: serde_json::Value,
+) -> Result<serde_json::Value, GenomeLoadError> {
+    let mut version = match value.get("version") {
+        None => 0,
+        Some(v) => v
+            .as_u64()
+            .and_then(|v| u32::try_from(v).ok())
+            .ok_or_else(|| {
+                GenomeLoadError::MalformedJson(serde::de::Error::custom(
+                    "`version` must be a non-negative integer",
+                ))
+            })?,
+    };
+
+    if version > GENOME_SAVE_VERSION {
+        return Err(GenomeLoadError::UnsupportedVersion(version));
+    }
+
+    while version < GENOME_SAVE_VERSION {
+        value = match version {
+            0 => migrate_v0_to_v1(value)?,
+            1 => migrate_v1_to_v2(value)?,
+            _ => return Err(GenomeLoadError::UnsupportedVersion(version)),
+        };
+        version += 1;
+    }
+
+    Ok(value)
+}
+
+/// v0 -> v1: wrap in a versioned envelope and switch `kind`/`state` from
+/// Rust variant names (`SugarCatabolism`) to stable snake_case ids (`sugar_catabolism`).
+fn migrate_v0_to_v1(mut value: serde_json::Value) -> Result<serde_json::Value, GenomeLoadError> {
+    let Some(object) = value.as_object_mut() else {
+        return Err(GenomeLoadError::MalformedJson(serde::de::Error::custom(
+            "genome save must be a JSON object",
+        )));
+    };
+
+    if let Some(genes) = object.get_mut("genes").and_then(|g| g.as_array_mut()) {
+        for gene in genes.iter_mut() {
+            for field in ["kind", "state"] {
+                if let Some(serde_json::Value::String(name)) = gene.get_mut(field) {
+                    *name = pascal_to_snake_case(name);
+                }
+            }
+        }
     }
+
+    object.insert("version".to_string(), serde_json::Value::from(1u32));
+    Ok(value)
+}
+
+/// v1 -> v2: cells became motile, so genomes saved before the flagellum existed gain an
+/// expressed `motility` gene like the starter genome has.
+fn migrate_v1_to_v2(mut value: serde_json::Value) -> Result<serde_json::Value, GenomeLoadError> {
+    let Some(object) = value.as_object_mut() else {
+        return Err(GenomeLoadError::MalformedJson(serde::de::Error::custom(
+            "genome save must be a JSON object",
+        )));
+    };
+
+    if let Some(genes) = object.get_mut("genes").and_then(|g| g.as_array_mut()) {
+        if !genes.iter().any(|gene| gene["kind"] == "motility") {
+            genes.push(serde_json::json!({ "kind": "motility", "state": "expressed" }));
+        }
+    }
+
+    object.insert("version".to_string(), serde_json::Value::from(2u32));
+    Ok(value)
+}
+
+fn pascal_to_snake_case(name: &str) -> String {
+    let mut out = String::with_capacity(name.len() + 4);
+    for (i, ch) in name.chars().enumerate() {
+        if ch.is_ascii_uppercase() {
+            if i > 0 {
+                out.push('_');
+            }
+            out.push(ch.to_ascii_lowercase());
+        } else {
+            out.push(ch);
+        }
+    }
+    out
+}
+
+/// Report unknown block kinds by name instead of as a generic serde error
+fn check_block_kinds(value: &serde_json::Value) -> Result<(), GenomeLoadError> {
+    let Some(genes) = value.get("genes").and_then(|g| g.as_array()) else {
+        return Ok(());
+    };
+    for gene in genes {
+        if let Some(kind) = gene.get("kind") {
+            if serde_json::from_value::<BlockKind>(kind.clone()).is_err() {
+                let name = kind
+                    .as_str()
+                    .map(str::to_string)
+                    .unwrap_or_else(|| kind.to_string());
+                return Err(GenomeLoadError::UnknownBlockKind(name));
+            }
+        }
+    }
+    Ok(())
 }
 
 impl Genome {
@@ -165,7 +352,7 @@ impl Genome {
     }
 
     /// Create a genome from a JSON string produced by [`GenomeSaveData`]
-    pub fn from_json(json: &str) -> Result<Self, serde_json::Error> {
+    pub fn from_json(json: &str) -> Result<Self, GenomeLoadError> {
         GenomeSaveData::from_json(json).map(Into::into)
     }
 
@@ -174,6 +361,11 @@ impl Genome {
         self.table.insert(block_kind, GeneState::Silent);
     }
 
+    /// Remove a gene tile from the genome, returning its last state
+    pub fn remove_gene(&mut self, block_kind: BlockKind) -> Option<GeneState> {
+        self.table.remove(&block_kind)
+    }
+
     /// Express a gene (activate the metabolic block)
     pub fn express_gene(&mut self, block_kind: BlockKind) -> bool {
         if let Some(state) = self.table.get_mut(&block_kind) {
@@ -234,6 +426,16 @@ impl Genome {
         self.table.get(block_kind)
     }
 
+    /// How strongly a gene is expressed: 1 when expressed, 0.5 when mutated, 0 when silent
+    /// or absent.
+    pub fn expression_level(&self, block_kind: BlockKind) -> f32 {
+        match self.table.get(&block_kind) {
+            Some(GeneState::Expressed) => 1.0,
+            Some(GeneState::Mutated) => 0.5,
+            _ => 0.0,
+        }
+    }
+
     /// Get all expressed genes
     pub fn get_expressed_genes(&self) -> Vec<BlockKind> {
         self.table
@@ -249,7 +451,7 @@ impl Genome {
     }
 
     /// Internal method to update the previous state snapshot
-    fn update_previous_state(&mut self) {
+    pub(crate) fn update_previous_state(&mut self) {
         self.previous_table = self.table.clone();
     }
 
@@ -287,6 +489,27 @@ impl Genome {
         }
         false
     }
+
+    /// Copy of this genome for a daughter cell. With a `mutation_config`, every gene is
+    /// exposed to its strategy for `exposure` seconds, scaled like any other mutation.
+    pub fn replicate(&self, mutation_config: Option<&mut MutationConfig>, exposure: f32) -> Genome {
+        let mut copy = Genome {
+            table: self.table.clone(),
+            previous_table: HashMap::new(),
+        };
+        if let Some(config) = mutation_config {
+            let exposure = exposure * config.rate_multiplier;
+            let mut kinds: Vec<BlockKind> = copy.table.keys().copied().collect();
+            kinds.sort();
+            for kind in kinds {
+                if config.strategy.should_mutate(kind, exposure) {
+                    let target = config.strategy.get_mutation_target(kind);
+                    apply_mutation(&mut copy, kind, &target);
+                }
+            }
+        }
+        copy
+    }
 }
 
 /// Event containing changes in gene expression that affect metabolic blocks
@@ -300,6 +523,299 @@ pub struct GenomeDiffEvent {
 #[derive(Event, Debug)]
 pub struct MetabolicUpdateEvent;
 
+/// A player-issued change to the genome, applied by [`apply_genome_commands`].
+///
+/// Input systems send these instead of editing [`Genome`] directly, so the same
+/// path serves live play, the genome editor and replays. Every command is checked
+/// against the current genome and paid for from [`GenomeOperationCosts`] before it
+/// is applied; a rejected command changes nothing.
+#[derive(Event, Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "op", content = "gene", rename_all = "snake_case")]
+pub enum GenomeCommand {
+    /// Turn a silent gene on
+    Express(BlockKind),
+    /// Turn an expressed gene off
+    Silence(BlockKind),
+    /// Insert a new, silent gene
+    Add(BlockKind),
+    /// Delete a gene in any state
+    Remove(BlockKind),
+    /// Return a mutated gene to silent
+    Repair(BlockKind),
+    /// Replace the first gene with the second, which takes over its state
+    Swap(BlockKind, BlockKind),
+}
+
+/// Undo or redo the most recent genome command, see [`GenomeHistory`].
+#[derive(Event, Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum GenomeHistoryCommand {
+    Undo,
+    Redo,
+}
+
+/// Why a [`GenomeCommand`], undo or redo was rejected
+#[derive(Debug, Clone, PartialEq)]
+pub enum GenomeCommandError {
+    /// The command needs a gene the genome does not have
+    MissingGene(BlockKind),
+    /// The command would add a gene the genome already has
+    GeneAlreadyPresent(BlockKind),
+    /// The gene is present but in the wrong state for the command
+    WrongState { block_kind: BlockKind, state: GeneState },
+    /// Not enough of a currency to pay for the command
+    InsufficientCurrency {
+        currency: Currency,
+        required: f32,
+        available: f32,
+    },
+    /// Undo/redo found the gene changed by something else (e.g. a mutation) since the edit
+    Conflict(BlockKind),
+}
+
+impl std::fmt::Display for GenomeCommandError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            GenomeCommandError::MissingGene(kind) => write!(f, "{:?} gene is not present", kind),
+            GenomeCommandError::GeneAlreadyPresent(kind) => {
+                write!(f, "{:?} gene is already present", kind)
+            }
+            GenomeCommandError::WrongState { block_kind, state } => {
+                write!(f, "{:?} gene is {:?}", block_kind, state)
+            }
+            GenomeCommandError::InsufficientCurrency {
+                currency,
+                required,
+                available,
+            } => write!(
+                f,
+                "needs {:.1} {:?} but only {:.1} is available",
+                required, currency, available
+            ),
+            GenomeCommandError::Conflict(kind) => {
+                write!(f, "{:?} gene has changed since the edit", kind)
+            }
+        }
+    }
+}
+
+impl std::error::Error for GenomeCommandError {}
+
+/// Gene states as a command found or left them; `None` means the gene is absent.
+pub type GeneStates = Vec<(BlockKind, Option<GeneState>)>;
+
+/// An applied [`GenomeCommand`] together with everything needed to reverse it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct GenomeEdit {
+    pub command: GenomeCommand,
+    pub before: GeneStates,
+    pub after: GeneStates,
+    /// What was paid, refunded on undo and charged again on redo
+    pub cost: Vec<(Currency, f32)>,
+}
+
+/// How many edits [`GenomeHistory`] keeps before dropping the oldest.
+pub const GENOME_HISTORY_LIMIT: usize = 100;
+
+/// Undo and redo stacks of applied genome commands.
+#[derive(Resource, Debug, Default)]
+pub struct GenomeHistory {
+    undo: Vec<GenomeEdit>,
+    redo: Vec<GenomeEdit>,
+}
+
+impl GenomeCommand {
+    /// Check the command against `genome` and return the gene states it would leave.
+    pub fn plan(&self, genome: &Genome) -> Result<GeneStates, GenomeCommandError> {
+        let present = |kind: BlockKind| {
+            genome
+                .get_gene_state(&kind)
+                .cloned()
+                .ok_or(GenomeCommandError::MissingGene(kind))
+        };
+        let absent = |kind: BlockKind| match genome.get_gene_state(&kind) {
+            Some(_) => Err(GenomeCommandError::GeneAlreadyPresent(kind)),
+            None => Ok(()),
+        };
+        let require = |kind: BlockKind, required: GeneState| {
+            let state = present(kind)?;
+            if state == required {
+                Ok(())
+            } else {
+                Err(GenomeCommandError::WrongState {
+                    block_kind: kind,
+                    state,
+                })
+            }
+        };
+
+        match *self {
+            GenomeCommand::Express(kind) => {
+                require(kind, GeneState::Silent)?;
+                Ok(vec![(kind, Some(GeneState::Expressed))])
+            }
+            GenomeCommand::Silence(kind) => {
+                require(kind, GeneState::Expressed)?;
+                Ok(vec![(kind, Some(GeneState::Silent))])
+            }
+            GenomeCommand::Add(kind) => {
+                absent(kind)?;
+                Ok(vec![(kind, Some(GeneState::Silent))])
+            }
+            GenomeCommand::Remove(kind) => {
+                present(kind)?;
+                Ok(vec![(kind, None)])
+            }
+            GenomeCommand::Repair(kind) => {
+                require(kind, GeneState::Mutated)?;
+                Ok(vec![(kind, Some(GeneState::Silent))])
+            }
+            GenomeCommand::Swap(out, into) => {
+                let state = present(out)?;
+                absent(into)?;
+                Ok(vec![(out, None), (into, Some(state))])
+            }
+        }
+    }
+}
+
+impl GenomeHistory {
+    /// Push an applied edit, clearing the redo stack.
+    pub fn record(&mut self, edit: GenomeEdit) {
+        if self.undo.len() == GENOME_HISTORY_LIMIT {
+            self.undo.remove(0);
+        }
+        self.undo.push(edit);
+        self.redo.clear();
+    }
+
+    /// Edits that can be undone, oldest first.
+    pub fn undo_stack(&self) -> &[GenomeEdit] {
+        &self.undo
+    }
+
+    /// Edits that can be redone, most recently undone last.
+    pub fn redo_stack(&self) -> &[GenomeEdit] {
+        &self.redo
+    }
+
+    /// Reverse the most recent edit and refund its cost.
+    ///
+    /// Returns the undone command, or `None` if there is nothing to undo. An edit whose
+    /// genes have changed since is dropped with [`GenomeCommandError::Conflict`].
+    pub fn undo(
+        &mut self,
+        genome: &mut Genome,
+        pools: &mut CurrencyPools,
+    ) -> Result<Option<GenomeCommand>, GenomeCommandError> {
+        let Some(edit) = self.undo.pop() else {
+            return Ok(None);
+        };
+        check_gene_states(genome, &edit.after)?;
+        set_gene_states(genome, &edit.before);
+        for &(currency, amount) in &edit.cost {
+            pools.modify(currency, amount);
+        }
+        let command = edit.command;
+        self.redo.push(edit);
+        Ok(Some(command))
+    }
+
+    /// Re-apply the most recently undone edit, paying its cost again.
+    ///
+    /// Returns the redone command, or `None` if there is nothing to redo. An edit that can
+    /// no longer be paid for stays on the redo stack; one whose genes have changed is dropped.
+    pub fn redo(
+        &mut self,
+        genome: &mut Genome,
+        pools: &mut CurrencyPools,
+    ) -> Result<Option<GenomeCommand>, GenomeCommandError> {
+        let Some(edit) = self.redo.last() else {
+            return Ok(None);
+        };
+        if let Err(err) = check_gene_states(genome, &edit.before) {
+            self.redo.pop();
+            return Err(err);
+        }
+        charge(pools, &edit.cost)?;
+        set_gene_states(genome, &edit.after);
+        let edit = self.redo.pop().expect("checked above");
+        let command = edit.command;
+        self.undo.push(edit);
+        Ok(Some(command))
+    }
+}
+
+/// Validate `command`, pay for it and apply it to `genome`.
+pub fn execute_genome_command(
+    genome: &mut Genome,
+    pools: &mut CurrencyPools,
+    costs: &GenomeOperationCosts,
+    command: GenomeCommand,
+) -> Result<GenomeEdit, GenomeCommandError> {
+    let after = command.plan(genome)?;
+    let cost = costs.cost_of(&command);
+    charge(pools, &cost)?;
+
+    let before = after
+        .iter()
+        .map(|(kind, _)| (*kind, genome.get_gene_state(kind).cloned()))
+        .collect();
+    set_gene_states(genome, &after);
+    Ok(GenomeEdit {
+        command,
+        before,
+        after,
+        cost,
+    })
+}
+
+fn check_gene_states(genome: &Genome, expected: &GeneStates) -> Result<(), GenomeCommandError> {
+    for (kind, state) in expected {
+        if genome.get_gene_state(kind) != state.as_ref() {
+            return Err(GenomeCommandError::Conflict(*kind));
+        }
+    }
+    Ok(())
+}
+
+fn set_gene_states(genome: &mut Genome, states: &GeneStates) {
+    for (kind, state) in states {
+        match state {
+            Some(state) => {
+                genome.table.insert(*kind, state.clone());
+            }
+            None => {
+                genome.remove_gene(*kind);
+            }
+        }
+    }
+}
+
+/// Deduct every cost, or nothing if any of them cannot be paid.
+fn charge(pools: &mut CurrencyPools, cost: &[(Currency, f32)]) -> Result<(), GenomeCommandError> {
+    for &(currency, amount) in cost {
+        if !pools.can_consume(currency, amount) {
+            return Err(GenomeCommandError::InsufficientCurrency {
+                currency,
+                required: amount,
+                available: pools.get(currency),
+            });
+        }
+    }
+    for &(currency, amount) in cost {
+        pools.modify(currency, -amount);
+    }
+    Ok(())
+}
+
+/// Event sent by [`mutation_system`] for every gene it changes.
+#[derive(Event, Debug, Clone, PartialEq)]
+pub struct GeneMutationEvent {
+    pub block_kind: BlockKind,
+    pub target: GeneState,
+}
+
 /// A differential summary of genome changes
 #[derive(Debug)]
 pub struct GenomeDiff {
@@ -346,6 +862,26 @@ pub trait MutationStrategy: Send + Sync {
     fn get_mutation_target(&mut self, block_kind: BlockKind) -> GeneState;
 }
 
+impl GenomeOperationCosts {
+    /// Currencies charged for `command`.
+    ///
+    /// Expression only charges ATP until the cell has a nucleotide pool to draw
+    /// `expression_nucleotide_cost` from. Silencing is free.
+    pub fn cost_of(&self, command: &GenomeCommand) -> Vec<(Currency, f32)> {
+        match command {
+            GenomeCommand::Express(_) => vec![(Currency::ATP, self.expression_atp_cost)],
+            GenomeCommand::Silence(_) => Vec::new(),
+            GenomeCommand::Add(_)
+            | GenomeCommand::Remove(_)
+            | GenomeCommand::Repair(_)
+            | GenomeCommand::Swap(..) => vec![
+                (Currency::ATP, self.editing_atp_cost),
+                (Currency::ReducingPower, self.editing_reducing_power_cost),
+            ],
+        }
+    }
+}
+
 impl Default for GenomeOperationCosts {
     fn default() -> Self {
         Self {
@@ -401,6 +937,8 @@ impl MutationStrategy for DeterministicMutationStrategy {
 #[derive(Resource)]
 pub struct MutationConfig {
     pub strategy: Box<dyn MutationStrategy>,
+    /// Scales the time each gene is exposed to its strategy; raised by toxic stress
+    pub rate_multiplier: f32,
 }
 
 impl MutationConfig {
@@ -408,6 +946,7 @@ impl MutationConfig {
     pub fn random() -> Self {
         Self {
             strategy: Box::new(RandomMutationStrategy::default()),
+            rate_multiplier: 1.0,
         }
     }
     
@@ -415,6 +954,7 @@ impl MutationConfig {
     pub fn deterministic() -> Self {
         Self {
             strategy: Box::new(DeterministicMutationStrategy),
+            rate_multiplier: 1.0,
         }
     }
 }
@@ -430,23 +970,32 @@ pub struct GenomePlugin;
 
 impl Plugin for GenomePlugin {
     fn build(&self, app: &mut App) {
+        CellsPlugin::add_to(app);
+        // Moved onto the player's cell at startup
         app.insert_resource(Genome::default())
             .insert_resource(GenomeOperationCosts::default())
             .insert_resource(MutationConfig::default())
+            .init_resource::<GenomeHistory>()
             .add_event::<GenomeDiffEvent>()
             .add_event::<MetabolicUpdateEvent>()
+            .add_event::<GenomeCommand>()
+            .add_event::<GenomeHistoryCommand>()
+            .add_event::<GeneMutationEvent>()
             .add_systems(PreUpdate, poll_genome_diff)
-            .add_systems(Update, apply_genome_diff)
-            .add_systems(PostUpdate, mutation_system);
+            .add_systems(Update, (apply_genome_commands, apply_genome_diff).chain())
+            .add_systems(CellSchedule, mutation_system.in_set(MetabolicSet::Blocks));
     }
 }
 
-/// System that compares current vs. previous genome snapshot and emits only the delta
+/// System that compares the player's current vs. previous genome snapshot and emits only the delta
 pub fn poll_genome_diff(
-    mut genome: ResMut<Genome>, 
+    mut genomes: Query<&mut Genome, With<PlayerCell>>,
     mut diff_writer: EventWriter<GenomeDiffEvent>,
     mut metabolic_diff_writer: EventWriter<MetabolicUpdateEvent>
 ) {
+    let Ok(mut genome) = genomes.get_single_mut() else {
+        return;
+    };
     let diff = genome.compute_diff();
 
     // Send expression change events (for existing systems)
@@ -486,30 +1035,81 @@ pub fn apply_genome_diff(
     }
 }
 
-/// System that applies mutations according to the configured strategy
+/// System that validates, pays for and applies queued [`GenomeCommand`]s to the player's
+/// cell, recording each in [`GenomeHistory`], then handles undo/redo requests.
+pub fn apply_genome_commands(
+    mut command_reader: EventReader<GenomeCommand>,
+    mut history_reader: EventReader<GenomeHistoryCommand>,
+    mut player: Query<(&mut Genome, &mut CurrencyPools), With<PlayerCell>>,
+    mut history: ResMut<GenomeHistory>,
+    costs: Res<GenomeOperationCosts>,
+) {
+    let Ok((mut genome, mut pools)) = player.get_single_mut() else {
+        return;
+    };
+    for command in command_reader.read() {
+        match execute_genome_command(&mut genome, &mut pools, &costs, *command) {
+            Ok(edit) => {
+                info!("Applied {:?}", edit.command);
+                history.record(edit);
+            }
+            Err(err) => warn!("Rejected {:?}: {}", command, err),
+        }
+    }
+
+    for request in history_reader.read() {
+        let result = match request {
+            GenomeHistoryCommand::Undo => history.undo(&mut genome, &mut pools),
+            GenomeHistoryCommand::Redo => history.redo(&mut genome, &mut pools),
+        };
+        match result {
+            Ok(Some(command)) => info!("{:?}: {:?}", request, command),
+            Ok(None) => info!("Nothing to {:?}", request),
+            Err(err) => warn!("Cannot {:?}: {}", request, err),
+        }
+    }
+}
+
+/// Move a gene to the state chosen by a mutation strategy.
+pub fn apply_mutation(genome: &mut Genome, block_kind: BlockKind, target: &GeneState) -> bool {
+    match target {
+        GeneState::Mutated => {
+            warn!("Gene {:?} has mutated!", block_kind);
+            genome.mutate_gene(block_kind)
+        }
+        GeneState::Silent => {
+            warn!("Gene {:?} has been silenced!", block_kind);
+            genome.silence_gene(block_kind)
+        }
+        GeneState::Expressed => {
+            warn!("Gene {:?} has been expressed!", block_kind);
+            genome.express_gene(block_kind)
+        }
+    }
+}
+
+/// System that applies mutations according to the configured strategy.
+/// Runs on the metabolic tick, so `delta_time` is the fixed tick length scaled by
+/// [`MutationConfig::rate_multiplier`]. Only the player's mutations are sent as
+/// [`GeneMutationEvent`]s.
 pub fn mutation_system(
     mut genome: ResMut<Genome>, 
     mut mutation_config: ResMut<MutationConfig>,
-    time: Res<Time>
+    mut mutation_writer: EventWriter<GeneMutationEvent>,
+    time: Res<Time>,
+    scope: CellScope,
 ) {
-    let delta_time = time.delta_secs();
+    let delta_time = time.delta_secs() * mutation_config.rate_multiplier;
 
     for (block_kind, _state) in genome.table.clone().iter() {
         if mutation_config.strategy.should_mutate(*block_kind, delta_time) {
-            let target_state = mutation_config.strategy.get_mutation_target(*block_kind);
-            match target_state {
-                GeneState::Mutated => {
-                    genome.mutate_gene(*block_kind);
-                    warn!("Gene {:?} has mutated!", block_kind);
-                }
-                GeneState::Silent => {
-                    genome.silence_gene(*block_kind);
-                    warn!("Gene {:?} has been silenced!", block_kind);
-                }
-                GeneState::Expressed => {
-                    genome.express_gene(*block_kind);
-                    warn!("Gene {:?} has been expressed!", block_kind);
-                }
+            let target = mutation_config.strategy.get_mutation_target(*block_kind);
+            apply_mutation(&mut genome, *block_kind, &target);
+            if scope.cell().is_none() {
+                mutation_writer.send(GeneMutationEvent {
+                    block_kind: *block_kind,
+                    target,
+                });
             }
         }
     }
@@ -523,6 +1123,9 @@ pub fn create_starter_genome() -> Genome {
     genome.add_gene(BlockKind::SugarCatabolism);
     genome.add_gene(BlockKind::Fermentation);
     genome.add_gene(BlockKind::AminoAcidBiosynthesis);
+    // Cells start out able to move
+    genome.add_gene(BlockKind::Motility);
+    genome.express_gene(BlockKind::Motility);
 
     genome
 }
@@ -1,4 +1,5 @@
 pub mod genome;
 pub mod fermentation;
 pub mod fat_storage;
+pub mod motility;
 pub mod vesicle_export;
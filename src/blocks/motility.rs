@@ -0,0 +1,68 @@
+//! # Motility
+//!
+//! The flagellum is a gene like any metabolic block: unless [`BlockKind::Motility`] is
+//! expressed, the cell cannot roll or jump. Its power is the gene's expression level
+//! scaled by how much ATP the cell has, up to [`MotilityRules::full_power_atp`], and every
+//! movement is paid for out of [`CurrencyPools`]: rolling by the torque it applies (see
+//! [`CellPhysics`](crate::player::physics::CellPhysics)), jumping by a fixed fee.
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::blocks::genome::{BlockKind, Genome};
+use crate::metabolism::CurrencyPools;
+use crate::molecules::Currency;
+
+/// How the flagellum turns ATP into movement.
+#[derive(Resource, Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct MotilityRules {
+    /// ATP at which the flagellum reaches full power; below it power falls off linearly
+    pub full_power_atp: f32,
+    /// ATP a jump costs
+    pub jump_atp: f32,
+}
+
+impl Default for MotilityRules {
+    fn default() -> Self {
+        Self {
+            full_power_atp: 50.0,
+            jump_atp: 2.0,
+        }
+    }
+}
+
+impl MotilityRules {
+    /// Fraction of full power the flagellum of a cell with `genome` and `pools` has.
+    pub fn power(&self, genome: &Genome, pools: &CurrencyPools) -> f32 {
+        let expression = genome.expression_level(BlockKind::Motility);
+        if expression <= 0.0 {
+            return 0.0;
+        }
+        let supply = if self.full_power_atp > 0.0 {
+            (pools.get(Currency::ATP) / self.full_power_atp).clamp(0.0, 1.0)
+        } else {
+            1.0
+        };
+        expression * supply
+    }
+
+    /// Pay for a jump at `power`, returning the fraction of a full jump it buys; nothing is
+    /// paid and 0 returned if the cell cannot afford it.
+    pub fn pay_for_jump(&self, pools: &mut CurrencyPools, power: f32) -> f32 {
+        if power <= 0.0 || !pools.can_consume(Currency::ATP, self.jump_atp) {
+            return 0.0;
+        }
+        pools.modify(Currency::ATP, -self.jump_atp);
+        power
+    }
+}
+
+/// Plugin for the motility block.
+pub struct MotilityPlugin;
+
+impl Plugin for MotilityPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<MotilityRules>();
+    }
+}
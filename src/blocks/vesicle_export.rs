@@ -1,6 +1,8 @@
 use bevy::prelude::*;
+use crate::environment::{Nutrient, Surroundings};
 use crate::molecules::Currency;
-use crate::metabolism::CurrencyPools;
+use crate::metabolism::cells::{CellSchedule, CellsPlugin};
+use crate::metabolism::{CurrencyPools, MetabolicSet};
 
 #[derive(Component)]
 pub struct VesicleExportBlock;
@@ -12,9 +14,10 @@ pub struct VesicleExportPlugin;
 
 impl Plugin for VesicleExportPlugin {
     fn build(&self, app: &mut App) {
+        CellsPlugin::add_to(app);
         app.insert_resource(VesicleExportRate(0.1)) // Default export rate
             .add_systems(Startup, spawn_vesicle_export_block)
-            .add_systems(FixedUpdate, vesicle_export_system);
+            .add_systems(CellSchedule, vesicle_export_system.in_set(MetabolicSet::Blocks));
     }
 }
 
@@ -23,18 +26,25 @@ fn spawn_vesicle_export_block(mut commands: Commands) {
     println!("VesicleExportBlock spawned!");
 }
 
+/// Export organic waste out of the cell, into the environment around it.
 fn vesicle_export_system(
     export_rate: Res<VesicleExportRate>,
     mut currency_pools: ResMut<CurrencyPools>,
+    mut surroundings: Surroundings,
 ) {
     let amount_to_export = export_rate.0;
     let organic_waste = currency_pools.get(Currency::OrganicWaste);
 
-    if organic_waste >= amount_to_export {
+    let exported = if organic_waste >= amount_to_export {
         currency_pools.modify(Currency::OrganicWaste, -amount_to_export);
         // debug!("VesicleExport: Exported {:.2} OrganicWaste", amount_to_export);
+        amount_to_export
     } else {
         currency_pools.set(Currency::OrganicWaste, 0.0);
         // debug!("VesicleExport: Exported remaining {:.2} OrganicWaste", organic_waste);
+        organic_waste
+    };
+    if exported > 0.0 {
+        surroundings.secrete(Nutrient::OrganicWaste, exported);
     }
 }
@@ -1,7 +1,7 @@
 use crate::player::controller::Action; // Added for Action enum
 use crate::player::Player;
 use bevy::prelude::*;
-use leafwing_input_manager::prelude::*; // Added for ActionState
+use leafwing_input_manager::prelude::ActionState;
 use std::f32::consts::PI; // Import Player marker component
 
 // Resource to store the last known focus point for the camera
@@ -46,20 +46,24 @@ pub fn spawn_3d_camera(commands: &mut Commands) -> Entity {
             Camera3d::default(),
             Transform::from_xyz(0.0, 1.5, 4.0).looking_at(Vec3::ZERO, Vec3::Y),
             FollowCamera::default(),
-            InputManagerBundle::with_map(Action::input_map()),
         ))
         .id()
 }
 
-// System to handle camera panning based on mouse input
+// System to handle camera panning with the player's `Pan` action (mouse or right stick)
 fn pan_camera_input(
     player_query: Query<&GlobalTransform, With<Player>>, // Added player query
-    mut camera_query: Query<(&mut Transform, &FollowCamera, &ActionState<Action>), With<Camera3d>>,
+    actions: Query<&ActionState<Action>, With<Player>>,
+    mut camera_query: Query<(&mut Transform, &FollowCamera), With<Camera3d>>,
     last_focus: Res<LastCameraFocus>, // Get the resource
 ) {
     const CAMERA_ROTATE_RATE: f32 = 0.005;
 
-    for (mut transform, follow_camera, action_state) in camera_query.iter_mut() {
+    let Ok(action_state) = actions.get_single() else {
+        return;
+    };
+
+    for (mut transform, follow_camera) in camera_query.iter_mut() {
         let camera_pan_vector = action_state.axis_pair(&Action::Pan);
 
         if camera_pan_vector.length_squared() > 0.0 {
@@ -0,0 +1,199 @@
+//! # Currency Dashboard
+//!
+//! An egui panel listing every currency pool with its current amount, the net change over
+//! the last metabolic tick and a sparkline of its recent history, all read from
+//! [`MetricsHistory`]. Pools that are nearly empty, or free fatty acids above the
+//! [`LipidToxicityThreshold`], are drawn in a warning colour.
+//!
+//! The cell's [`CellVitality`] is shown as a badge next to the tick count, together with
+//! its [`MetabolicEfficiency`] once toxic damage has lowered it.
+//!
+//! [`GameAction::ToggleDashboard`] (`F2`) toggles the panel in every game state. It draws
+//! into the egui context set up by the inspector plugin, so it is only built with the
+//! `full` feature.
+
+use bevy::prelude::*;
+use bevy_egui::{egui, EguiContexts};
+use leafwing_input_manager::prelude::*;
+
+use crate::input::GameAction;
+use crate::metabolism::cells::PlayerCell;
+use crate::metabolism::toxicity::MetabolicEfficiency;
+use crate::metabolism::vitality::CellVitality;
+use crate::metabolism::CurrencyPools;
+use crate::metrics::{Metric, MetricsHistory};
+use crate::molecules::{Currency, LipidToxicityThreshold};
+
+const SPARKLINE_SIZE: egui::Vec2 = egui::vec2(160.0, 24.0);
+const LOW_COLOR: egui::Color32 = egui::Color32::from_rgb(230, 160, 40);
+const TOXIC_COLOR: egui::Color32 = egui::Color32::from_rgb(220, 50, 50);
+const NORMAL_COLOR: egui::Color32 = egui::Color32::from_rgb(90, 200, 120);
+
+/// What the dashboard shows and when it warns.
+#[derive(Resource, Debug, Clone)]
+pub struct DashboardSettings {
+    pub visible: bool,
+    /// Ticks of history drawn in each sparkline
+    pub sparkline_ticks: u64,
+    /// Pools at or below this amount are flagged as nearly empty
+    pub low_threshold: f32,
+}
+
+impl Default for DashboardSettings {
+    fn default() -> Self {
+        Self {
+            visible: false,
+            sparkline_ticks: 120,
+            low_threshold: 5.0,
+        }
+    }
+}
+
+/// Warning level of a single pool.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PoolStatus {
+    Normal,
+    Low,
+    Toxic,
+}
+
+/// Classify `amount` of `currency`. A toxicity threshold of zero or less counts as unset.
+pub fn pool_status(
+    currency: Currency,
+    amount: f32,
+    settings: &DashboardSettings,
+    toxicity_threshold: f32,
+) -> PoolStatus {
+    if currency == Currency::FreeFattyAcids
+        && toxicity_threshold > 0.0
+        && amount > toxicity_threshold
+    {
+        PoolStatus::Toxic
+    } else if amount <= settings.low_threshold {
+        PoolStatus::Low
+    } else {
+        PoolStatus::Normal
+    }
+}
+
+pub(crate) fn plugin(app: &mut App) {
+    app.init_resource::<DashboardSettings>().add_systems(
+        Update,
+        (toggle_dashboard, dashboard_ui.run_if(dashboard_visible)).chain(),
+    );
+}
+
+fn toggle_dashboard(actions: Res<ActionState<GameAction>>, mut settings: ResMut<DashboardSettings>) {
+    if actions.just_pressed(&GameAction::ToggleDashboard) {
+        settings.visible = !settings.visible;
+    }
+}
+
+fn dashboard_visible(settings: Res<DashboardSettings>) -> bool {
+    settings.visible
+}
+
+fn dashboard_ui(
+    mut contexts: EguiContexts,
+    settings: Res<DashboardSettings>,
+    pools: Query<&CurrencyPools, With<PlayerCell>>,
+    history: Res<MetricsHistory>,
+    toxicity: Res<LipidToxicityThreshold>,
+    vitality: Res<CellVitality>,
+    efficiency: Res<MetabolicEfficiency>,
+) {
+    let Ok(pools) = pools.get_single() else {
+        return;
+    };
+    let mut currencies: Vec<(Currency, f32)> = pools.pools.iter().map(|(&c, &v)| (c, v)).collect();
+    currencies.sort_by_key(|&(currency, _)| currency);
+
+    let latest_tick = history.latest().map(|sample| sample.tick).unwrap_or(0);
+    let window_start = latest_tick.saturating_sub(settings.sparkline_ticks);
+
+    egui::Window::new("Metabolism")
+        .resizable(false)
+        .show(contexts.ctx_mut(), |ui| {
+            ui.horizontal(|ui| {
+                ui.label(format!("Tick {}", latest_tick));
+                match *vitality {
+                    CellVitality::Active => ui.colored_label(NORMAL_COLOR, "active"),
+                    CellVitality::Stressed => ui.colored_label(LOW_COLOR, "stressed"),
+                    CellVitality::Dormant => ui.colored_label(LOW_COLOR, "dormant"),
+                    CellVitality::Dead => ui.colored_label(TOXIC_COLOR, "dead"),
+                };
+                if efficiency.0 < 1.0 {
+                    ui.colored_label(
+                        TOXIC_COLOR,
+                        format!("poisoned: {:.0}% efficiency", efficiency.0 * 100.0),
+                    );
+                }
+            });
+            egui::Grid::new("currency_dashboard")
+                .num_columns(4)
+                .striped(true)
+                .show(ui, |ui| {
+                    for (currency, amount) in currencies {
+                        let status = pool_status(currency, amount, &settings, toxicity.0);
+                        let color = match status {
+                            PoolStatus::Normal => ui.visuals().text_color(),
+                            PoolStatus::Low => LOW_COLOR,
+                            PoolStatus::Toxic => TOXIC_COLOR,
+                        };
+                        ui.colored_label(color, format!("{:?}", currency));
+                        ui.colored_label(color, format!("{:.1}", amount));
+
+                        let metric = Metric::Currency(currency);
+                        match history.delta(metric) {
+                            Some(delta) => ui.label(format!("{:+.2}/tick", delta)),
+                            None => ui.label("-"),
+                        };
+
+                        let series = history.series(metric, window_start..);
+                        let line_color = match status {
+                            PoolStatus::Normal => NORMAL_COLOR,
+                            _ => color,
+                        };
+                        sparkline(ui, &series, line_color);
+                        ui.end_row();
+                    }
+                });
+        });
+}
+
+/// Draw `series` scaled to fit a small fixed-size box.
+fn sparkline(ui: &mut egui::Ui, series: &[(u64, f32)], color: egui::Color32) {
+    let (rect, _) = ui.allocate_exact_size(SPARKLINE_SIZE, egui::Sense::hover());
+    let painter = ui.painter_at(rect);
+    painter.rect_stroke(
+        rect,
+        2.0,
+        ui.visuals().widgets.noninteractive.bg_stroke,
+        egui::StrokeKind::Inside,
+    );
+    if series.len() < 2 {
+        return;
+    }
+
+    let (first_tick, last_tick) = (series[0].0, series[series.len() - 1].0);
+    let (min, max) = series
+        .iter()
+        .fold((f32::INFINITY, f32::NEG_INFINITY), |(lo, hi), &(_, v)| (lo.min(v), hi.max(v)));
+    // A flat line sits in the middle instead of dividing by zero
+    let range = if max > min { max - min } else { 1.0 };
+    let span = (last_tick - first_tick).max(1) as f32;
+
+    let points = series
+        .iter()
+        .map(|&(tick, value)| {
+            let x = rect.left() + (tick - first_tick) as f32 / span * rect.width();
+            let y = if max > min {
+                rect.bottom() - (value - min) / range * rect.height()
+            } else {
+                rect.center().y
+            };
+            egui::pos2(x, y)
+        })
+        .collect();
+    painter.add(egui::Shape::line(points, egui::Stroke::new(1.5, color)));
+}
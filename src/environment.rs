@@ -0,0 +1,344 @@
+//! # Environment
+//!
+//! The world around the cells is a [`NutrientGrid`] laid over the ground: square patches,
+//! each holding an amount of every [`Nutrient`]. Once per metabolic tick the grid diffuses
+//! between neighbouring patches and every patch drifts back towards its baseline, then each
+//! cell in the scene absorbs from the patch under it into its [`CurrencyPools`], as set out
+//! by [`EnvironmentRules::uptake`]. Organic waste a cell exports through its vesicles is
+//! secreted into the patch it sits on.
+//!
+//! Only cells with a [`Transform`] take part, so where a cell goes decides what it can eat:
+//! a patch it has grazed bare only recovers slowly.
+
+use std::collections::BTreeMap;
+
+use bevy::ecs::system::SystemParam;
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::metabolism::cells::{simulate_cells, Cell, CellScope, PlayerCell};
+use crate::metabolism::CurrencyPools;
+use crate::molecules::Currency;
+
+/// Something that lies in the environment rather than inside a cell.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Nutrient {
+    Sugar,
+    Oxygen,
+    Ammonium,
+    Light,
+    /// Secreted by cells, not absorbed
+    OrganicWaste,
+}
+
+impl Nutrient {
+    /// Every nutrient, in declaration order.
+    pub const ALL: [Nutrient; 5] = [
+        Nutrient::Sugar,
+        Nutrient::Oxygen,
+        Nutrient::Ammonium,
+        Nutrient::Light,
+        Nutrient::OrganicWaste,
+    ];
+
+    /// Number of nutrients.
+    pub const COUNT: usize = Self::ALL.len();
+
+    /// Position of this nutrient in [`Nutrient::ALL`].
+    pub fn index(self) -> usize {
+        self as usize
+    }
+}
+
+/// Amount of every nutrient, indexed by [`Nutrient::index`].
+pub type NutrientLevels = [f32; Nutrient::COUNT];
+
+/// How a cell absorbs one nutrient.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Uptake {
+    /// The pool the absorbed nutrient ends up in
+    pub currency: Currency,
+    /// Most absorbed per tick
+    pub rate: f32,
+}
+
+/// How the environment evolves and what cells take from it.
+#[derive(Resource, Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct EnvironmentRules {
+    /// Fraction of the difference to each neighbouring patch that flows across per tick;
+    /// at most 0.25
+    pub diffusion: f32,
+    /// Fraction of the way back to its baseline a patch goes per tick
+    pub replenishment: f32,
+    /// Level every patch of a new grid starts at and returns to
+    pub baseline: BTreeMap<Nutrient, f32>,
+    /// Nutrients cells absorb; the metabolism has no currency of its own for most of
+    /// them yet, so each feeds the closest one
+    pub uptake: BTreeMap<Nutrient, Uptake>,
+}
+
+impl Default for EnvironmentRules {
+    fn default() -> Self {
+        Self {
+            diffusion: 0.1,
+            replenishment: 0.01,
+            baseline: BTreeMap::from([
+                (Nutrient::Sugar, 20.0),
+                (Nutrient::Oxygen, 20.0),
+                (Nutrient::Ammonium, 10.0),
+                (Nutrient::Light, 10.0),
+            ]),
+            uptake: BTreeMap::from([
+                (Nutrient::Sugar, Uptake { currency: Currency::Pyruvate, rate: 1.0 }),
+                (Nutrient::Oxygen, Uptake { currency: Currency::ATP, rate: 0.5 }),
+                (Nutrient::Ammonium, Uptake { currency: Currency::CarbonSkeletons, rate: 0.5 }),
+                (Nutrient::Light, Uptake { currency: Currency::ReducingPower, rate: 0.5 }),
+            ]),
+        }
+    }
+}
+
+impl EnvironmentRules {
+    /// The baseline of every nutrient.
+    pub fn baseline_levels(&self) -> NutrientLevels {
+        let mut levels = [0.0; Nutrient::COUNT];
+        for (&nutrient, &amount) in &self.baseline {
+            levels[nutrient.index()] = amount;
+        }
+        levels
+    }
+}
+
+/// Nutrient levels over a rectangle of the ground (the XZ plane), in square patches.
+#[derive(Resource, Debug, Clone, PartialEq)]
+pub struct NutrientGrid {
+    /// World position of the corner with the lowest X and Z
+    pub origin: Vec2,
+    pub patch_size: f32,
+    pub width: usize,
+    pub depth: usize,
+    levels: Vec<NutrientLevels>,
+    baseline: Vec<NutrientLevels>,
+}
+
+impl FromWorld for NutrientGrid {
+    /// A grid of 10-unit patches over the 500x500 floor of the 3D scene, at the baseline
+    /// of the [`EnvironmentRules`].
+    fn from_world(world: &mut World) -> Self {
+        let baseline = world
+            .get_resource::<EnvironmentRules>()
+            .cloned()
+            .unwrap_or_default()
+            .baseline_levels();
+        Self::new(Vec2::splat(-250.0), 10.0, 50, 50, baseline)
+    }
+}
+
+impl NutrientGrid {
+    /// A `width` by `depth` grid with every patch at `baseline`.
+    pub fn new(origin: Vec2, patch_size: f32, width: usize, depth: usize, baseline: NutrientLevels) -> Self {
+        Self {
+            origin,
+            patch_size,
+            width,
+            depth,
+            levels: vec![baseline; width * depth],
+            baseline: vec![baseline; width * depth],
+        }
+    }
+
+    /// Index of the patch under `position`, if it is over the grid.
+    pub fn patch_at(&self, position: Vec3) -> Option<usize> {
+        let offset = (position.xz() - self.origin) / self.patch_size;
+        if offset.x < 0.0 || offset.y < 0.0 {
+            return None;
+        }
+        let (x, z) = (offset.x as usize, offset.y as usize);
+        (x < self.width && z < self.depth).then(|| z * self.width + x)
+    }
+
+    /// Centre of a patch, at ground level.
+    pub fn patch_center(&self, patch: usize) -> Vec3 {
+        let (x, z) = (patch % self.width, patch / self.width);
+        let center = self.origin + (Vec2::new(x as f32, z as f32) + 0.5) * self.patch_size;
+        Vec3::new(center.x, 0.0, center.y)
+    }
+
+    /// A grid with the given levels and baselines of every patch, row by row; `None` unless
+    /// both have `width * depth` patches.
+    pub fn from_patches(
+        origin: Vec2,
+        patch_size: f32,
+        width: usize,
+        depth: usize,
+        levels: Vec<NutrientLevels>,
+        baseline: Vec<NutrientLevels>,
+    ) -> Option<Self> {
+        let patches = width * depth;
+        (levels.len() == patches && baseline.len() == patches).then_some(Self {
+            origin,
+            patch_size,
+            width,
+            depth,
+            levels,
+            baseline,
+        })
+    }
+
+    /// Levels of every patch, row by row.
+    pub fn levels(&self) -> &[NutrientLevels] {
+        &self.levels
+    }
+
+    /// Baselines of every patch, row by row.
+    pub fn baselines(&self) -> &[NutrientLevels] {
+        &self.baseline
+    }
+
+    pub fn patch_count(&self) -> usize {
+        self.levels.len()
+    }
+
+    pub fn get(&self, patch: usize, nutrient: Nutrient) -> f32 {
+        self.levels[patch][nutrient.index()]
+    }
+
+    pub fn set(&mut self, patch: usize, nutrient: Nutrient, amount: f32) {
+        self.levels[patch][nutrient.index()] = amount.max(0.0);
+    }
+
+    /// Add `amount` of a nutrient to a patch.
+    pub fn add(&mut self, patch: usize, nutrient: Nutrient, amount: f32) {
+        let current = self.get(patch, nutrient);
+        self.set(patch, nutrient, current + amount);
+    }
+
+    /// Take up to `amount` of a nutrient from a patch, returning what was taken.
+    pub fn take(&mut self, patch: usize, nutrient: Nutrient, amount: f32) -> f32 {
+        let taken = amount.clamp(0.0, self.get(patch, nutrient));
+        self.add(patch, nutrient, -taken);
+        taken
+    }
+
+    /// Level a patch returns to.
+    pub fn baseline(&self, patch: usize, nutrient: Nutrient) -> f32 {
+        self.baseline[patch][nutrient.index()]
+    }
+
+    pub fn set_baseline(&mut self, patch: usize, nutrient: Nutrient, amount: f32) {
+        self.baseline[patch][nutrient.index()] = amount.max(0.0);
+    }
+
+    /// Total amount of a nutrient over the whole grid.
+    pub fn total(&self, nutrient: Nutrient) -> f32 {
+        self.levels.iter().map(|levels| levels[nutrient.index()]).sum()
+    }
+
+    /// Exchange a fraction of the difference between every pair of neighbouring patches.
+    /// Nothing is lost at the edges, so the total of every nutrient is kept.
+    pub fn diffuse(&mut self, rate: f32) {
+        let rate = rate.clamp(0.0, 0.25);
+        if rate == 0.0 {
+            return;
+        }
+        let mut next = self.levels.clone();
+        for z in 0..self.depth {
+            for x in 0..self.width {
+                let patch = z * self.width + x;
+                // Every pair once: with the neighbour to the right and the one behind
+                let neighbours = [
+                    (x + 1 < self.width).then(|| patch + 1),
+                    (z + 1 < self.depth).then(|| patch + self.width),
+                ];
+                for neighbour in neighbours.into_iter().flatten() {
+                    for nutrient in Nutrient::ALL.map(Nutrient::index) {
+                        let flow = (self.levels[patch][nutrient] - self.levels[neighbour][nutrient]) * rate;
+                        next[patch][nutrient] -= flow;
+                        next[neighbour][nutrient] += flow;
+                    }
+                }
+            }
+        }
+        self.levels = next;
+    }
+
+    /// Move every patch `rate` of the way back to its baseline.
+    pub fn replenish(&mut self, rate: f32) {
+        let rate = rate.clamp(0.0, 1.0);
+        for (levels, baseline) in self.levels.iter_mut().zip(&self.baseline) {
+            for (level, target) in levels.iter_mut().zip(baseline) {
+                *level += (target - *level) * rate;
+            }
+        }
+    }
+}
+
+/// The patch of the [`NutrientGrid`] under the cell being simulated.
+#[derive(SystemParam)]
+pub struct Surroundings<'w, 's> {
+    grid: Option<ResMut<'w, NutrientGrid>>,
+    cells: Query<'w, 's, (Entity, &'static Transform, Has<PlayerCell>), With<Cell>>,
+    scope: CellScope<'w>,
+}
+
+impl Surroundings<'_, '_> {
+    /// Add `amount` of a nutrient to the patch under the cell, if it is over the grid.
+    pub fn secrete(&mut self, nutrient: Nutrient, amount: f32) {
+        let cell = self.scope.cell();
+        let position = self
+            .cells
+            .iter()
+            .find(|&(entity, _, player)| cell.map_or(player, |cell| cell == entity))
+            .map(|(_, transform, _)| transform.translation);
+        let Some(grid) = self.grid.as_mut() else {
+            return;
+        };
+        if let Some(patch) = position.and_then(|position| grid.patch_at(position)) {
+            grid.add(patch, nutrient, amount);
+        }
+    }
+}
+
+// --- Systems ---
+
+/// Diffuse and replenish the grid, once per metabolic tick.
+pub fn update_environment(mut grid: ResMut<NutrientGrid>, rules: Res<EnvironmentRules>) {
+    grid.diffuse(rules.diffusion);
+    grid.replenish(rules.replenishment);
+}
+
+/// Let every cell in the scene absorb from the patch under it.
+pub fn absorb_nutrients(
+    mut grid: ResMut<NutrientGrid>,
+    rules: Res<EnvironmentRules>,
+    mut cells: Query<(&Transform, &mut CurrencyPools), With<Cell>>,
+) {
+    for (transform, mut pools) in &mut cells {
+        let Some(patch) = grid.patch_at(transform.translation) else {
+            continue;
+        };
+        for (&nutrient, uptake) in &rules.uptake {
+            let taken = grid.take(patch, nutrient, uptake.rate);
+            if taken > 0.0 {
+                pools.modify(uptake.currency, taken);
+            }
+        }
+    }
+}
+
+// --- Plugin ---
+
+pub struct EnvironmentPlugin;
+
+impl Plugin for EnvironmentPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<EnvironmentRules>()
+            .init_resource::<NutrientGrid>()
+            .add_systems(
+                FixedUpdate,
+                (update_environment, absorb_nutrients).chain().before(simulate_cells),
+            );
+    }
+}
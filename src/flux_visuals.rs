@@ -0,0 +1,254 @@
+//! # Flux Visuals
+//!
+//! Draws the metabolic graph inside the 2D and 3D scenes. Every [`MetabolicNode`] gets a
+//! glowing sphere coloured by its [`BlockStatus`] as in `flowmap_design.md`: green when
+//! active, amber banded with a darker amber when mutated, grey when silent. The glow and
+//! size of each sphere follow the node's share of the largest flux in [`FluxResult`].
+//!
+//! Dependencies from the [`MetabolicGraph`] are drawn as gizmo lines whose brightness
+//! pulses along the edge at a speed set by the producer's flux; edges with an inactive
+//! endpoint are dashed. Everything here only reads the solver's output, once per frame.
+
+use std::collections::HashMap;
+use std::f32::consts::TAU;
+
+use bevy::prelude::*;
+
+use crate::metabolism::cells::CellMember;
+use crate::metabolism::{BlockStatus, FluxResult, MetabolicGraph, MetabolicNode};
+use crate::GameState;
+
+const ACTIVE_COLOR: Color = Color::srgb(0.25, 0.8, 0.3);
+const MUTATED_COLOR: Color = Color::srgb(0.95, 0.65, 0.1);
+const MUTATED_STRIPE_COLOR: Color = Color::srgb(0.55, 0.35, 0.05);
+const SILENT_COLOR: Color = Color::srgb(0.45, 0.45, 0.45);
+
+/// How the metabolic graph is drawn in the scenes.
+#[derive(Resource, Debug, Clone)]
+pub struct FluxVisualSettings {
+    /// Centre of the ring the nodes are laid out on
+    pub center: Vec3,
+    /// Radius of that ring
+    pub ring_radius: f32,
+    /// Sphere radius of a node with no flux
+    pub node_radius: f32,
+    /// Emissive strength of the node with the largest flux
+    pub max_glow: f32,
+    /// Mutated nodes alternate stripe colours this many times per second
+    pub stripe_hz: f32,
+}
+
+impl Default for FluxVisualSettings {
+    fn default() -> Self {
+        Self {
+            center: Vec3::new(0.0, 1.5, -6.0),
+            ring_radius: 4.0,
+            node_radius: 0.4,
+            max_glow: 8.0,
+            stripe_hz: 2.0,
+        }
+    }
+}
+
+/// The sphere drawn for a metabolic node.
+#[derive(Component, Debug, Clone, Copy)]
+pub struct NodeVisual {
+    pub node: Entity,
+}
+
+/// Put on a metabolic node once its [`NodeVisual`] has been spawned.
+#[derive(Component, Debug, Clone, Copy)]
+pub struct HasNodeVisual(pub Entity);
+
+/// Base colour of a node with `status` at `elapsed_secs`.
+pub fn status_color(status: BlockStatus, elapsed_secs: f32, stripe_hz: f32) -> Color {
+    match status {
+        BlockStatus::Active => ACTIVE_COLOR,
+        BlockStatus::Mutated => {
+            if (elapsed_secs * stripe_hz).fract() < 0.5 {
+                MUTATED_COLOR
+            } else {
+                MUTATED_STRIPE_COLOR
+            }
+        }
+        BlockStatus::Silent => SILENT_COLOR,
+    }
+}
+
+/// `flux` as a fraction of `peak_flux`, in `0.0..=1.0`; zero when nothing flows.
+pub fn glow_strength(flux: f32, peak_flux: f32) -> f32 {
+    if peak_flux <= f32::EPSILON {
+        return 0.0;
+    }
+    (flux.abs() / peak_flux).clamp(0.0, 1.0)
+}
+
+pub(crate) fn plugin(app: &mut App) {
+    let in_scene = in_state(GameState::Scene2D).or(in_state(GameState::Scene3D));
+    app.init_resource::<FluxVisualSettings>()
+        .add_systems(
+            Update,
+            (spawn_node_visuals, update_node_visuals, draw_flux_edges)
+                .chain()
+                .run_if(in_scene),
+        )
+        .add_systems(OnExit(GameState::Scene2D), despawn_node_visuals)
+        .add_systems(OnExit(GameState::Scene3D), despawn_node_visuals);
+}
+
+/// Nodes of the player's cell that have no sphere yet.
+type PlayerNodesWithoutVisual = (With<MetabolicNode>, Without<HasNodeVisual>, Without<CellMember>);
+
+/// Spawn a sphere for every node of the player's cell that has none, and drop spheres whose
+/// node is gone.
+pub fn spawn_node_visuals(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    settings: Res<FluxVisualSettings>,
+    new_nodes: Query<Entity, PlayerNodesWithoutVisual>,
+    nodes: Query<(), With<MetabolicNode>>,
+    visuals: Query<(Entity, &NodeVisual)>,
+) {
+    for (entity, visual) in &visuals {
+        if nodes.get(visual.node).is_err() {
+            commands.entity(entity).despawn_recursive();
+        }
+    }
+
+    for node in &new_nodes {
+        let visual = commands
+            .spawn((
+                Mesh3d(meshes.add(Sphere::new(settings.node_radius).mesh().uv(24, 16))),
+                MeshMaterial3d(materials.add(StandardMaterial {
+                    base_color: SILENT_COLOR,
+                    ..default()
+                })),
+                Transform::from_translation(settings.center),
+                NodeVisual { node },
+                Name::new("Node Visual"),
+            ))
+            .id();
+        commands.entity(node).insert(HasNodeVisual(visual));
+    }
+}
+
+/// Place, colour and light every node sphere from the latest flux.
+pub fn update_node_visuals(
+    time: Res<Time>,
+    settings: Res<FluxVisualSettings>,
+    flux_result: Res<FluxResult>,
+    nodes: Query<&MetabolicNode>,
+    mut visuals: Query<(&NodeVisual, &mut Transform, &MeshMaterial3d<StandardMaterial>)>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+) {
+    let peak = peak_flux(&flux_result);
+    let positions = ring_layout(&settings, &nodes, visuals.iter().map(|(v, ..)| v.node));
+
+    for (visual, mut transform, material) in &mut visuals {
+        let Ok(node) = nodes.get(visual.node) else {
+            continue;
+        };
+        let flux = flux_result.entity_flux.get(&visual.node).copied().unwrap_or(0.0);
+        let glow = glow_strength(flux, peak);
+
+        if let Some(&position) = positions.get(&visual.node) {
+            transform.translation = position;
+        }
+        transform.scale = Vec3::splat(1.0 + 0.5 * glow);
+
+        if let Some(material) = materials.get_mut(&material.0) {
+            let color = status_color(node.status, time.elapsed_secs(), settings.stripe_hz);
+            material.base_color = color;
+            material.emissive = LinearRgba::from(color) * (glow * settings.max_glow);
+        }
+    }
+}
+
+/// Draw each dependency as a line from producer to consumer with a pulse running along it.
+pub fn draw_flux_edges(
+    mut gizmos: Gizmos,
+    time: Res<Time>,
+    graph: Res<MetabolicGraph>,
+    flux_result: Res<FluxResult>,
+    nodes: Query<(&MetabolicNode, Option<&HasNodeVisual>)>,
+    visuals: Query<&Transform, With<NodeVisual>>,
+) {
+    let peak = peak_flux(&flux_result);
+    let endpoint = |entity: Entity| {
+        let (node, visual) = nodes.get(entity).ok()?;
+        let transform = visuals.get(visual?.0).ok()?;
+        Some((node.status, transform.translation))
+    };
+
+    for (consumer, producer) in graph.links() {
+        let (Some((consumer_status, end)), Some((producer_status, start))) =
+            (endpoint(consumer), endpoint(producer))
+        else {
+            continue;
+        };
+        let flux = flux_result.entity_flux.get(&producer).copied().unwrap_or(0.0);
+        let glow = glow_strength(flux, peak);
+        let dashed = producer_status != BlockStatus::Active
+            || consumer_status != BlockStatus::Active;
+
+        // Dashes (or fine segments for solid lines) let the pulse travel along the edge
+        let segments = 12;
+        let phase = time.elapsed_secs() * (0.2 + glow);
+        for i in 0..segments {
+            if dashed && i % 2 == 1 {
+                continue;
+            }
+            let t0 = i as f32 / segments as f32;
+            let t1 = (i + 1) as f32 / segments as f32;
+            let pulse = 0.5 + 0.5 * ((t0 - phase) * TAU).cos();
+            let brightness = 0.25 + 0.75 * glow * pulse;
+            let color = Color::srgb(0.3 * brightness, 0.8 * brightness, brightness);
+            gizmos.line(start.lerp(end, t0), start.lerp(end, t1), color);
+        }
+    }
+}
+
+/// Remove every node sphere when leaving a scene that shows them.
+pub fn despawn_node_visuals(
+    mut commands: Commands,
+    visuals: Query<Entity, With<NodeVisual>>,
+    nodes: Query<Entity, With<HasNodeVisual>>,
+) {
+    for entity in &visuals {
+        commands.entity(entity).despawn_recursive();
+    }
+    for entity in &nodes {
+        commands.entity(entity).remove::<HasNodeVisual>();
+    }
+}
+
+fn peak_flux(flux_result: &FluxResult) -> f32 {
+    flux_result
+        .entity_flux
+        .values()
+        .fold(0.0, |peak: f32, flux| peak.max(flux.abs()))
+}
+
+/// Evenly spaced positions on the settings' ring, in block kind order.
+fn ring_layout(
+    settings: &FluxVisualSettings,
+    nodes: &Query<&MetabolicNode>,
+    shown: impl Iterator<Item = Entity>,
+) -> HashMap<Entity, Vec3> {
+    let mut ordered: Vec<(_, Entity)> = shown
+        .filter_map(|entity| nodes.get(entity).ok().map(|node| (node.kind, entity)))
+        .collect();
+    ordered.sort();
+
+    let count = ordered.len().max(1) as f32;
+    ordered
+        .into_iter()
+        .enumerate()
+        .map(|(index, (_, entity))| {
+            let angle = index as f32 / count * TAU;
+            let offset = Vec3::new(angle.cos(), 0.0, angle.sin()) * settings.ring_radius;
+            (entity, settings.center + offset)
+        })
+        .collect()
+}
@@ -0,0 +1,342 @@
+//! # Input Bindings
+//!
+//! Every player-facing input is a leafwing action with keyboard and gamepad defaults:
+//! rolling, jumping and the camera are [`Action`]s on the player's cell, while scene
+//! switching, genome commands, the flow editor, replays, the 2D scene and the inspector and
+//! dashboard toggles are [`GameAction`]s kept in a global [`ActionState`] resource, so they
+//! work in every scene.
+//!
+//! Both maps live in [`InputBindings`], which is read from [`DEFAULT_BINDINGS_PATH`] at
+//! startup (and written there with the defaults if the file does not exist yet). To rebind
+//! an action, edit that file, or change the resource in game: the new bindings are applied
+//! to the player and saved back to disk. Actions a settings file leaves out keep their
+//! defaults, so older files pick up actions added since.
+//!
+//! ## Default gamepad layout
+//!
+//! | Action | Gamepad |
+//! |---|---|
+//! | Move / look, and move in the 2D scene | Left / right stick |
+//! | Jump | South |
+//! | 3D scene / 2D scene / genome editor / flow editor | Select + D-pad up / down / left / right |
+//! | Main menu | Start |
+//! | Demo genome commands | Left bumper + North / East / West / South |
+//! | Undo / redo | Left bumper + D-pad left / right |
+//! | Genome editor: previous / next gene | D-pad left / right |
+//! | Genome editor: toggle / repair gene | South / West |
+//! | Flow editor: apply / revert edits | South / East |
+//! | Record / play back a replay | Select + West / East |
+//! | Inspector / dashboard | Right / left stick click |
+
+use std::path::{Path, PathBuf};
+
+use bevy::prelude::*;
+use leafwing_input_manager::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::player::controller::Action;
+
+/// Where the game reads and saves the player's input bindings.
+pub const DEFAULT_BINDINGS_PATH: &str = "settings/input.json";
+
+/// Actions that are not tied to the player's cell.
+#[derive(Actionlike, Clone, Debug, Copy, PartialEq, Eq, Hash, Reflect, Serialize, Deserialize)]
+pub enum GameAction {
+    Scene3D,
+    Scene2D,
+    GenomeEditor,
+    FlowEditor,
+    MainMenu,
+    /// Demo command: express sugar catabolism
+    ExpressSugarCatabolism,
+    /// Demo command: silence fermentation
+    SilenceFermentation,
+    /// Demo command: add the light capture gene
+    AddLightCapture,
+    /// Demo command: spawn a respiration block
+    SpawnRespiration,
+    Undo,
+    Redo,
+    PreviousGene,
+    NextGene,
+    /// Express the selected gene, or silence it if it is expressed
+    ToggleGene,
+    RepairGene,
+    ToggleInspector,
+    ToggleDashboard,
+    /// Start recording a replay, or stop and save it
+    ToggleRecording,
+    PlayReplay,
+    /// Flow editor: apply the pending edits
+    ApplyFlowEdit,
+    /// Flow editor: discard the pending edits
+    RevertFlowEdit,
+    /// Move the cell in the 2D scene
+    #[actionlike(DualAxis)]
+    Move2D,
+}
+
+impl GameAction {
+    /// Every button action; [`GameAction::Move2D`] is a stick.
+    pub const ALL: [GameAction; 21] = [
+        GameAction::Scene3D,
+        GameAction::Scene2D,
+        GameAction::GenomeEditor,
+        GameAction::FlowEditor,
+        GameAction::MainMenu,
+        GameAction::ExpressSugarCatabolism,
+        GameAction::SilenceFermentation,
+        GameAction::AddLightCapture,
+        GameAction::SpawnRespiration,
+        GameAction::Undo,
+        GameAction::Redo,
+        GameAction::PreviousGene,
+        GameAction::NextGene,
+        GameAction::ToggleGene,
+        GameAction::RepairGene,
+        GameAction::ToggleInspector,
+        GameAction::ToggleDashboard,
+        GameAction::ToggleRecording,
+        GameAction::PlayReplay,
+        GameAction::ApplyFlowEdit,
+        GameAction::RevertFlowEdit,
+    ];
+
+    pub fn input_map() -> InputMap<Self> {
+        let select = |button| ButtonlikeChord::new([GamepadButton::Select, button]);
+        let bumper = |button| ButtonlikeChord::new([GamepadButton::LeftTrigger, button]);
+        let ctrl = |key| ButtonlikeChord::modified(ModifierKey::Control, key);
+        let wasd = VirtualDPad::new(KeyCode::KeyW, KeyCode::KeyS, KeyCode::KeyA, KeyCode::KeyD);
+
+        InputMap::new([
+            (GameAction::Scene3D, KeyCode::Digit1),
+            (GameAction::Scene2D, KeyCode::Digit2),
+            (GameAction::GenomeEditor, KeyCode::Digit3),
+            (GameAction::FlowEditor, KeyCode::Digit4),
+            (GameAction::MainMenu, KeyCode::Escape),
+            (GameAction::ExpressSugarCatabolism, KeyCode::KeyG),
+            (GameAction::SilenceFermentation, KeyCode::KeyH),
+            (GameAction::AddLightCapture, KeyCode::KeyJ),
+            (GameAction::SpawnRespiration, KeyCode::KeyK),
+            (GameAction::PreviousGene, KeyCode::ArrowLeft),
+            (GameAction::NextGene, KeyCode::ArrowRight),
+            (GameAction::ToggleGene, KeyCode::Space),
+            (GameAction::RepairGene, KeyCode::KeyR),
+            (GameAction::ToggleInspector, KeyCode::F1),
+            (GameAction::ToggleDashboard, KeyCode::F2),
+            (GameAction::ToggleRecording, KeyCode::F9),
+            (GameAction::PlayReplay, KeyCode::F10),
+            (GameAction::ApplyFlowEdit, KeyCode::Enter),
+            (GameAction::RevertFlowEdit, KeyCode::Backspace),
+        ])
+        .with(GameAction::Undo, ctrl(KeyCode::KeyZ))
+        .with(GameAction::Redo, ctrl(KeyCode::KeyY))
+        .with(
+            GameAction::Redo,
+            ButtonlikeChord::new([ModifierKey::Control, ModifierKey::Shift]).with(KeyCode::KeyZ),
+        )
+        .with(GameAction::Scene3D, select(GamepadButton::DPadUp))
+        .with(GameAction::Scene2D, select(GamepadButton::DPadDown))
+        .with(GameAction::GenomeEditor, select(GamepadButton::DPadLeft))
+        .with(GameAction::FlowEditor, select(GamepadButton::DPadRight))
+        .with(GameAction::MainMenu, GamepadButton::Start)
+        .with(GameAction::ExpressSugarCatabolism, bumper(GamepadButton::North))
+        .with(GameAction::SilenceFermentation, bumper(GamepadButton::East))
+        .with(GameAction::AddLightCapture, bumper(GamepadButton::West))
+        .with(GameAction::SpawnRespiration, bumper(GamepadButton::South))
+        .with(GameAction::Undo, bumper(GamepadButton::DPadLeft))
+        .with(GameAction::Redo, bumper(GamepadButton::DPadRight))
+        .with(GameAction::PreviousGene, GamepadButton::DPadLeft)
+        .with(GameAction::NextGene, GamepadButton::DPadRight)
+        .with(GameAction::ToggleGene, GamepadButton::South)
+        .with(GameAction::RepairGene, GamepadButton::West)
+        .with(GameAction::ToggleInspector, GamepadButton::RightThumb)
+        .with(GameAction::ToggleDashboard, GamepadButton::LeftThumb)
+        .with(GameAction::ToggleRecording, select(GamepadButton::West))
+        .with(GameAction::PlayReplay, select(GamepadButton::East))
+        .with(GameAction::ApplyFlowEdit, GamepadButton::South)
+        .with(GameAction::RevertFlowEdit, GamepadButton::East)
+        .with_dual_axis(GameAction::Move2D, wasd)
+        .with_dual_axis(GameAction::Move2D, GamepadStick::LEFT)
+    }
+}
+
+/// The player's input bindings, as stored in the settings file.
+#[derive(Resource, Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct InputBindings {
+    /// Bindings of the player's cell and the camera following it
+    pub player: InputMap<Action>,
+    pub game: InputMap<GameAction>,
+}
+
+impl Default for InputBindings {
+    fn default() -> Self {
+        Self {
+            player: Action::input_map(),
+            game: GameAction::input_map(),
+        }
+    }
+}
+
+/// Errors that can occur while saving or loading input bindings
+#[derive(Debug)]
+pub enum InputBindingsError {
+    Io(std::io::Error),
+    MalformedJson(serde_json::Error),
+}
+
+impl std::fmt::Display for InputBindingsError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            InputBindingsError::Io(err) => write!(f, "input bindings I/O error: {}", err),
+            InputBindingsError::MalformedJson(err) => {
+                write!(f, "malformed input bindings JSON: {}", err)
+            }
+        }
+    }
+}
+
+impl std::error::Error for InputBindingsError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            InputBindingsError::Io(err) => Some(err),
+            InputBindingsError::MalformedJson(err) => Some(err),
+        }
+    }
+}
+
+impl From<std::io::Error> for InputBindingsError {
+    fn from(err: std::io::Error) -> Self {
+        InputBindingsError::Io(err)
+    }
+}
+
+impl From<serde_json::Error> for InputBindingsError {
+    fn from(err: serde_json::Error) -> Self {
+        InputBindingsError::MalformedJson(err)
+    }
+}
+
+/// Give every action `map` has no bindings for the bindings it has in `defaults`.
+fn fill_unbound<A: Actionlike>(map: &mut InputMap<A>, defaults: &InputMap<A>) {
+    let bound: Vec<A> = map
+        .buttonlike_actions()
+        .chain(map.axislike_actions())
+        .chain(map.dual_axislike_actions())
+        .chain(map.triple_axislike_actions())
+        .cloned()
+        .collect();
+    let mut missing = defaults.clone();
+    for action in &bound {
+        missing.clear_action(action);
+    }
+    map.merge(&missing);
+}
+
+impl InputBindings {
+    /// Serialize the bindings to pretty-printed JSON
+    pub fn to_json(&self) -> Result<String, serde_json::Error> {
+        serde_json::to_string_pretty(self)
+    }
+
+    /// Deserialize bindings from a JSON string; unbound actions get their defaults.
+    ///
+    /// Inputs are deserialized through leafwing's input registry, which is filled in when
+    /// an [`InputManagerPlugin`] is added to an app.
+    pub fn from_json(json: &str) -> Result<Self, InputBindingsError> {
+        let mut bindings: Self = serde_json::from_str(json)?;
+        let defaults = Self::default();
+        fill_unbound(&mut bindings.player, &defaults.player);
+        fill_unbound(&mut bindings.game, &defaults.game);
+        Ok(bindings)
+    }
+
+    /// Write the bindings to `path`, creating parent directories as needed
+    pub fn save_to_file(&self, path: impl AsRef<Path>) -> Result<(), InputBindingsError> {
+        let path = path.as_ref();
+        if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(path, self.to_json()?)?;
+        Ok(())
+    }
+
+    /// Read bindings from `path`
+    pub fn load_from_file(path: impl AsRef<Path>) -> Result<Self, InputBindingsError> {
+        Self::from_json(&std::fs::read_to_string(path)?)
+    }
+}
+
+/// The settings file [`InputBindings`] are loaded from and saved to.
+#[derive(Resource, Debug, Clone, PartialEq, Eq)]
+pub struct InputBindingsPath(pub PathBuf);
+
+impl Default for InputBindingsPath {
+    fn default() -> Self {
+        Self(PathBuf::from(DEFAULT_BINDINGS_PATH))
+    }
+}
+
+// --- Systems ---
+
+/// Read the settings file, or create it with the defaults if there is none. A file that
+/// cannot be read is left alone and the defaults are used.
+pub fn load_input_bindings(mut commands: Commands, path: Res<InputBindingsPath>) {
+    let bindings = match InputBindings::load_from_file(&path.0) {
+        Ok(bindings) => {
+            info!("Loaded input bindings from {}", path.0.display());
+            bindings
+        }
+        Err(InputBindingsError::Io(err)) if err.kind() == std::io::ErrorKind::NotFound => {
+            let bindings = InputBindings::default();
+            if let Err(err) = bindings.save_to_file(&path.0) {
+                warn!("Could not write default input bindings: {}", err);
+            }
+            bindings
+        }
+        Err(err) => {
+            warn!("Using default input bindings: {}", err);
+            InputBindings::default()
+        }
+    };
+    commands.insert_resource(bindings.game.clone());
+    commands.insert_resource(bindings);
+}
+
+/// Hand changed bindings to the player and the global action state, and save them.
+pub fn apply_input_bindings(
+    bindings: Res<InputBindings>,
+    path: Res<InputBindingsPath>,
+    mut game_map: ResMut<InputMap<GameAction>>,
+    mut player_maps: Query<&mut InputMap<Action>>,
+) {
+    if !bindings.is_changed() {
+        return;
+    }
+    *game_map = bindings.game.clone();
+    for mut map in &mut player_maps {
+        *map = bindings.player.clone();
+    }
+    // Freshly loaded bindings are already on disk
+    if !bindings.is_added() {
+        if let Err(err) = bindings.save_to_file(&path.0) {
+            warn!("Could not save input bindings: {}", err);
+        }
+    }
+}
+
+// --- Plugin ---
+
+pub struct InputBindingsPlugin;
+
+impl Plugin for InputBindingsPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_plugins(InputManagerPlugin::<GameAction>::default())
+            .init_resource::<InputBindingsPath>()
+            .init_resource::<ActionState<GameAction>>()
+            .insert_resource(GameAction::input_map())
+            .add_systems(PreStartup, load_input_bindings)
+            .add_systems(Update, apply_input_bindings);
+    }
+}
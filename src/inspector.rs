@@ -2,6 +2,9 @@ use bevy::prelude::*;
 use bevy::window::PrimaryWindow;
 use bevy_egui::{egui, EguiContext, EguiPlugin};
 use bevy_inspector_egui::DefaultInspectorConfigPlugin;
+use leafwing_input_manager::prelude::ActionState;
+
+use crate::input::GameAction;
 
 /// Plugin for adding inspector functionality to debug builds
 pub(crate) fn plugin(app: &mut App) {
@@ -39,12 +42,12 @@ fn inspector_ui(world: &mut World) {
     });
 }
 
-/// System to toggle the inspector with the F1 key
+/// System to toggle the inspector with the inspector action (`F1` by default)
 fn input_toggle_active(
-    keyboard: Res<ButtonInput<KeyCode>>,
+    actions: Option<Res<ActionState<GameAction>>>,
     mut inspector_active: Local<bool>,
 ) -> bool {
-    if keyboard.just_pressed(KeyCode::F1) {
+    if actions.is_some_and(|actions| actions.just_pressed(&GameAction::ToggleInspector)) {
         *inspector_active = !*inspector_active;
     }
     *inspector_active
@@ -2,27 +2,43 @@
 use avian3d::prelude::*;
 use bevy::prelude::*;
 use bevy::state::app::StatesPlugin;
+use bevy::time::TimeUpdateStrategy;
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
 
 pub mod inspector;
 pub mod blocks;
 pub mod camera;
+#[cfg(feature = "full")]
+pub mod dashboard;
 pub mod debug;
 pub mod dev_tools;
+pub mod environment;
+#[cfg(feature = "full")]
+pub mod flux_visuals;
+pub mod input;
 
 pub mod molecules;
+pub mod npc;
 pub mod player;
+pub mod replay;
 pub mod scenes;
 pub mod shared;
 pub mod metabolism;
+pub mod metrics;
+pub mod scenario;
+pub mod snapshot;
+pub mod terrain;
 
 /// Game states for scene management
-#[derive(States, Debug, Clone, PartialEq, Eq, Hash, Default)]
+#[derive(States, Debug, Clone, PartialEq, Eq, Hash, Default, Serialize, Deserialize)]
 pub enum GameState {
     #[default]
     MainMenu,
     Scene3D,
     Scene2D,
     GenomeEditing,
+    FlowEditor,
 }
 
 /// Main app configuration
@@ -47,13 +63,23 @@ impl MetabolisticApp {
             .add_plugins(blocks::fermentation::FermentationPlugin)
             .add_plugins(blocks::fat_storage::FatStoragePlugin)
             .add_plugins(blocks::vesicle_export::VesicleExportPlugin)
-            .add_plugins(metabolism::MetabolicFlowPlugin);
+            .add_plugins(blocks::motility::MotilityPlugin)
+            .add_plugins(metabolism::MetabolicFlowPlugin)
+            .add_plugins(environment::EnvironmentPlugin)
+            .add_plugins(terrain::TerrainPlugin)
+            .add_plugins(npc::NpcPlugin)
+            .add_plugins(snapshot::SnapshotPlugin)
+            .add_plugins(metrics::MetricsPlugin)
+            .add_plugins(replay::ReplayPlugin);
             
         #[cfg(feature = "full")]
         {
-            app.add_plugins(dev_tools::plugin)
+            app.add_plugins(input::InputBindingsPlugin)
+                .add_plugins(dev_tools::plugin)
                 .add_plugins(debug::plugin)
                 .add_plugins(inspector::plugin)
+                .add_plugins(dashboard::plugin)
+                .add_plugins(flux_visuals::plugin)
                 // Camera systems that work with any scene type
                 .add_plugins(camera::CameraSystemsPlugin)
                 // Scene-specific plugins
@@ -61,11 +87,18 @@ impl MetabolisticApp {
                 .add_plugins(scenes::scene_3d::Scene3DPlugin)
                 .add_plugins(scenes::scene_2d::Scene2DPlugin)
                 .add_plugins(scenes::genome_edit::GenomeEditPlugin)
+                .add_plugins(scenes::flow_editor::FlowEditorPlugin)
                 // Shared systems that run in multiple states
                 .add_systems(Startup, shared::setup_shared_resources)
                 .add_systems(
                     Update,
-                    (shared::state_transition_input, shared::genome_demo_system),
+                    (
+                        shared::state_transition_input,
+                        shared::genome_demo_system,
+                        shared::genome_history_input,
+                        replay::replay_hotkeys,
+                    )
+                        .run_if(replay::live_input_enabled),
                 );
         }
 
@@ -89,9 +122,21 @@ impl MetabolisticApp {
             .add_plugins(blocks::fermentation::FermentationPlugin)
             .add_plugins(blocks::fat_storage::FatStoragePlugin)
             .add_plugins(blocks::vesicle_export::VesicleExportPlugin)
+            .add_plugins(blocks::motility::MotilityPlugin)
             .add_plugins(metabolism::MetabolicFlowPlugin)
+            .add_plugins(environment::EnvironmentPlugin)
+            .add_plugins(terrain::TerrainPlugin)
+            .add_plugins(npc::NpcPlugin)
+            .add_plugins(snapshot::SnapshotPlugin)
+            .add_plugins(metrics::MetricsPlugin)
+            .add_plugins(replay::ReplayPlugin)
             // Override with deterministic mutations for testing
             .insert_resource(blocks::genome::MutationConfig::deterministic())
+            // Solve flux in the same tick regardless of graph size
+            .insert_resource(metabolism::solver::FluxSolveMode::Synchronous)
+            // Wall-clock time never advances the fixed clock; metabolism only moves
+            // through explicit ticks (`metabolism::run_ticks`), so runs are reproducible
+            .insert_resource(TimeUpdateStrategy::ManualDuration(Duration::ZERO))
             // Only add shared systems that don't require input
             .add_systems(Startup, shared::setup_shared_resources);
 
@@ -0,0 +1,268 @@
+//! # Cells
+//!
+//! Every organism is a [`Cell`] entity with its own [`Genome`], [`CurrencyPools`] and
+//! [`CellMetabolism`]. The blocks, wires, compartments and transporters of a cell carry a
+//! [`CellMember`] pointing back at it.
+//!
+//! The player's cell is marked [`PlayerCell`]. It carries its genome and pools like any
+//! other cell, and the genome editor, dashboard and controls read them from there; the
+//! genome and pools an app starts with in the resources of the same types are moved onto
+//! it at startup. Its metabolic graph, flux and vitality stay in the resources the flow
+//! editor and dashboard display, and its members have no `CellMember`.
+//!
+//! Each metabolic tick [`simulate_cells`] runs the [`CellSchedule`] once for every cell,
+//! the player's first, with that cell's state moved into the resources. The `Genome` and
+//! `CurrencyPools` resources only exist while a cell is simulated. Systems that query
+//! members keep to the cell being simulated through [`CellScope`].
+
+use bevy::ecs::schedule::ScheduleLabel;
+use bevy::ecs::system::SystemParam;
+use bevy::prelude::*;
+
+use super::compartments::{spawn_compartments, CellCompartments};
+use super::solver::PendingFluxSolve;
+use super::toxicity::MetabolicEfficiency;
+use super::vitality::{CellVitality, StarvationTicks};
+use super::{CurrencyPools, FlowDirty, FluxResult, MetabolicGraph, MetabolicSet};
+use crate::blocks::fermentation::fermentation_block;
+use crate::blocks::genome::{Genome, MetabolicUpdateEvent};
+use crate::blocks::vesicle_export::VesicleExportBlock;
+use crate::molecules::CellMass;
+
+/// An organism with its own genome and currency pools.
+#[derive(Component, Debug, Default, Clone, Copy)]
+pub struct Cell;
+
+/// The cell the player controls.
+#[derive(Component, Debug, Default, Clone, Copy)]
+pub struct PlayerCell;
+
+/// The cell an entity belongs to; entities without one belong to the player's cell.
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CellMember(pub Entity);
+
+/// Metabolic state of a cell other than the player's, besides its genome and pools.
+#[derive(Component, Default)]
+pub struct CellMetabolism {
+    pub graph: MetabolicGraph,
+    pub flux_result: FluxResult,
+    pub flow_dirty: FlowDirty,
+    pub vitality: CellVitality,
+    pub starvation: StarvationTicks,
+    pub efficiency: MetabolicEfficiency,
+    pub compartments: CellCompartments,
+    pub pending_solve: PendingFluxSolve,
+}
+
+/// One metabolic tick of a single cell: the `Tick`, `Blocks` and `Flow` sets of
+/// [`MetabolicSet`](super::MetabolicSet).
+#[derive(ScheduleLabel, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct CellSchedule;
+
+/// The cell other than the player's whose state is in the resources while
+/// [`CellSchedule`] runs; `None` for the player's cell.
+#[derive(Resource, Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct SimulatedCell(pub Option<Entity>);
+
+/// Which cell's members a system should touch this run.
+#[derive(SystemParam)]
+pub struct CellScope<'w> {
+    simulated: Option<Res<'w, SimulatedCell>>,
+}
+
+impl CellScope<'_> {
+    /// The cell being simulated; `None` for the player's cell.
+    pub fn cell(&self) -> Option<Entity> {
+        self.simulated.as_ref().and_then(|simulated| simulated.0)
+    }
+
+    /// Whether an entity with `member` belongs to the cell being simulated.
+    pub fn contains(&self, member: Option<&CellMember>) -> bool {
+        self.cell() == member.map(|member| member.0)
+    }
+}
+
+/// Runs [`CellSchedule`] for every cell on each metabolic tick.
+///
+/// Every plugin with systems in that schedule adds it through [`CellsPlugin::add_to`], so
+/// block plugins also work in apps without the rest of the metabolism.
+pub struct CellsPlugin;
+
+impl CellsPlugin {
+    /// Add the plugin to `app` unless it is there already.
+    pub fn add_to(app: &mut App) {
+        if !app.is_plugin_added::<Self>() {
+            app.add_plugins(Self);
+        }
+    }
+}
+
+impl Plugin for CellsPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<SimulatedCell>()
+            .init_schedule(CellSchedule)
+            .configure_sets(
+                CellSchedule,
+                (MetabolicSet::Tick, MetabolicSet::Blocks, MetabolicSet::Flow).chain(),
+            )
+            .add_systems(PostStartup, ensure_player_cell)
+            .add_systems(FixedUpdate, simulate_cells);
+    }
+}
+
+/// Spawn a cell besides the player's, with the blocks and compartments every cell
+/// starts with. Its blocks pick up their status from `genome` on the first tick.
+pub fn spawn_cell(world: &mut World, genome: Genome, pools: CurrencyPools) -> Entity {
+    let cell = world.spawn(CellMass { base: 1.0, extra: 0.0 }).id();
+    init_cell(world, cell, genome, pools);
+    cell
+}
+
+/// Turn `cell`, which should carry a [`CellMass`], into a cell like [`spawn_cell`] does.
+pub fn init_cell(world: &mut World, cell: Entity, genome: Genome, pools: CurrencyPools) {
+    world.spawn((fermentation_block(), CellMember(cell)));
+    world.spawn((VesicleExportBlock, CellMember(cell)));
+    let compartments = spawn_compartments(world, Some(cell));
+    world.entity_mut(cell).insert((
+        Cell,
+        Name::new("Cell"),
+        CellMember(cell),
+        genome,
+        pools,
+        CellMetabolism {
+            compartments,
+            ..Default::default()
+        },
+    ));
+}
+
+/// Give the player's cell an entity, unless one was spawned with the player, and move the
+/// genome and pools in the resources onto it.
+pub fn ensure_player_cell(world: &mut World) {
+    let genome = world.remove_resource::<Genome>().unwrap_or_default();
+    let pools = world
+        .remove_resource::<CurrencyPools>()
+        .unwrap_or_else(CurrencyPools::with_defaults);
+    match player_cell(world) {
+        Some(cell) => {
+            world.entity_mut(cell).insert((Cell, genome, pools));
+        }
+        None => {
+            world.spawn((Cell, PlayerCell, Name::new("Player cell"), genome, pools));
+        }
+    }
+}
+
+/// The player's cell, if it has been spawned.
+pub fn player_cell(world: &World) -> Option<Entity> {
+    world
+        .iter_entities()
+        .find(|entity| entity.contains::<PlayerCell>())
+        .map(|entity| entity.id())
+}
+
+/// The player's genome: on its cell, or in the resource before startup and while the
+/// player's cell is simulated.
+pub fn player_genome(world: &World) -> &Genome {
+    player_state(world).expect("the player has no genome")
+}
+
+/// The player's genome, for changing.
+pub fn player_genome_mut(world: &mut World) -> Mut<'_, Genome> {
+    player_state_mut(world).expect("the player has no genome")
+}
+
+/// The player's pools: on its cell, or in the resource before startup and while the
+/// player's cell is simulated.
+pub fn player_pools(world: &World) -> &CurrencyPools {
+    player_state(world).expect("the player has no pools")
+}
+
+/// The player's pools, for changing.
+pub fn player_pools_mut(world: &mut World) -> Mut<'_, CurrencyPools> {
+    player_state_mut(world).expect("the player has no pools")
+}
+
+fn player_state<T: Component + Resource>(world: &World) -> Option<&T> {
+    player_cell(world)
+        .and_then(|cell| world.get::<T>(cell))
+        .or_else(|| world.get_resource::<T>())
+}
+
+fn player_state_mut<T: Component + Resource>(world: &mut World) -> Option<Mut<'_, T>> {
+    match player_cell(world).filter(|&cell| world.entity(cell).contains::<T>()) {
+        Some(cell) => world.get_mut::<T>(cell),
+        None => world.get_resource_mut::<T>(),
+    }
+}
+
+/// Swap a cell's metabolic state with the resources it is simulated in.
+fn exchange(world: &mut World, state: &mut CellMetabolism) {
+    fn swap<R: Resource>(world: &mut World, value: &mut R) {
+        std::mem::swap(world.resource_mut::<R>().bypass_change_detection(), value);
+    }
+    swap(world, &mut state.graph);
+    swap(world, &mut state.flux_result);
+    swap(world, &mut state.flow_dirty);
+    swap(world, &mut state.vitality);
+    swap(world, &mut state.starvation);
+    swap(world, &mut state.efficiency);
+    swap(world, &mut state.compartments);
+    swap(world, &mut state.pending_solve);
+}
+
+/// Cells with everything [`simulate_cells`] needs.
+type SimulatedCells = (
+    With<Cell>,
+    With<Genome>,
+    With<CurrencyPools>,
+    Or<(With<PlayerCell>, With<CellMetabolism>)>,
+);
+
+/// Run one metabolic tick of every cell: the player's first, then the others in spawn
+/// order.
+pub fn simulate_cells(world: &mut World) {
+    // Cells born this tick start metabolising on the next one
+    let mut cells: Vec<(bool, Entity)> = world
+        .query_filtered::<(Has<PlayerCell>, Entity), SimulatedCells>()
+        .iter(world)
+        .map(|(player, cell)| (!player, cell))
+        .collect();
+    cells.sort();
+
+    for (other, cell) in cells {
+        let Some((genome, pools)) = world.entity_mut(cell).take::<(Genome, CurrencyPools)>() else {
+            continue;
+        };
+        // The player's graph, flux and vitality are the resources already
+        let mut state = world.entity_mut(cell).take::<CellMetabolism>();
+        world.insert_resource(genome);
+        world.insert_resource(pools);
+        if let Some(state) = state.as_mut() {
+            exchange(world, state);
+        }
+
+        if other {
+            world.resource_mut::<SimulatedCell>().0 = Some(cell);
+            // The player's genome is diffed every frame; other cells catch up once per tick
+            let genome_changed = world.resource::<Genome>().has_any_changes();
+            if genome_changed {
+                world.resource_mut::<Genome>().update_previous_state();
+                world.send_event(MetabolicUpdateEvent);
+            }
+        }
+        world.run_schedule(CellSchedule);
+        world.resource_mut::<SimulatedCell>().0 = None;
+
+        if let Some(state) = state.as_mut() {
+            exchange(world, state);
+        }
+        let genome = world.remove_resource::<Genome>().unwrap_or_default();
+        let pools = world.remove_resource::<CurrencyPools>().unwrap_or_default();
+        let mut entity = world.entity_mut(cell);
+        entity.insert((genome, pools));
+        if let Some(state) = state {
+            entity.insert(state);
+        }
+    }
+}
@@ -0,0 +1,175 @@
+//! # Compartments
+//!
+//! Membrane-bound spaces inside the cell. Each organelle — mitochondrion, chloroplast,
+//! vacuole — is a [`Compartment`] entity with its own [`CompartmentPools`]. The cytosol is a
+//! compartment entity too, but its pools are the shared [`CurrencyPools`] resource that
+//! every block without an assignment already draws from.
+//!
+//! A block tagged [`InCompartment`] has its flux solved against that compartment's pools,
+//! so ATP made in a mitochondrion stays there until a [`Transporter`] carries it across the
+//! membrane. Transporters move a currency between the cytosol and one organelle every tick
+//! and pay for it in cytosolic ATP.
+//!
+//! Dependencies in the [`MetabolicGraph`](super::MetabolicGraph) ignore compartments; they
+//! only order the solve.
+
+use std::collections::HashMap;
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use super::cells::{CellMember, CellScope};
+use super::{BlockStatus, CurrencyPools, MetabolicNode};
+use crate::molecules::Currency;
+
+/// The kinds of compartment a cell has.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CompartmentKind {
+    Cytosol,
+    Mitochondrion,
+    Chloroplast,
+    Vacuole,
+}
+
+impl CompartmentKind {
+    /// Every compartment kind, cytosol first.
+    pub const ALL: [CompartmentKind; 4] = [
+        CompartmentKind::Cytosol,
+        CompartmentKind::Mitochondrion,
+        CompartmentKind::Chloroplast,
+        CompartmentKind::Vacuole,
+    ];
+}
+
+/// A membrane-bound space within the cell.
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Compartment {
+    pub kind: CompartmentKind,
+}
+
+/// Currency pools of an organelle. The cytosol has none; it uses [`CurrencyPools`].
+#[derive(Component, Debug, Default, Clone)]
+pub struct CompartmentPools(pub CurrencyPools);
+
+/// Places a block in a compartment; blocks without one work in the cytosol.
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct InCompartment(pub Entity);
+
+/// Which way a [`Transporter`] moves its currency.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TransportDirection {
+    /// From the cytosol into the organelle
+    Import,
+    /// From the organelle out to the cytosol
+    Export,
+}
+
+/// A block spanning an organelle membrane that moves up to `rate` units of `currency`
+/// per tick, paying `atp_cost` cytosolic ATP for every unit moved.
+///
+/// With a [`MetabolicNode`] it follows the genome: mutated transporters run at half rate
+/// and silent ones not at all.
+#[derive(Component, Debug, Clone, Copy, PartialEq)]
+pub struct Transporter {
+    pub compartment: Entity,
+    pub currency: Currency,
+    pub direction: TransportDirection,
+    pub rate: f32,
+    pub atp_cost: f32,
+}
+
+/// Compartment entity of each kind in the player's cell, or in the cell being simulated.
+#[derive(Resource, Debug, Default, Clone)]
+pub struct CellCompartments(pub HashMap<CompartmentKind, Entity>);
+
+impl CellCompartments {
+    pub fn get(&self, kind: CompartmentKind) -> Option<Entity> {
+        self.0.get(&kind).copied()
+    }
+}
+
+fn spawn_compartment(world: &mut World, kind: CompartmentKind, cell: Option<Entity>) -> Entity {
+    let mut entity = world.spawn((Compartment { kind }, Name::new(format!("{:?}", kind))));
+    if kind != CompartmentKind::Cytosol {
+        entity.insert(CompartmentPools::default());
+    }
+    if let Some(cell) = cell {
+        entity.insert(CellMember(cell));
+    }
+    entity.id()
+}
+
+/// Spawn every compartment for `cell`, or for the player's cell when `None`.
+pub fn spawn_compartments(world: &mut World, cell: Option<Entity>) -> CellCompartments {
+    CellCompartments(
+        CompartmentKind::ALL
+            .into_iter()
+            .map(|kind| (kind, spawn_compartment(world, kind, cell)))
+            .collect(),
+    )
+}
+
+/// Spawn any compartment the player's cell is missing and record all of them in
+/// [`CellCompartments`].
+pub fn ensure_compartments(world: &mut World) {
+    let mut query = world.query_filtered::<(Entity, &Compartment), Without<CellMember>>();
+    let mut compartments: HashMap<CompartmentKind, Entity> = query
+        .iter(world)
+        .map(|(entity, compartment)| (compartment.kind, entity))
+        .collect();
+    for kind in CompartmentKind::ALL {
+        compartments
+            .entry(kind)
+            .or_insert_with(|| spawn_compartment(world, kind, None));
+    }
+    world.insert_resource(CellCompartments(compartments));
+}
+
+/// Move currencies across organelle membranes.
+pub fn transport_system(
+    mut cytosol: ResMut<CurrencyPools>,
+    transporters: Query<(&Transporter, Option<&MetabolicNode>, Option<&CellMember>)>,
+    mut organelles: Query<&mut CompartmentPools>,
+    scope: CellScope,
+) {
+    for (transporter, node, member) in &transporters {
+        if !scope.contains(member) {
+            continue;
+        }
+        let rate = match node.map_or(BlockStatus::Active, |node| node.status) {
+            BlockStatus::Active => transporter.rate,
+            BlockStatus::Mutated => transporter.rate * 0.5,
+            BlockStatus::Silent => continue,
+        };
+        let Ok(mut organelle) = organelles.get_mut(transporter.compartment) else {
+            continue;
+        };
+        let currency = transporter.currency;
+        let available = match transporter.direction {
+            TransportDirection::Import => cytosol.get(currency),
+            TransportDirection::Export => organelle.0.get(currency),
+        };
+
+        // The fee is always paid in cytosolic ATP, which imported ATP also comes out of
+        let mut amount = rate.min(available);
+        if transporter.atp_cost > 0.0 {
+            let imports_atp =
+                transporter.direction == TransportDirection::Import && currency == Currency::ATP;
+            let per_unit = transporter.atp_cost + if imports_atp { 1.0 } else { 0.0 };
+            amount = amount.min(cytosol.get(Currency::ATP) / per_unit);
+        }
+        if amount <= 0.0 {
+            continue;
+        }
+
+        let moved = match transporter.direction {
+            TransportDirection::Import => amount,
+            TransportDirection::Export => -amount,
+        };
+        cytosol.modify(currency, -moved);
+        organelle.0.modify(currency, moved);
+        cytosol.modify(Currency::ATP, -amount * transporter.atp_cost);
+    }
+}
@@ -0,0 +1,120 @@
+//! # Division
+//!
+//! A cell that has grown heavy enough and stocked up on the currencies in
+//! [`DivisionRules::min_currencies`] splits in two at the end of its metabolic tick. Every
+//! pool, storage beads included, is halved between mother and daughter, and so is the mass
+//! the beads add. The daughter inherits a copy of the genome in which each gene may mutate
+//! through the [`MutationConfig`], and is spawned next to its mother as a new [`Cell`].
+//!
+//! Daughters are always cells of their own, even when the player's cell divides.
+
+use std::collections::BTreeMap;
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use super::cells::{init_cell, Cell, CellMember, CellScope, PlayerCell};
+use super::CurrencyPools;
+use crate::blocks::genome::{Genome, MutationConfig};
+use crate::molecules::{CellMass, Currency};
+
+/// When cells divide and how their daughters are made.
+#[derive(Resource, Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DivisionRules {
+    /// Total mass (`base + extra`) a cell needs before it can divide
+    pub min_mass: f32,
+    /// Amount of each currency a cell needs before it can divide
+    pub min_currencies: BTreeMap<Currency, f32>,
+    /// Time the daughter's genes are exposed to the mutation strategy while copied
+    pub replication_exposure: f32,
+    /// Distance between mother and daughter in the scene
+    pub spacing: f32,
+}
+
+impl Default for DivisionRules {
+    fn default() -> Self {
+        Self {
+            min_mass: 2.0,
+            min_currencies: BTreeMap::from([(Currency::ATP, 150.0), (Currency::CarbonSkeletons, 40.0)]),
+            replication_exposure: 10.0,
+            spacing: 1.0,
+        }
+    }
+}
+
+impl DivisionRules {
+    /// Whether a cell of `mass` with `pools` is ready to divide.
+    pub fn ready(&self, mass: &CellMass, pools: &CurrencyPools) -> bool {
+        mass.base + mass.extra >= self.min_mass
+            && self
+                .min_currencies
+                .iter()
+                .all(|(&currency, &amount)| pools.get(currency) >= amount)
+    }
+}
+
+/// Sent when `parent` divides; `daughter` is the cell it gave rise to.
+#[derive(Event, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CellDivided {
+    pub parent: Entity,
+    pub daughter: Entity,
+}
+
+/// Split the pools in half, leaving one half in `pools` and returning the other.
+pub fn split_pools(pools: &mut CurrencyPools) -> CurrencyPools {
+    let mut daughter = CurrencyPools::default();
+    for (&currency, amount) in pools.pools.iter_mut() {
+        *amount /= 2.0;
+        daughter.pools.insert(currency, *amount);
+    }
+    daughter
+}
+
+// --- Systems ---
+
+/// Divide the cell being simulated once it meets the [`DivisionRules`].
+#[allow(clippy::too_many_arguments)]
+pub fn divide_cells(
+    mut commands: Commands,
+    rules: Res<DivisionRules>,
+    mut pools: ResMut<CurrencyPools>,
+    genome: Res<Genome>,
+    mut mutation_config: Option<ResMut<MutationConfig>>,
+    mut masses: Query<(Entity, &mut CellMass, Option<&CellMember>)>,
+    transforms: Query<&Transform, With<Cell>>,
+    player: Query<Entity, With<PlayerCell>>,
+    scope: CellScope,
+    mut divisions: EventWriter<CellDivided>,
+) {
+    let Some((entity, mut mass)) = masses
+        .iter_mut()
+        .find(|(_, _, member)| scope.contains(*member))
+        .map(|(entity, mass, _)| (entity, mass))
+    else {
+        return;
+    };
+    if !rules.ready(&mass, &pools) {
+        return;
+    }
+
+    let daughter_pools = split_pools(&mut pools);
+    mass.extra /= 2.0;
+    let daughter_mass = CellMass {
+        base: mass.base,
+        extra: mass.extra,
+    };
+    let daughter_genome = genome.replicate(mutation_config.as_deref_mut(), rules.replication_exposure);
+
+    let parent = scope.cell().or_else(|| player.get_single().ok()).unwrap_or(entity);
+    let origin = transforms.get(parent).copied().unwrap_or_default();
+    let transform = origin.with_translation(origin.translation + Vec3::X * rules.spacing);
+
+    let daughter = commands.spawn((daughter_mass, transform)).id();
+    commands.queue(move |world: &mut World| {
+        init_cell(world, daughter, daughter_genome, daughter_pools);
+        world.entity_mut(daughter).insert(Name::new("Daughter cell"));
+    });
+    info!("Cell {:?} divided into {:?}", parent, daughter);
+    divisions.send(CellDivided { parent, daughter });
+}
@@ -0,0 +1,391 @@
+//! # Dense Metabolic Graph
+//!
+//! The solver's view of the metabolic network, laid out as dense vectors. Every
+//! [`MetabolicNode`](super::MetabolicNode) gets an index into [`MetabolicGraph::nodes`];
+//! its flux is a fixed-size [`CurrencyVector`] and the producers it depends on are a row of
+//! a [`CsrAdjacency`].
+//!
+//! A full [`MetabolicGraph::build`] groups producers by currency, so linking costs
+//! `O(nodes × currencies + dependencies)` instead of comparing every pair of nodes.
+//! When a single node's profile changes, [`MetabolicGraph::update_profile`] relinks only
+//! the rows that change; [`MetabolicGraph::add_node`] and [`MetabolicGraph::remove_node`]
+//! do the same for nodes entering or leaving the graph.
+
+use std::collections::HashMap;
+
+use bevy::prelude::*;
+
+use super::{FlowEdge, FluxProfile};
+use crate::molecules::Currency;
+
+/// Flux of one node for every currency, indexed by [`Currency::index`].
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct CurrencyVector(pub [f32; Currency::COUNT]);
+
+impl CurrencyVector {
+    pub fn get(&self, currency: Currency) -> f32 {
+        self.0[currency.index()]
+    }
+
+    pub fn set(&mut self, currency: Currency, amount: f32) {
+        self.0[currency.index()] = amount;
+    }
+
+    /// Non-zero entries in currency order.
+    pub fn iter(&self) -> impl Iterator<Item = (Currency, f32)> + '_ {
+        Currency::ALL
+            .into_iter()
+            .map(|currency| (currency, self.get(currency)))
+            .filter(|&(_, amount)| amount != 0.0)
+    }
+
+    /// Bit set of the currencies this node produces.
+    pub fn produced(&self) -> u32 {
+        self.mask(|amount| amount > 0.0)
+    }
+
+    /// Bit set of the currencies this node consumes.
+    pub fn consumed(&self) -> u32 {
+        self.mask(|amount| amount < 0.0)
+    }
+
+    fn mask(&self, test: impl Fn(f32) -> bool) -> u32 {
+        self.0
+            .iter()
+            .enumerate()
+            .filter(|&(_, &amount)| test(amount))
+            .fold(0, |mask, (i, _)| mask | 1 << i)
+    }
+}
+
+impl From<&FluxProfile> for CurrencyVector {
+    fn from(profile: &FluxProfile) -> Self {
+        let mut vector = Self::default();
+        for (&currency, &amount) in &profile.0 {
+            vector.set(currency, amount);
+        }
+        vector
+    }
+}
+
+/// Currencies whose bit is set in `mask`.
+fn currencies_in(mask: u32) -> impl Iterator<Item = Currency> {
+    Currency::ALL
+        .into_iter()
+        .filter(move |currency| mask & 1 << currency.index() != 0)
+}
+
+/// Compressed sparse rows: row `i` lists the node indices node `i` depends on.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CsrAdjacency {
+    offsets: Vec<u32>,
+    targets: Vec<u32>,
+}
+
+impl Default for CsrAdjacency {
+    fn default() -> Self {
+        Self {
+            offsets: vec![0],
+            targets: Vec::new(),
+        }
+    }
+}
+
+impl CsrAdjacency {
+    pub fn from_rows(rows: impl IntoIterator<Item = Vec<u32>>) -> Self {
+        let mut csr = Self::default();
+        for row in rows {
+            csr.targets.extend(row);
+            csr.offsets.push(csr.targets.len() as u32);
+        }
+        csr
+    }
+
+    /// Number of rows.
+    pub fn len(&self) -> usize {
+        self.offsets.len() - 1
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Targets of row `i`; empty for rows past the end.
+    pub fn row(&self, i: usize) -> &[u32] {
+        if i >= self.len() {
+            return &[];
+        }
+        &self.targets[self.offsets[i] as usize..self.offsets[i + 1] as usize]
+    }
+
+    /// Total number of targets over all rows.
+    pub fn target_count(&self) -> usize {
+        self.targets.len()
+    }
+
+    /// Append a row at the end.
+    pub fn push_row(&mut self, row: Vec<u32>) {
+        self.targets.extend(row);
+        self.offsets.push(self.targets.len() as u32);
+    }
+
+    /// Drop row `removed` and every target pointing at it, shifting later indices down.
+    pub fn remove_index(&mut self, removed: usize) {
+        let removed = removed as u32;
+        let rows: Vec<Vec<u32>> = (0..self.len())
+            .filter(|&i| i as u32 != removed)
+            .map(|i| {
+                self.row(i)
+                    .iter()
+                    .filter(|&&target| target != removed)
+                    .map(|&target| if target > removed { target - 1 } else { target })
+                    .collect()
+            })
+            .collect();
+        *self = Self::from_rows(rows);
+    }
+
+    /// Swap in new contents for some rows, keeping the others.
+    pub fn replace_rows(&mut self, mut replacements: HashMap<usize, Vec<u32>>) {
+        if replacements.is_empty() {
+            return;
+        }
+        let rows: Vec<Vec<u32>> = (0..self.len())
+            .map(|i| replacements.remove(&i).unwrap_or_else(|| self.row(i).to_vec()))
+            .collect();
+        *self = Self::from_rows(rows);
+    }
+}
+
+/// Dense vectors of nodes & edges used by solver.
+#[derive(Resource, Debug, Clone, Default)]
+pub struct MetabolicGraph {
+    /// Node entities by dense index
+    pub nodes: Vec<Entity>,
+    pub edges: Vec<Entity>,
+    /// Dense index of each node entity
+    pub index: HashMap<Entity, usize>,
+    /// Flux profile of each node, by dense index
+    pub profiles: Vec<CurrencyVector>,
+    /// Producers each node waits on, by dense index
+    pub dependencies: CsrAdjacency,
+    /// Producers of each currency, sorted by dense index
+    producers: Vec<Vec<u32>>,
+    /// Producers wired into each (consumer, currency) input port
+    wires: HashMap<(Entity, Currency), Vec<Entity>>,
+}
+
+impl MetabolicGraph {
+    /// Build the graph from every node's profile and the flow editor's wires.
+    ///
+    /// Wires only count while both of their ends are nodes.
+    pub fn build(
+        nodes: Vec<(Entity, CurrencyVector)>,
+        edges: Vec<Entity>,
+        wires: impl IntoIterator<Item = FlowEdge>,
+    ) -> Self {
+        let mut graph = Self {
+            edges,
+            producers: vec![Vec::new(); Currency::COUNT],
+            ..default()
+        };
+        for (i, (entity, profile)) in nodes.into_iter().enumerate() {
+            graph.nodes.push(entity);
+            graph.index.insert(entity, i);
+            graph.profiles.push(profile);
+            for currency in currencies_in(profile.produced()) {
+                graph.producers[currency.index()].push(i as u32);
+            }
+        }
+        for wire in wires {
+            graph.wires.entry((wire.to, wire.currency)).or_default().push(wire.from);
+        }
+        graph.dependencies = CsrAdjacency::from_rows((0..graph.profiles.len()).map(|i| graph.link(i)));
+        graph
+    }
+
+    /// Producers node `consumer` depends on: for each currency it consumes, the nodes
+    /// wired into that port, or every producer of the currency if no node is wired in.
+    fn link(&self, consumer: usize) -> Vec<u32> {
+        let mut producers = Vec::new();
+        for currency in currencies_in(self.profiles[consumer].consumed()) {
+            let wired: Vec<u32> = self
+                .wires
+                .get(&(self.nodes[consumer], currency))
+                .into_iter()
+                .flatten()
+                .filter_map(|producer| self.index.get(producer))
+                .map(|&producer| producer as u32)
+                .collect();
+            if wired.is_empty() {
+                producers.extend(&self.producers[currency.index()]);
+            } else {
+                producers.extend(wired);
+            }
+        }
+        producers.retain(|&producer| producer as usize != consumer);
+        producers.sort_unstable();
+        producers.dedup();
+        producers
+    }
+
+    /// Entities `entity` depends on, in dense index order.
+    pub fn dependencies_of(&self, entity: Entity) -> impl Iterator<Item = Entity> + '_ {
+        let row = self.index.get(&entity).map_or(&[][..], |&i| self.dependencies.row(i));
+        row.iter().map(|&producer| self.nodes[producer as usize])
+    }
+
+    /// Every (consumer, producer) dependency.
+    pub fn links(&self) -> impl Iterator<Item = (Entity, Entity)> + '_ {
+        (0..self.dependencies.len()).flat_map(move |consumer| {
+            self.dependencies
+                .row(consumer)
+                .iter()
+                .map(move |&producer| (self.nodes[consumer], self.nodes[producer as usize]))
+        })
+    }
+
+    /// Replace one node's profile, relinking only the rows it affects.
+    ///
+    /// Changed amounts alone leave the adjacency untouched; starting or stopping the
+    /// production of a currency relinks its consumers, and a changed set of consumed
+    /// currencies relinks the node itself. Returns whether any dependency changed.
+    pub fn update_profile(&mut self, entity: Entity, profile: CurrencyVector) -> bool {
+        let Some(&node) = self.index.get(&entity) else {
+            return false;
+        };
+        let old = std::mem::replace(&mut self.profiles[node], profile);
+        let toggled = old.produced() ^ profile.produced();
+        let relink_self = old.consumed() != profile.consumed();
+        if toggled == 0 && !relink_self {
+            return false;
+        }
+
+        for currency in currencies_in(toggled) {
+            let producers = &mut self.producers[currency.index()];
+            match producers.binary_search(&(node as u32)) {
+                Ok(at) => {
+                    producers.remove(at);
+                }
+                Err(at) => producers.insert(at, node as u32),
+            }
+        }
+
+        let affected = (0..self.profiles.len())
+            .filter(|&consumer| {
+                (consumer == node && relink_self)
+                    || (consumer != node && self.profiles[consumer].consumed() & toggled != 0)
+            })
+            .collect();
+        self.relink(affected)
+    }
+
+    /// Consumers with `producer` wired into one of their ports.
+    fn wired_consumers(&self, producer: Entity) -> Vec<usize> {
+        self.wires
+            .iter()
+            .filter(|(_, producers)| producers.contains(&producer))
+            .filter_map(|(&(consumer, _), _)| self.index.get(&consumer).copied())
+            .collect()
+    }
+
+    /// Relink `consumers`, returning whether any of their rows changed.
+    fn relink(&mut self, consumers: Vec<usize>) -> bool {
+        let replacements: HashMap<usize, Vec<u32>> = consumers
+            .into_iter()
+            .map(|consumer| (consumer, self.link(consumer)))
+            .filter(|(consumer, row)| self.dependencies.row(*consumer) != row.as_slice())
+            .collect();
+        let changed = !replacements.is_empty();
+        self.dependencies.replace_rows(replacements);
+        changed
+    }
+
+    /// Add a node at the end of the dense order, linking it to its producers and its
+    /// products to their consumers. A node already in the graph just gets `profile`.
+    /// Returns whether any dependency changed.
+    pub fn add_node(&mut self, entity: Entity, profile: CurrencyVector) -> bool {
+        if self.index.contains_key(&entity) {
+            return self.update_profile(entity, profile);
+        }
+        if self.producers.is_empty() {
+            self.producers = vec![Vec::new(); Currency::COUNT];
+        }
+        let node = self.profiles.len();
+        self.index.insert(entity, node);
+        self.nodes.push(entity);
+        self.profiles.push(CurrencyVector::default());
+        self.dependencies.push_row(Vec::new());
+
+        let linked = self.update_profile(entity, profile);
+        // Wires drawn from this node before it joined the graph now count
+        let mut consumers = self.wired_consumers(entity);
+        consumers.push(node);
+        self.relink(consumers) || linked
+    }
+
+    /// Remove a node and every dependency on it; ports it was wired into fall back to
+    /// automatic routing unless other nodes are wired in too. Later nodes move down one
+    /// index, keeping their order. Returns whether the node was in the graph.
+    pub fn remove_node(&mut self, entity: Entity) -> bool {
+        let Some(&node) = self.index.get(&entity) else {
+            return false;
+        };
+        self.update_profile(entity, CurrencyVector::default());
+
+        // Close the gap
+        let removed = node as u32;
+        self.dependencies.remove_index(node);
+        self.nodes.remove(node);
+        self.profiles.remove(node);
+        self.index.remove(&entity);
+        for i in self.index.values_mut() {
+            if *i > node {
+                *i -= 1;
+            }
+        }
+        for producers in &mut self.producers {
+            for producer in producers.iter_mut() {
+                if *producer > removed {
+                    *producer -= 1;
+                }
+            }
+        }
+
+        let consumers = self.wired_consumers(entity);
+        self.relink(consumers);
+        true
+    }
+
+    /// How this graph differs from `expected`, ignoring node order; `None` if it does not.
+    ///
+    /// Used to verify the patched graph against a full rebuild.
+    pub fn mismatch(&self, expected: &Self) -> Option<String> {
+        if self.index.len() != expected.index.len() {
+            return Some(format!(
+                "{} nodes, expected {}",
+                self.index.len(),
+                expected.index.len()
+            ));
+        }
+        for (&entity, &i) in &self.index {
+            let Some(&j) = expected.index.get(&entity) else {
+                return Some(format!("{} should not be in the graph", entity));
+            };
+            if self.profiles[i] != expected.profiles[j] {
+                return Some(format!(
+                    "{} has profile {:?}, expected {:?}",
+                    entity, self.profiles[i], expected.profiles[j]
+                ));
+            }
+            let mut found: Vec<Entity> = self.dependencies_of(entity).collect();
+            let mut wanted: Vec<Entity> = expected.dependencies_of(entity).collect();
+            found.sort();
+            wanted.sort();
+            if found != wanted {
+                return Some(format!("{} depends on {:?}, expected {:?}", entity, found, wanted));
+            }
+        }
+        None
+    }
+}
@@ -2,10 +2,27 @@ use std::collections::HashMap;
 
 use bevy::prelude::*;
 use bevy::ecs::schedule::ScheduleLabel;
+use serde::{Deserialize, Serialize};
 
 use crate::blocks::genome::{poll_genome_diff, BlockKind, Genome, MetabolicUpdateEvent, GeneState};
 use crate::molecules::Currency;
 
+pub mod cells;
+pub mod compartments;
+pub mod division;
+pub mod graph;
+pub mod solver;
+pub mod toxicity;
+pub mod vitality;
+
+use cells::{simulate_cells, CellMember, CellMetabolism, CellSchedule, CellScope, CellsPlugin};
+use compartments::{ensure_compartments, transport_system, CellCompartments, CompartmentPools, InCompartment};
+use division::{divide_cells, CellDivided, DivisionRules};
+pub use graph::{CsrAdjacency, CurrencyVector, MetabolicGraph};
+use solver::{solve_flux_in_background, solves_in_background, FluxProblem, FluxSolveMode, PendingFluxSolve};
+use toxicity::{apply_metabolic_efficiency, apply_toxicity, MetabolicEfficiency, ToxicityRules};
+use vitality::{metabolism_runs, update_vitality, CellVitality, StarvationTicks, VitalityChanged, VitalityRules};
+
 // --- Components ---
 
 /// Marker component for entities that are part of the metabolic system.
@@ -19,19 +36,10 @@ pub struct FluxProfile(pub HashMap<Currency, f32>);
 
 // --- Resources ---
 
-/// Dense vectors of nodes & edges used by solver.
-#[derive(Resource, Default)]
-pub struct MetabolicGraph {
-    // Placeholder for actual graph data structures (e.g., adjacency list, matrix)
-    pub nodes: Vec<Entity>,
-    pub edges: Vec<Entity>,
-    // Track currency dependencies between blocks
-    pub dependencies: HashMap<Entity, Vec<Entity>>, // entity -> list of entities it depends on
-}
-
 /// Central currency pools managed by the metabolic flow system.
 /// This replaces individual currency resources for flow-based calculations.
-#[derive(Resource, Default, Debug)]
+/// A component of every [`cells::Cell`]; a resource only while a cell is simulated.
+#[derive(Resource, Component, Default, Debug, Clone)]
 pub struct CurrencyPools {
     pub pools: HashMap<Currency, f32>,
 }
@@ -58,6 +66,25 @@ impl CurrencyPools {
         self.get(currency) >= amount
     }
     
+    /// Hash of every pool's exact contents, stable across runs and platforms.
+    ///
+    /// Used to check that a replayed session ends in the same state it was recorded in.
+    pub fn state_hash(&self) -> u64 {
+        let mut pools: Vec<(Currency, f32)> = self.pools.iter().map(|(&c, &v)| (c, v)).collect();
+        pools.sort_by_key(|&(currency, _)| currency);
+
+        // FNV-1a, so the value does not depend on the std hasher's seed or version
+        let mut hash: u64 = 0xcbf2_9ce4_8422_2325;
+        for (currency, amount) in pools {
+            let bytes = (currency as u32).to_le_bytes().into_iter().chain(amount.to_bits().to_le_bytes());
+            for byte in bytes {
+                hash ^= u64::from(byte);
+                hash = hash.wrapping_mul(0x0000_0100_0000_01b3);
+            }
+        }
+        hash
+    }
+
     /// Initialize with default starting amounts
     pub fn with_defaults() -> Self {
         let mut pools = HashMap::new();
@@ -74,10 +101,29 @@ impl CurrencyPools {
     }
 }
 
-/// True when edits require a graph rebuild.
+/// True when edits require a full graph rebuild, such as wires changed in the flow editor.
+/// Added, removed and re-profiled nodes are patched in without it.
 #[derive(Resource, Default)]
 pub struct FlowDirty(pub bool);
 
+/// Default length of one metabolic tick in seconds (4 Hz).
+pub const DEFAULT_METABOLIC_TICK_SECONDS: f64 = 0.25;
+
+/// Length of one metabolic tick in seconds.
+/// Changing it retimes `Time<Fixed>`, which drives every metabolic system.
+#[derive(Resource, Debug, Clone, Copy)]
+pub struct MetabolicTickRate(pub f64);
+
+impl Default for MetabolicTickRate {
+    fn default() -> Self {
+        Self(DEFAULT_METABOLIC_TICK_SECONDS)
+    }
+}
+
+/// Number of metabolic ticks simulated so far.
+#[derive(Resource, Default, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MetabolicTick(pub u64);
+
 /// Per-node flux results with currency-specific changes.
 #[derive(Resource, Default)]
 pub struct FluxResult {
@@ -85,12 +131,15 @@ pub struct FluxResult {
     pub entity_flux: HashMap<Entity, f32>,
     /// Currency changes to be applied: Currency -> total delta
     pub currency_changes: HashMap<Currency, f32>,
+    /// Changes to organelle pools, per compartment entity
+    pub compartment_changes: HashMap<Entity, HashMap<Currency, f32>>,
 }
 
 // --- Components (for ECS representation, mostly for editor/debug) ---
 
 /// Status of a metabolic block, derived from genome expression.
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
 pub enum BlockStatus {
     Active,
     Mutated,
@@ -108,7 +157,7 @@ impl From<GeneState> for BlockStatus {
 }
 
 /// Component for a node in the metabolic graph.
-#[derive(Component)]
+#[derive(Component, Debug, Clone, Serialize, Deserialize)]
 pub struct MetabolicNode {
     pub kind: BlockKind,
     pub status: BlockStatus,
@@ -118,63 +167,217 @@ pub struct MetabolicNode {
 #[derive(Component)]
 pub struct MetabolicEdge;
 
+/// A wire drawn in the flow editor, carrying `currency` from one node's output port to
+/// another node's input port. Lives on a [`MetabolicEdge`] entity.
+///
+/// Once a node has wired inputs for a currency it only waits on those producers for it;
+/// currencies without wires keep being routed automatically.
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct FlowEdge {
+    pub from: Entity,
+    pub to: Entity,
+    pub currency: Currency,
+}
+
 // --- Schedules ---
 
 #[derive(ScheduleLabel, Debug, Clone, PartialEq, Eq, Hash)]
 pub struct MetabolicSchedule;
 
+/// Ordering of metabolic work within [`CellSchedule`]; one pass is one cell's metabolic tick.
+#[derive(SystemSet, Debug, Clone, PartialEq, Eq, Hash)]
+pub enum MetabolicSet {
+    /// Update `CellVitality` and apply toxic damage.
+    Tick,
+    /// Per-block systems that adjust flux profiles or move currencies directly.
+    /// Skipped on ticks where `metabolism_runs` is false.
+    Blocks,
+    /// Graph rebuild, flux solve and currency application (`MetabolicSchedule`).
+    /// Large graphs may be solved in the background; see [`solver`].
+    Flow,
+}
+
 fn run_metabolic_schedule(world: &mut World) {
     world.run_schedule(MetabolicSchedule);
 }
 
+/// Advance the simulation by `ticks` metabolic ticks, independent of wall-clock time.
+///
+/// Runs `FixedUpdate` directly, so block systems and the flux solver execute exactly
+/// as they would in a running app, for every cell; useful for headless tools and tests.
+/// Like Bevy's fixed main loop, each tick advances `Time<Fixed>` by one timestep and
+/// shows it as `Res<Time>`, so time-based systems see the tick length.
+pub fn run_ticks(world: &mut World, ticks: u64) {
+    let previous = world.get_resource::<Time>().copied();
+    for _ in 0..ticks {
+        if let Some(mut fixed) = world.get_resource_mut::<Time<Fixed>>() {
+            let timestep = fixed.timestep();
+            fixed.advance_by(timestep);
+            let tick_time = fixed.as_generic();
+            world.insert_resource(tick_time);
+        }
+        world.run_schedule(FixedUpdate);
+    }
+    if let Some(previous) = previous {
+        world.insert_resource(previous);
+    }
+}
+
 // --- Systems ---
 
+fn advance_metabolic_tick(mut tick: ResMut<MetabolicTick>) {
+    tick.0 += 1;
+}
+
+/// Keep `Time<Fixed>` in step with `MetabolicTickRate`.
+fn sync_tick_rate(rate: Res<MetabolicTickRate>, mut fixed: ResMut<Time<Fixed>>) {
+    fixed.set_timestep_seconds(rate.0);
+}
+
+/// Nodes of every cell, with the cell they belong to.
+type CellNodes<'w, 's> =
+    Query<'w, 's, (Entity, Option<&'static FluxProfile>, Option<&'static CellMember>), With<MetabolicNode>>;
+
+/// Edges of every cell, with their wire and the cell they belong to.
+type CellEdges<'w, 's> =
+    Query<'w, 's, (Entity, Option<&'static FlowEdge>, Option<&'static CellMember>), With<MetabolicEdge>>;
+
+/// Build the graph of the cell being simulated from scratch.
+fn build_cell_graph(scope: &CellScope, nodes: &CellNodes, edges: &CellEdges) -> MetabolicGraph {
+    let nodes = nodes
+        .iter()
+        .filter(|&(_, _, member)| scope.contains(member))
+        .map(|(entity, profile, _)| (entity, profile.map(CurrencyVector::from).unwrap_or_default()))
+        .collect();
+    let edges: Vec<_> = edges
+        .iter()
+        .filter(|&(_, _, member)| scope.contains(member))
+        .collect();
+    MetabolicGraph::build(
+        nodes,
+        edges.iter().map(|&(entity, _, _)| entity).collect(),
+        edges.iter().filter_map(|&(_, wire, _)| wire.copied()),
+    )
+}
+
 pub fn rebuild_graph(
     mut metabolic_graph: ResMut<MetabolicGraph>,
-    query_nodes: Query<Entity, With<MetabolicNode>>,
-    query_edges: Query<Entity, With<MetabolicEdge>>,
-    query_flux_profiles: Query<(Entity, &FluxProfile)>,
+    query_nodes: CellNodes,
+    query_edges: CellEdges,
+    scope: CellScope,
 ) {
-    metabolic_graph.nodes = query_nodes.iter().collect();
-    metabolic_graph.edges = query_edges.iter().collect();
-    
-    // Build dependency graph based on currency flows
-    metabolic_graph.dependencies.clear();
-    
-    // For each node, find which other nodes produce currencies it consumes
-    for (consumer_entity, consumer_flux) in query_flux_profiles.iter() {
-        let mut dependencies = Vec::new();
-        
-        // Find currencies this block consumes (negative flux)
-        let consumed_currencies: Vec<Currency> = consumer_flux.0.iter()
-            .filter(|(_, &amount)| amount < 0.0)
-            .map(|(&currency, _)| currency)
-            .collect();
-        
-        // Find other blocks that produce these currencies
-        for (producer_entity, producer_flux) in query_flux_profiles.iter() {
-            if producer_entity == consumer_entity {
-                continue; // Skip self
-            }
-            
-            // Check if this producer produces any currency the consumer needs
-            for &currency in &consumed_currencies {
-                if let Some(&amount) = producer_flux.0.get(&currency) {
-                    if amount > 0.0 { // Positive flux = production
-                        dependencies.push(producer_entity);
-                        break; // Only need to add dependency once per producer
-                    }
-                }
-            }
-        }
-        
-        metabolic_graph.dependencies.insert(consumer_entity, dependencies);
-    }
+    *metabolic_graph = build_cell_graph(&scope, &query_nodes, &query_edges);
     
     info!("Rebuilding metabolic graph: {} nodes, {} edges, {} dependencies", 
           metabolic_graph.nodes.len(), 
           metabolic_graph.edges.len(),
-          metabolic_graph.dependencies.len());
+          metabolic_graph.dependencies.target_count());
+}
+
+/// Node and profile removals seen since the graph was last patched.
+///
+/// Removal events only live for a couple of frames, while the metabolic tick can skip many,
+/// so they are collected every frame and drained on the next tick.
+#[derive(Resource, Default, Debug)]
+pub struct GraphRemovals {
+    pub nodes: Vec<Entity>,
+    pub profiles: Vec<Entity>,
+}
+
+/// Compare the patched graph with a full rebuild every tick and report any difference.
+/// On by default in debug builds.
+#[derive(Resource, Debug, Clone, Copy)]
+pub struct VerifyGraph(pub bool);
+
+impl Default for VerifyGraph {
+    fn default() -> Self {
+        Self(cfg!(debug_assertions))
+    }
+}
+
+fn collect_graph_removals(
+    mut removals: ResMut<GraphRemovals>,
+    mut removed_nodes: RemovedComponents<MetabolicNode>,
+    mut removed_profiles: RemovedComponents<FluxProfile>,
+) {
+    if removed_nodes.is_empty() && removed_profiles.is_empty() {
+        return;
+    }
+    removals.nodes.extend(removed_nodes.read());
+    removals.profiles.extend(removed_profiles.read());
+}
+
+/// Nodes that joined the graph or changed their profile.
+type NodeChanged = (With<MetabolicNode>, Or<(Added<MetabolicNode>, Changed<FluxProfile>)>);
+
+/// Bring the graphs up to date with nodes added or removed and profiles changed since the
+/// last tick, relinking only the affected dependencies.
+///
+/// A change is seen by whichever cell runs first afterwards, so it is applied to the graph
+/// of the cell the node belongs to, which may be stored in that cell's [`CellMetabolism`].
+#[allow(clippy::too_many_arguments)]
+pub fn patch_graph(
+    mut metabolic_graph: ResMut<MetabolicGraph>,
+    mut removals: ResMut<GraphRemovals>,
+    mut removed_nodes: RemovedComponents<MetabolicNode>,
+    mut removed_profiles: RemovedComponents<FluxProfile>,
+    query_changed: Query<(Entity, Option<&FluxProfile>, Option<&CellMember>), NodeChanged>,
+    query_nodes: Query<(Option<&FluxProfile>, Option<&CellMember>), With<MetabolicNode>>,
+    mut cells: Query<&mut CellMetabolism>,
+    scope: CellScope,
+) {
+    // Ticks run without a frame in between (`run_ticks`) see removals here first
+    let removed_nodes: Vec<Entity> = removals.nodes.drain(..).chain(removed_nodes.read()).collect();
+    let removed_profiles: Vec<Entity> = removals.profiles.drain(..).chain(removed_profiles.read()).collect();
+
+    for entity in removed_nodes {
+        if !query_nodes.contains(entity) {
+            // The despawned node's membership is gone with it, so try every graph
+            metabolic_graph.remove_node(entity);
+            for mut cell in &mut cells {
+                cell.graph.remove_node(entity);
+            }
+        }
+    }
+    let mut patch = |member: Option<&CellMember>, apply: &mut dyn FnMut(&mut MetabolicGraph)| {
+        if scope.contains(member) {
+            apply(&mut metabolic_graph);
+        } else if let Some(mut cell) = member.and_then(|member| cells.get_mut(member.0).ok()) {
+            apply(&mut cell.graph);
+        }
+    };
+
+    for entity in removed_profiles {
+        if let Ok((None, member)) = query_nodes.get(entity) {
+            patch(member, &mut |graph| {
+                graph.update_profile(entity, CurrencyVector::default());
+            });
+        }
+    }
+    for (entity, profile, member) in query_changed.iter() {
+        let vector = profile.map(CurrencyVector::from).unwrap_or_default();
+        patch(member, &mut |graph| {
+            graph.add_node(entity, vector);
+        });
+    }
+}
+
+fn verifying_graph(verify: Res<VerifyGraph>) -> bool {
+    verify.0
+}
+
+/// Debug check: the patched graph must match a full rebuild.
+pub fn verify_graph(
+    metabolic_graph: Res<MetabolicGraph>,
+    query_nodes: CellNodes,
+    query_edges: CellEdges,
+    scope: CellScope,
+) {
+    let rebuilt = build_cell_graph(&scope, &query_nodes, &query_edges);
+    if let Some(mismatch) = metabolic_graph.mismatch(&rebuilt) {
+        error!("Metabolic graph differs from a full rebuild: {}", mismatch);
+        debug_assert!(false, "Metabolic graph differs from a full rebuild: {}", mismatch);
+    }
 }
 
 pub fn solve_flux_system(
@@ -182,100 +385,56 @@ pub fn solve_flux_system(
     mut flux_result: ResMut<FluxResult>,
     currency_pools: Res<CurrencyPools>,
     query_blocks: Query<(&MetabolicNode, &FluxProfile)>,
+    query_compartments: Query<&InCompartment>,
+    query_organelles: Query<&CompartmentPools>,
 ) {
     info!("Solving metabolic flux for {} nodes and {} edges...", metabolic_graph.nodes.len(), metabolic_graph.edges.len());
     
-    flux_result.entity_flux.clear();
-    flux_result.currency_changes.clear();
-    
-    // Topologically sort nodes to respect dependencies
-    let sorted_nodes = topological_sort(&metabolic_graph);
-    
-    for node_entity in sorted_nodes {
-        if let Ok((node, flux_profile)) = query_blocks.get(node_entity) {
-            let mut total_flux_for_node = 0.0;
-            let mut can_execute = true;
-
-            // Check if all required currencies are available
-            for (currency, &amount) in flux_profile.0.iter() {
-                if amount < 0.0 { // Consumption
-                    let required = -amount;
-                    // Apply BlockStatus modifiers to required amount
-                    let modified_required = match node.status {
-                        BlockStatus::Active => required,
-                        BlockStatus::Mutated => required * 0.5,
-                        BlockStatus::Silent => 0.0,
-                    };
-                    
-                    if modified_required > 0.0 {
-                        let available = currency_pools.get(*currency) + 
-                                       flux_result.currency_changes.get(currency).unwrap_or(&0.0);
-                        if available < modified_required {
-                            can_execute = false;
-                            break;
-                        }
-                    }
-                }
-            }
-
-            if can_execute {
-                // Apply flux changes
-                for (currency, &amount) in flux_profile.0.iter() {
-                    let modified_amount = match node.status {
-                        BlockStatus::Active => amount,
-                        BlockStatus::Mutated => amount * 0.5,
-                        BlockStatus::Silent => 0.0,
-                    };
-                    
-                    if modified_amount != 0.0 {
-                        *flux_result.currency_changes.entry(*currency).or_insert(0.0) += modified_amount;
-                        total_flux_for_node += modified_amount;
-                    }
-                }
-            }
-            
-            flux_result.entity_flux.insert(node_entity, total_flux_for_node);
-        }
-    }
+    let problem = FluxProblem::capture(
+        &metabolic_graph,
+        &currency_pools,
+        &query_blocks,
+        &query_compartments,
+        &query_organelles,
+    );
+    *flux_result = solver::solve(&problem);
 }
 
 /// Topological sort of metabolic nodes respecting dependencies
 fn topological_sort(graph: &MetabolicGraph) -> Vec<Entity> {
     let mut sorted = Vec::new();
-    let mut visited = std::collections::HashSet::new();
-    let mut visiting = std::collections::HashSet::new();
+    let mut visited = vec![false; graph.nodes.len()];
+    let mut visiting = vec![false; graph.nodes.len()];
     
     fn visit(
-        node: Entity,
+        node: usize,
         graph: &MetabolicGraph,
-        visited: &mut std::collections::HashSet<Entity>,
-        visiting: &mut std::collections::HashSet<Entity>,
+        visited: &mut [bool],
+        visiting: &mut [bool],
         sorted: &mut Vec<Entity>,
     ) {
-        if visited.contains(&node) {
+        if visited[node] {
             return;
         }
-        if visiting.contains(&node) {
+        if visiting[node] {
             // Cycle detected, just skip for now
             return;
         }
         
-        visiting.insert(node);
+        visiting[node] = true;
         
         // Visit dependencies first
-        if let Some(deps) = graph.dependencies.get(&node) {
-            for &dep in deps {
-                visit(dep, graph, visited, visiting, sorted);
-            }
+        for &dep in graph.dependencies.row(node) {
+            visit(dep as usize, graph, visited, visiting, sorted);
         }
         
-        visiting.remove(&node);
-        visited.insert(node);
-        sorted.push(node);
+        visiting[node] = false;
+        visited[node] = true;
+        sorted.push(graph.nodes[node]);
     }
     
     // Visit all nodes
-    for &node in &graph.nodes {
+    for node in 0..graph.nodes.len() {
         visit(node, graph, &mut visited, &mut visiting, &mut sorted);
     }
     
@@ -286,6 +445,7 @@ fn topological_sort(graph: &MetabolicGraph) -> Vec<Entity> {
 pub fn apply_currency_changes_system(
     flux_result: Res<FluxResult>,
     mut currency_pools: ResMut<CurrencyPools>,
+    mut query_organelles: Query<&mut CompartmentPools>,
 ) {
     for (&currency, &delta) in flux_result.currency_changes.iter() {
         if delta != 0.0 {
@@ -294,6 +454,16 @@ pub fn apply_currency_changes_system(
                   currency, delta, currency_pools.get(currency));
         }
     }
+    for (&compartment, changes) in flux_result.compartment_changes.iter() {
+        let Ok(mut organelle) = query_organelles.get_mut(compartment) else {
+            continue;
+        };
+        for (&currency, &delta) in changes.iter() {
+            if delta != 0.0 {
+                organelle.0.modify(currency, delta);
+            }
+        }
+    }
 }
 
 pub fn apply_flux_results_system(
@@ -319,11 +489,13 @@ pub fn apply_flux_results_system(
 pub fn on_genome_diff(
     mut diff_reader: EventReader<MetabolicUpdateEvent>,
     genome: Res<Genome>,
-    mut nodes: Query<&mut MetabolicNode>,
+    mut nodes: Query<(&mut MetabolicNode, Option<&CellMember>)>,
     mut dirty: ResMut<FlowDirty>,
+    scope: CellScope,
 ) {
-    if diff_reader.read().next().is_some() {
-        for mut node in &mut nodes {
+    // Drain every update, so none is left over for the next cell simulated
+    if diff_reader.read().count() > 0 {
+        for (mut node, _) in nodes.iter_mut().filter(|(_, member)| scope.contains(*member)) {
             node.status = genome.get_gene_state(&node.kind)
                 .cloned()
                 .unwrap_or(GeneState::Silent)
@@ -339,22 +511,60 @@ pub struct MetabolicFlowPlugin;
 
 impl Plugin for MetabolicFlowPlugin {
     fn build(&self, app: &mut App) {
+        CellsPlugin::add_to(app);
         app
             .init_resource::<MetabolicGraph>()
             .init_resource::<FlowDirty>()
             .init_resource::<FluxResult>()
+            .init_resource::<MetabolicTickRate>()
+            .init_resource::<MetabolicTick>()
+            .init_resource::<CellVitality>()
+            .init_resource::<VitalityRules>()
+            .init_resource::<StarvationTicks>()
+            .init_resource::<ToxicityRules>()
+            .init_resource::<MetabolicEfficiency>()
+            .init_resource::<DivisionRules>()
+            .init_resource::<GraphRemovals>()
+            .init_resource::<CellCompartments>()
+            .init_resource::<VerifyGraph>()
+            .init_resource::<FluxSolveMode>()
+            .init_resource::<PendingFluxSolve>()
+            .add_event::<VitalityChanged>()
+            .add_event::<CellDivided>()
+            // Moved onto the player's cell at startup
             .insert_resource(CurrencyPools::with_defaults())
             .add_schedule(Schedule::new(MetabolicSchedule))
+            // Dormant cells only metabolise every few ticks, dead ones not at all
+            .configure_sets(
+                CellSchedule,
+                (MetabolicSet::Blocks, MetabolicSet::Flow).run_if(metabolism_runs),
+            )
+            .add_systems(Startup, ensure_compartments)
+            .add_systems(CellSchedule, transport_system.in_set(MetabolicSet::Blocks))
             .add_systems(PreUpdate, poll_genome_diff)
+            .add_systems(Last, collect_graph_removals)
             .add_systems(MetabolicSchedule, (
                 on_genome_diff,
                 apply_deferred,
                 rebuild_graph.run_if(resource_changed::<FlowDirty>),
-                solve_flux_system,
+                patch_graph,
+                verify_graph.run_if(verifying_graph),
+                solve_flux_system.run_if(not(solves_in_background)),
+                solve_flux_in_background.run_if(solves_in_background),
+                apply_metabolic_efficiency,
                 apply_currency_changes_system,
                 apply_flux_results_system,
             ).chain()) // Chain ensures proper ordering
-            .add_systems(Update, run_metabolic_schedule)
-            .insert_resource(Time::<Fixed>::from_seconds(0.25));
+            .add_systems(
+                CellSchedule,
+                (update_vitality, apply_toxicity).chain().in_set(MetabolicSet::Tick),
+            )
+            .add_systems(
+                CellSchedule,
+                (run_metabolic_schedule, divide_cells).chain().in_set(MetabolicSet::Flow),
+            )
+            .add_systems(FixedUpdate, advance_metabolic_tick.before(simulate_cells))
+            .add_systems(PreUpdate, sync_tick_rate.run_if(resource_changed::<MetabolicTickRate>))
+            .insert_resource(Time::<Fixed>::from_seconds(DEFAULT_METABOLIC_TICK_SECONDS));
     }
 }
\ No newline at end of file
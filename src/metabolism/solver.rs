@@ -0,0 +1,267 @@
+//! # Flux Solver
+//!
+//! The flux solve as plain data. [`FluxProblem::capture`] copies everything the solver
+//! reads out of the ECS — the topologically sorted nodes of the [`MetabolicGraph`], their
+//! [`FluxProfile`]s and the [`CurrencyPools`] — so [`solve`] can run anywhere, including on
+//! a background thread.
+//!
+//! With [`FluxSolveMode::Background`], graphs with at least `min_nodes` nodes are solved on
+//! the `AsyncComputeTaskPool`: a tick starts a task and a later tick applies its result,
+//! so large colonies never stall a frame. Until a task finishes, no solved flux is applied,
+//! and a finished result is scaled down to what the pools still hold when it lands.
+//! Smaller graphs, and every graph in [`FluxSolveMode::Synchronous`], are solved in place
+//! by `solve_flux_system`, which keeps headless runs and tests deterministic.
+
+use std::collections::HashMap;
+
+use bevy::prelude::*;
+use bevy::tasks::{block_on, poll_once, AsyncComputeTaskPool, Task};
+
+use super::compartments::{CompartmentPools, InCompartment};
+use super::{
+    topological_sort, BlockStatus, CurrencyPools, CurrencyVector, FluxProfile, FluxResult,
+    MetabolicGraph, MetabolicNode,
+};
+use crate::molecules::Currency;
+
+/// Where the flux solve runs.
+#[derive(Resource, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FluxSolveMode {
+    /// Always solve on the main schedule, applying the result in the same tick.
+    Synchronous,
+    /// Solve graphs of at least `min_nodes` nodes on a background task.
+    Background { min_nodes: usize },
+}
+
+impl Default for FluxSolveMode {
+    fn default() -> Self {
+        Self::Background { min_nodes: 1000 }
+    }
+}
+
+/// One node of a [`FluxProblem`], in solve order.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ProblemNode {
+    pub entity: Entity,
+    pub status: BlockStatus,
+    pub flux: CurrencyVector,
+    /// Organelle whose pools the node works on; `None` for the cytosol
+    pub compartment: Option<Entity>,
+}
+
+/// Snapshot of everything the flux solver needs, detached from the `World`.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct FluxProblem {
+    /// Nodes with dependencies before their dependents
+    pub nodes: Vec<ProblemNode>,
+    /// Cytosolic currency amounts at the time of capture
+    pub pools: HashMap<Currency, f32>,
+    /// Currency amounts of every organelle a node works in
+    pub compartment_pools: HashMap<Entity, HashMap<Currency, f32>>,
+}
+
+impl FluxProblem {
+    /// Copy the graph, flux profiles and pools out of the ECS.
+    ///
+    /// Blocks placed in a compartment without pools of its own work in the cytosol.
+    pub fn capture(
+        graph: &MetabolicGraph,
+        pools: &CurrencyPools,
+        blocks: &Query<(&MetabolicNode, &FluxProfile)>,
+        compartments: &Query<&InCompartment>,
+        organelles: &Query<&CompartmentPools>,
+    ) -> Self {
+        let mut compartment_pools = HashMap::new();
+        let nodes = topological_sort(graph)
+            .into_iter()
+            .filter_map(|entity| {
+                let (node, profile) = blocks.get(entity).ok()?;
+                let compartment = compartments.get(entity).ok().and_then(|&InCompartment(compartment)| {
+                    let organelle = organelles.get(compartment).ok()?;
+                    compartment_pools
+                        .entry(compartment)
+                        .or_insert_with(|| organelle.0.pools.clone());
+                    Some(compartment)
+                });
+                Some(ProblemNode {
+                    entity,
+                    status: node.status,
+                    flux: CurrencyVector::from(profile),
+                    compartment,
+                })
+            })
+            .collect();
+        Self {
+            nodes,
+            pools: pools.pools.clone(),
+            compartment_pools,
+        }
+    }
+}
+
+/// Flux through a node with `status` for a profile entry of `amount`.
+fn scaled(status: BlockStatus, amount: f32) -> f32 {
+    match status {
+        BlockStatus::Active => amount,
+        BlockStatus::Mutated => amount * 0.5,
+        BlockStatus::Silent => 0.0,
+    }
+}
+
+/// Solve `problem`: each node in order runs if its compartment's pools, plus what
+/// earlier nodes produced there this tick, cover everything it consumes.
+pub fn solve(problem: &FluxProblem) -> FluxResult {
+    let mut result = FluxResult::default();
+    let empty = HashMap::new();
+
+    for node in &problem.nodes {
+        let (pools, changes) = match node.compartment {
+            Some(compartment) => (
+                problem.compartment_pools.get(&compartment).unwrap_or(&empty),
+                result.compartment_changes.entry(compartment).or_default(),
+            ),
+            None => (&problem.pools, &mut result.currency_changes),
+        };
+
+        // Check if all required currencies are available
+        let can_execute = node.flux.iter().all(|(currency, amount)| {
+            let required = if amount < 0.0 { scaled(node.status, -amount) } else { 0.0 };
+            if required <= 0.0 {
+                return true;
+            }
+            let available = pools.get(&currency).copied().unwrap_or(0.0)
+                + changes.get(&currency).copied().unwrap_or(0.0);
+            available >= required
+        });
+
+        let mut total_flux_for_node = 0.0;
+        if can_execute {
+            for (currency, amount) in node.flux.iter() {
+                let modified_amount = scaled(node.status, amount);
+                if modified_amount != 0.0 {
+                    *changes.entry(currency).or_insert(0.0) += modified_amount;
+                    total_flux_for_node += modified_amount;
+                }
+            }
+        }
+        result.entity_flux.insert(node.entity, total_flux_for_node);
+    }
+
+    result
+}
+
+/// Largest share of `changes`, up to all of it, that `pools` can still pay for.
+fn affordable_fraction(pools: &HashMap<Currency, f32>, changes: &HashMap<Currency, f32>) -> f32 {
+    changes
+        .iter()
+        .filter(|&(_, &delta)| delta < 0.0)
+        .map(|(currency, &delta)| (pools.get(currency).copied().unwrap_or(0.0).max(0.0) / -delta).min(1.0))
+        .fold(1.0, f32::min)
+}
+
+/// Scale `result` down to what the current pools can still pay for.
+///
+/// A background result was solved against pools captured ticks earlier, which block
+/// systems may have drained since; applied in full it would overdraw them while still
+/// adding every product. The whole result is scaled by the same fraction, so products
+/// shrink with what was consumed. Compartments missing from `compartment_pools` are left
+/// out of the check.
+pub fn fit_to_pools(
+    result: &mut FluxResult,
+    pools: &HashMap<Currency, f32>,
+    compartment_pools: &HashMap<Entity, HashMap<Currency, f32>>,
+) {
+    let fraction = result
+        .compartment_changes
+        .iter()
+        .filter_map(|(compartment, changes)| {
+            compartment_pools.get(compartment).map(|pools| affordable_fraction(pools, changes))
+        })
+        .fold(affordable_fraction(pools, &result.currency_changes), f32::min);
+    if fraction >= 1.0 {
+        return;
+    }
+    let changes = result
+        .currency_changes
+        .values_mut()
+        .chain(result.compartment_changes.values_mut().flat_map(|changes| changes.values_mut()))
+        .chain(result.entity_flux.values_mut());
+    for amount in changes {
+        *amount *= fraction;
+    }
+}
+
+/// The background solve in flight, if any.
+#[derive(Resource, Default)]
+pub struct PendingFluxSolve(pub Option<Task<FluxResult>>);
+
+/// Run condition: true when this tick's flux is solved on a background task.
+///
+/// A task still in flight is always collected, even if the graph has since shrunk or the
+/// mode changed, so its result is never lost.
+pub fn solves_in_background(
+    mode: Res<FluxSolveMode>,
+    graph: Res<MetabolicGraph>,
+    pending: Res<PendingFluxSolve>,
+) -> bool {
+    pending.0.is_some() || wants_background(*mode, &graph)
+}
+
+fn wants_background(mode: FluxSolveMode, graph: &MetabolicGraph) -> bool {
+    match mode {
+        FluxSolveMode::Synchronous => false,
+        FluxSolveMode::Background { min_nodes } => graph.nodes.len() >= min_nodes,
+    }
+}
+
+/// Collect a finished background solve into `FluxResult`, scaled down to what the pools
+/// still hold (see [`fit_to_pools`]), then start the next one.
+///
+/// While the task is still running `FluxResult` stays empty, so nothing is applied.
+#[allow(clippy::too_many_arguments)]
+pub fn solve_flux_in_background(
+    mode: Res<FluxSolveMode>,
+    metabolic_graph: Res<MetabolicGraph>,
+    mut flux_result: ResMut<FluxResult>,
+    currency_pools: Res<CurrencyPools>,
+    query_blocks: Query<(&MetabolicNode, &FluxProfile)>,
+    query_compartments: Query<&InCompartment>,
+    query_organelles: Query<&CompartmentPools>,
+    mut pending: ResMut<PendingFluxSolve>,
+) {
+    flux_result.entity_flux.clear();
+    flux_result.currency_changes.clear();
+    flux_result.compartment_changes.clear();
+
+    if let Some(task) = pending.0.as_mut() {
+        match block_on(poll_once(task)) {
+            Some(mut solved) => {
+                let compartment_pools = solved
+                    .compartment_changes
+                    .keys()
+                    .filter_map(|&compartment| {
+                        let organelle = query_organelles.get(compartment).ok()?;
+                        Some((compartment, organelle.0.pools.clone()))
+                    })
+                    .collect();
+                fit_to_pools(&mut solved, &currency_pools.pools, &compartment_pools);
+                *flux_result = solved;
+                pending.0 = None;
+            }
+            None => return,
+        }
+    }
+
+    if wants_background(*mode, &metabolic_graph) {
+        let problem = FluxProblem::capture(
+            &metabolic_graph,
+            &currency_pools,
+            &query_blocks,
+            &query_compartments,
+            &query_organelles,
+        );
+        debug!("Solving metabolic flux for {} nodes in the background", problem.nodes.len());
+        let pool = AsyncComputeTaskPool::get_or_init(Default::default);
+        pending.0 = Some(pool.spawn(async move { solve(&problem) }));
+    }
+}
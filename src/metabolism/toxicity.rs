@@ -0,0 +1,192 @@
+//! # Toxicity
+//!
+//! Organic waste and free fatty acids harm the cell once they pile up. Every metabolic tick
+//! each [`CellHealth`] loses [`ToxicityRules::damage_per_unit`] for every unit a toxic pool
+//! holds above its threshold: [`ToxicityRules::waste_threshold`] for `OrganicWaste`, the
+//! [`LipidToxicityThreshold`] for `FreeFattyAcids` (a threshold of zero counts as unset).
+//! Without toxic excess, health slowly regenerates.
+//!
+//! A badly damaged cell works worse. Once its health drops below
+//! [`ToxicityRules::impaired_below`], the least healthy cell lowers the
+//! [`MetabolicEfficiency`] that scales every flux the solver produces, and raises
+//! [`MutationConfig::rate_multiplier`].
+//! A cell whose health reaches zero dies. Exporting waste and storing fat into beads are
+//! how the cell keeps these pools in check.
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use super::cells::{CellMember, CellScope};
+use super::vitality::{CellVitality, VitalityChanged};
+use super::{CurrencyPools, FluxResult};
+use crate::blocks::genome::MutationConfig;
+use crate::molecules::{Currency, LipidToxicityThreshold};
+
+/// Structural integrity of a cell; every [`CellMass`](crate::molecules::CellMass) has one.
+#[derive(Component, Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct CellHealth {
+    pub current: f32,
+    pub max: f32,
+}
+
+impl Default for CellHealth {
+    fn default() -> Self {
+        Self {
+            current: 100.0,
+            max: 100.0,
+        }
+    }
+}
+
+impl CellHealth {
+    /// Health as a fraction of its maximum, in `0.0..=1.0`.
+    pub fn fraction(&self) -> f32 {
+        if self.max <= 0.0 {
+            return 0.0;
+        }
+        (self.current / self.max).clamp(0.0, 1.0)
+    }
+}
+
+/// How toxic pools damage cells and what that damage costs them.
+#[derive(Resource, Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ToxicityRules {
+    /// Organic waste above this amount is toxic
+    pub waste_threshold: f32,
+    /// Health lost per tick for each unit of toxic excess
+    pub damage_per_unit: f32,
+    /// Health regained per tick while nothing is toxic
+    pub regeneration: f32,
+    /// Cells above this fraction of their health suffer no ill effects yet
+    pub impaired_below: f32,
+    /// Efficiency of a cell with no health left
+    pub min_efficiency: f32,
+    /// Mutation rate multiplier of a cell with no health left
+    pub max_mutation_multiplier: f32,
+}
+
+impl Default for ToxicityRules {
+    fn default() -> Self {
+        Self {
+            waste_threshold: 50.0,
+            damage_per_unit: 0.002,
+            regeneration: 0.05,
+            impaired_below: 0.75,
+            min_efficiency: 0.25,
+            max_mutation_multiplier: 5.0,
+        }
+    }
+}
+
+/// Fraction of its normal flux every metabolic block achieves.
+#[derive(Resource, Debug, Clone, Copy, PartialEq)]
+pub struct MetabolicEfficiency(pub f32);
+
+impl Default for MetabolicEfficiency {
+    fn default() -> Self {
+        Self(1.0)
+    }
+}
+
+/// Total amount by which the toxic pools exceed their thresholds.
+pub fn toxic_excess(pools: &CurrencyPools, rules: &ToxicityRules, lipid_threshold: f32) -> f32 {
+    let waste = (pools.get(Currency::OrganicWaste) - rules.waste_threshold).max(0.0);
+    let lipids = if lipid_threshold > 0.0 {
+        (pools.get(Currency::FreeFattyAcids) - lipid_threshold).max(0.0)
+    } else {
+        0.0
+    };
+    waste + lipids
+}
+
+/// How impaired a cell at `health_fraction` is: 0 above `impaired_below`, 1 with no health.
+pub fn impairment(health_fraction: f32, rules: &ToxicityRules) -> f32 {
+    if rules.impaired_below <= 0.0 {
+        return 0.0;
+    }
+    (1.0 - health_fraction / rules.impaired_below).clamp(0.0, 1.0)
+}
+
+/// Block efficiency of a cell at `health_fraction`.
+pub fn efficiency_at(health_fraction: f32, rules: &ToxicityRules) -> f32 {
+    1.0 - (1.0 - rules.min_efficiency) * impairment(health_fraction, rules)
+}
+
+/// Mutation rate multiplier of a cell at `health_fraction`.
+pub fn mutation_multiplier_at(health_fraction: f32, rules: &ToxicityRules) -> f32 {
+    1.0 + (rules.max_mutation_multiplier - 1.0) * impairment(health_fraction, rules)
+}
+
+/// Damage or heal every health of the cell being simulated, then derive efficiency,
+/// mutation rate and death from the least healthy one.
+#[allow(clippy::too_many_arguments)]
+pub fn apply_toxicity(
+    pools: Res<CurrencyPools>,
+    rules: Res<ToxicityRules>,
+    lipid_threshold: Option<Res<LipidToxicityThreshold>>,
+    mut cells: Query<(&mut CellHealth, Option<&CellMember>)>,
+    scope: CellScope,
+    mut efficiency: ResMut<MetabolicEfficiency>,
+    mut vitality: ResMut<CellVitality>,
+    mut changes: EventWriter<VitalityChanged>,
+    mutation_config: Option<ResMut<MutationConfig>>,
+) {
+    let excess = toxic_excess(&pools, &rules, lipid_threshold.map_or(0.0, |t| t.0));
+    let mut weakest: Option<f32> = None;
+    for (mut health, member) in &mut cells {
+        if !scope.contains(member) {
+            continue;
+        }
+        health.current = if excess > 0.0 {
+            (health.current - excess * rules.damage_per_unit).max(0.0)
+        } else {
+            (health.current + rules.regeneration).min(health.max)
+        };
+        let fraction = health.fraction();
+        weakest = Some(weakest.map_or(fraction, |w: f32| w.min(fraction)));
+    }
+
+    // Without any cells nothing can be damaged
+    let fraction = weakest.unwrap_or(1.0);
+    let next = MetabolicEfficiency(efficiency_at(fraction, &rules));
+    if *efficiency != next {
+        *efficiency = next;
+    }
+    if let Some(mut config) = mutation_config {
+        let multiplier = mutation_multiplier_at(fraction, &rules);
+        if config.rate_multiplier != multiplier {
+            config.rate_multiplier = multiplier;
+        }
+    }
+
+    if weakest == Some(0.0) && *vitality != CellVitality::Dead {
+        warn!("Cell destroyed by toxic build-up");
+        changes.send(VitalityChanged {
+            from: *vitality,
+            to: CellVitality::Dead,
+        });
+        *vitality = CellVitality::Dead;
+    }
+}
+
+/// Scale the solved flux by [`MetabolicEfficiency`] before it reaches the pools.
+pub fn apply_metabolic_efficiency(
+    efficiency: Res<MetabolicEfficiency>,
+    mut flux_result: ResMut<FluxResult>,
+) {
+    if efficiency.0 >= 1.0 {
+        return;
+    }
+    for flux in flux_result.entity_flux.values_mut() {
+        *flux *= efficiency.0;
+    }
+    for delta in flux_result.currency_changes.values_mut() {
+        *delta *= efficiency.0;
+    }
+    for changes in flux_result.compartment_changes.values_mut() {
+        for delta in changes.values_mut() {
+            *delta *= efficiency.0;
+        }
+    }
+}
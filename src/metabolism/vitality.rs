@@ -0,0 +1,137 @@
+//! # Cell Vitality
+//!
+//! Whether the cell has the energy to keep its metabolism running. [`CellVitality`] is
+//! derived from the [`CurrencyPools`] at the start of every metabolic tick:
+//!
+//! * **Active** – ATP is comfortably above [`VitalityRules::stressed_atp`].
+//! * **Stressed** – ATP is running low; metabolism still runs at full speed.
+//! * **Dormant** – an essential pool has been empty for [`VitalityRules::dormancy_ticks`]
+//!   ticks in a row. Block systems and the flux solver then only run every
+//!   [`VitalityRules::dormant_interval`] ticks (the "dormant skip" of `flowmap_design.md`),
+//!   and the cell wakes once ATP climbs back to [`VitalityRules::recovery_atp`].
+//! * **Dead** – an essential pool stayed empty for [`VitalityRules::death_ticks`] ticks in a
+//!   row. Metabolism stops for good; only loading a snapshot brings the cell back.
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use super::{CurrencyPools, MetabolicTick};
+use crate::molecules::Currency;
+
+/// How alive the cell is, updated once per metabolic tick.
+#[derive(
+    Resource, Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum CellVitality {
+    #[default]
+    Active,
+    Stressed,
+    Dormant,
+    Dead,
+}
+
+/// Thresholds that move the cell between [`CellVitality`] states.
+#[derive(Resource, Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct VitalityRules {
+    /// Below this much ATP the cell is stressed
+    pub stressed_atp: f32,
+    /// Consecutive starving ticks before the cell goes dormant
+    pub dormancy_ticks: u32,
+    /// A dormant cell wakes once ATP reaches this amount
+    pub recovery_atp: f32,
+    /// While dormant, metabolism runs once every this many ticks; 0 skips it entirely
+    pub dormant_interval: u64,
+    /// Consecutive ticks an essential pool may stay empty before the cell dies; 0 never dies
+    pub death_ticks: u32,
+    /// Pools the cell cannot live without
+    pub essential: Vec<Currency>,
+}
+
+impl Default for VitalityRules {
+    fn default() -> Self {
+        Self {
+            stressed_atp: 20.0,
+            dormancy_ticks: 8,
+            recovery_atp: 5.0,
+            dormant_interval: 4,
+            // One minute at the default tick rate
+            death_ticks: 240,
+            essential: vec![Currency::ATP],
+        }
+    }
+}
+
+/// Consecutive ticks that at least one essential pool has been empty.
+#[derive(Resource, Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct StarvationTicks(pub u32);
+
+/// Sent whenever [`CellVitality`] changes.
+#[derive(Event, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct VitalityChanged {
+    pub from: CellVitality,
+    pub to: CellVitality,
+}
+
+/// The state that follows `current` given the ATP level and [`StarvationTicks`].
+pub fn next_vitality(
+    current: CellVitality,
+    atp: f32,
+    starvation_ticks: u32,
+    rules: &VitalityRules,
+) -> CellVitality {
+    if current == CellVitality::Dead
+        || (rules.death_ticks > 0 && starvation_ticks >= rules.death_ticks)
+    {
+        return CellVitality::Dead;
+    }
+    let still_dormant = current == CellVitality::Dormant && atp < rules.recovery_atp;
+    if starvation_ticks >= rules.dormancy_ticks || still_dormant {
+        CellVitality::Dormant
+    } else if atp < rules.stressed_atp {
+        CellVitality::Stressed
+    } else {
+        CellVitality::Active
+    }
+}
+
+/// Count starvation and move [`CellVitality`] to its next state.
+pub fn update_vitality(
+    pools: Res<CurrencyPools>,
+    rules: Res<VitalityRules>,
+    mut starvation: ResMut<StarvationTicks>,
+    mut vitality: ResMut<CellVitality>,
+    mut changes: EventWriter<VitalityChanged>,
+) {
+    let starving = rules
+        .essential
+        .iter()
+        .any(|&currency| pools.get(currency) <= 0.0);
+    starvation.0 = if starving { starvation.0 + 1 } else { 0 };
+
+    let next = next_vitality(*vitality, pools.get(Currency::ATP), starvation.0, &rules);
+    if next != *vitality {
+        info!("Cell vitality: {:?} -> {:?}", *vitality, next);
+        changes.send(VitalityChanged {
+            from: *vitality,
+            to: next,
+        });
+        *vitality = next;
+    }
+}
+
+/// Run condition for block systems and the flux solver.
+pub fn metabolism_runs(
+    vitality: Res<CellVitality>,
+    rules: Res<VitalityRules>,
+    tick: Res<MetabolicTick>,
+) -> bool {
+    match *vitality {
+        CellVitality::Active | CellVitality::Stressed => true,
+        CellVitality::Dormant => {
+            rules.dormant_interval > 0 && tick.0.is_multiple_of(rules.dormant_interval)
+        }
+        CellVitality::Dead => false,
+    }
+}
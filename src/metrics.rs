@@ -0,0 +1,350 @@
+//! # Metrics
+//!
+//! Samples the player's cell once per metabolic tick into a bounded [`MetricsHistory`]:
+//! every currency pool, the flux of each metabolic node (from [`FluxResult`]), the state
+//! of every gene and the total [`CellMass`]. Samples are taken after the flux has been
+//! applied, so tick `n` holds the state at the end of that tick.
+//!
+//! The history can be queried over a window of ticks and exported as CSV or JSON lines.
+//! It is recorded in headless apps too, so tools and CI can archive the traces:
+//!
+//! ```rust,no_run
+//! use metabolistic3d::metabolism::run_ticks;
+//! use metabolistic3d::metrics::{Metric, MetricsHistory};
+//! use metabolistic3d::molecules::Currency;
+//! use metabolistic3d::MetabolisticApp;
+//!
+//! let mut app = MetabolisticApp::new_headless();
+//! app.update();
+//! run_ticks(app.world_mut(), 100);
+//!
+//! let history = app.world().resource::<MetricsHistory>();
+//! let atp = history.stats(Metric::Currency(Currency::ATP), 50..).unwrap();
+//! println!("ATP from tick 50 on: {:.1}..{:.1}, mean {:.1}", atp.min, atp.max, atp.mean);
+//! history.save_to_file("out/metrics.jsonl").unwrap();
+//! ```
+
+use std::collections::{BTreeMap, VecDeque};
+use std::ops::RangeBounds;
+use std::path::Path;
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::blocks::genome::{BlockKind, GeneState, Genome};
+use crate::metabolism::cells::{simulate_cells, Cell, CellMember, PlayerCell};
+use crate::metabolism::{CurrencyPools, FluxResult, MetabolicNode, MetabolicTick};
+use crate::molecules::{CellMass, Currency};
+
+/// Ticks kept by a default [`MetricsHistory`]; about 17 minutes at the default tick rate.
+pub const DEFAULT_METRICS_CAPACITY: usize = 4096;
+
+/// Everything measured at the end of one metabolic tick.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MetricsSample {
+    pub tick: u64,
+    pub currencies: BTreeMap<Currency, f32>,
+    /// Net flux per block kind, summed over its nodes
+    pub flux: BTreeMap<BlockKind, f32>,
+    pub genes: BTreeMap<BlockKind, GeneState>,
+    /// Mass (`base + extra`) of the player's cell: its body and anything else it owns
+    pub cell_mass: f32,
+}
+
+/// A single measured quantity that can be queried from the history.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Metric {
+    Currency(Currency),
+    Flux(BlockKind),
+    CellMass,
+}
+
+impl MetricsSample {
+    /// Value of `metric` in this sample, if it was measured.
+    pub fn value(&self, metric: Metric) -> Option<f32> {
+        match metric {
+            Metric::Currency(currency) => self.currencies.get(&currency).copied(),
+            Metric::Flux(kind) => self.flux.get(&kind).copied(),
+            Metric::CellMass => Some(self.cell_mass),
+        }
+    }
+}
+
+/// Summary of a metric over a window of ticks.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MetricStats {
+    pub min: f32,
+    pub max: f32,
+    pub mean: f32,
+    /// Number of samples in the window that measured the metric
+    pub samples: usize,
+}
+
+/// Errors that can occur while exporting metrics
+#[derive(Debug)]
+pub enum MetricsError {
+    Io(std::io::Error),
+    Json(serde_json::Error),
+}
+
+impl std::fmt::Display for MetricsError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            MetricsError::Io(err) => write!(f, "metrics I/O error: {}", err),
+            MetricsError::Json(err) => write!(f, "failed to encode metrics: {}", err),
+        }
+    }
+}
+
+impl std::error::Error for MetricsError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            MetricsError::Io(err) => Some(err),
+            MetricsError::Json(err) => Some(err),
+        }
+    }
+}
+
+impl From<std::io::Error> for MetricsError {
+    fn from(err: std::io::Error) -> Self {
+        MetricsError::Io(err)
+    }
+}
+
+impl From<serde_json::Error> for MetricsError {
+    fn from(err: serde_json::Error) -> Self {
+        MetricsError::Json(err)
+    }
+}
+
+/// The most recent samples, oldest first; the oldest is dropped once `capacity` is reached.
+#[derive(Resource, Debug, Clone)]
+pub struct MetricsHistory {
+    samples: VecDeque<MetricsSample>,
+    capacity: usize,
+}
+
+impl Default for MetricsHistory {
+    fn default() -> Self {
+        Self::with_capacity(DEFAULT_METRICS_CAPACITY)
+    }
+}
+
+impl MetricsHistory {
+    /// An empty history that keeps at most `capacity` samples.
+    pub fn with_capacity(capacity: usize) -> Self {
+        let capacity = capacity.max(1);
+        Self {
+            samples: VecDeque::with_capacity(capacity.min(DEFAULT_METRICS_CAPACITY)),
+            capacity,
+        }
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    pub fn len(&self) -> usize {
+        self.samples.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.samples.is_empty()
+    }
+
+    pub fn clear(&mut self) {
+        self.samples.clear();
+    }
+
+    /// Add a sample, dropping the oldest if the history is full.
+    pub fn push(&mut self, sample: MetricsSample) {
+        if self.samples.len() == self.capacity {
+            self.samples.pop_front();
+        }
+        self.samples.push_back(sample);
+    }
+
+    /// All retained samples, oldest first.
+    pub fn samples(&self) -> impl Iterator<Item = &MetricsSample> {
+        self.samples.iter()
+    }
+
+    pub fn latest(&self) -> Option<&MetricsSample> {
+        self.samples.back()
+    }
+
+    /// Change of `metric` between the two most recent samples.
+    pub fn delta(&self, metric: Metric) -> Option<f32> {
+        let mut recent = self.samples.iter().rev();
+        let latest = recent.next()?.value(metric)?;
+        let previous = recent.next()?.value(metric)?;
+        Some(latest - previous)
+    }
+
+    /// `(tick, value)` pairs of `metric` for every sample whose tick is in `window`.
+    pub fn series(&self, metric: Metric, window: impl RangeBounds<u64>) -> Vec<(u64, f32)> {
+        self.samples
+            .iter()
+            .filter(|sample| window.contains(&sample.tick))
+            .filter_map(|sample| sample.value(metric).map(|value| (sample.tick, value)))
+            .collect()
+    }
+
+    /// Min, max and mean of `metric` over the ticks in `window`; `None` if it has no samples.
+    pub fn stats(&self, metric: Metric, window: impl RangeBounds<u64>) -> Option<MetricStats> {
+        let values = self.series(metric, window);
+        if values.is_empty() {
+            return None;
+        }
+        let mut min = f32::INFINITY;
+        let mut max = f32::NEG_INFINITY;
+        let mut sum = 0.0f64;
+        for &(_, value) in &values {
+            min = min.min(value);
+            max = max.max(value);
+            sum += f64::from(value);
+        }
+        Some(MetricStats {
+            min,
+            max,
+            mean: (sum / values.len() as f64) as f32,
+            samples: values.len(),
+        })
+    }
+
+    pub fn min(&self, metric: Metric, window: impl RangeBounds<u64>) -> Option<f32> {
+        self.stats(metric, window).map(|s| s.min)
+    }
+
+    pub fn max(&self, metric: Metric, window: impl RangeBounds<u64>) -> Option<f32> {
+        self.stats(metric, window).map(|s| s.max)
+    }
+
+    pub fn mean(&self, metric: Metric, window: impl RangeBounds<u64>) -> Option<f32> {
+        self.stats(metric, window).map(|s| s.mean)
+    }
+
+    /// One row per sample. Columns are `tick`, each currency, `flux_<block>` and
+    /// `gene_<block>` for every block seen in the history, then `cell_mass`.
+    pub fn to_csv(&self) -> String {
+        let currencies = self.keys(|s| s.currencies.keys().copied().collect());
+        let flux_kinds = self.keys(|s| s.flux.keys().copied().collect());
+        let gene_kinds = self.keys(|s| s.genes.keys().copied().collect());
+
+        let mut csv = String::from("tick");
+        for currency in &currencies {
+            csv.push_str(&format!(",{}", snake_case_id(currency)));
+        }
+        for kind in &flux_kinds {
+            csv.push_str(&format!(",flux_{}", snake_case_id(kind)));
+        }
+        for kind in &gene_kinds {
+            csv.push_str(&format!(",gene_{}", snake_case_id(kind)));
+        }
+        csv.push_str(",cell_mass\n");
+
+        for sample in &self.samples {
+            csv.push_str(&sample.tick.to_string());
+            for currency in &currencies {
+                let amount = sample.currencies.get(currency).copied().unwrap_or(0.0);
+                csv.push_str(&format!(",{}", amount));
+            }
+            for kind in &flux_kinds {
+                let flux = sample.flux.get(kind).copied().unwrap_or(0.0);
+                csv.push_str(&format!(",{}", flux));
+            }
+            for kind in &gene_kinds {
+                csv.push(',');
+                if let Some(state) = sample.genes.get(kind) {
+                    csv.push_str(&snake_case_id(state));
+                }
+            }
+            csv.push_str(&format!(",{}\n", sample.cell_mass));
+        }
+        csv
+    }
+
+    /// One JSON object per line, one line per sample.
+    pub fn to_json_lines(&self) -> Result<String, serde_json::Error> {
+        let mut lines = String::new();
+        for sample in &self.samples {
+            lines.push_str(&serde_json::to_string(sample)?);
+            lines.push('\n');
+        }
+        Ok(lines)
+    }
+
+    /// Write the history to `path` as JSON lines if it ends in `.jsonl`, CSV otherwise.
+    pub fn save_to_file(&self, path: impl AsRef<Path>) -> Result<(), MetricsError> {
+        let path = path.as_ref();
+        if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+            std::fs::create_dir_all(parent)?;
+        }
+        let contents = match path.extension().and_then(|e| e.to_str()) {
+            Some("jsonl") => self.to_json_lines()?,
+            _ => self.to_csv(),
+        };
+        std::fs::write(path, contents)?;
+        Ok(())
+    }
+
+    /// Every key `keys` returns for any sample, sorted and deduplicated.
+    fn keys<K: Ord>(&self, keys: impl Fn(&MetricsSample) -> Vec<K>) -> Vec<K> {
+        let mut all: Vec<K> = self.samples.iter().flat_map(keys).collect();
+        all.sort();
+        all.dedup();
+        all
+    }
+}
+
+/// The id a value serializes to (`organic_waste`), falling back to its `Debug` name.
+fn snake_case_id<T: Serialize + std::fmt::Debug>(value: &T) -> String {
+    serde_json::to_value(value)
+        .ok()
+        .and_then(|v| v.as_str().map(str::to_string))
+        .unwrap_or_else(|| format!("{:?}", value))
+}
+
+// --- Systems ---
+
+/// Masses that belong to the player's cell rather than to another cell.
+type PlayerMasses = (Without<CellMember>, Or<(With<PlayerCell>, Without<Cell>)>);
+
+/// Record one [`MetricsSample`] of the player's cell at the end of every metabolic tick.
+pub fn record_metrics(
+    mut history: ResMut<MetricsHistory>,
+    tick: Res<MetabolicTick>,
+    player: Query<(&CurrencyPools, &Genome), With<PlayerCell>>,
+    flux_result: Res<FluxResult>,
+    nodes: Query<&MetabolicNode>,
+    masses: Query<&CellMass, PlayerMasses>,
+) {
+    let Ok((pools, genome)) = player.get_single() else {
+        return;
+    };
+    let mut flux = BTreeMap::new();
+    for (&entity, &node_flux) in &flux_result.entity_flux {
+        if let Ok(node) = nodes.get(entity) {
+            *flux.entry(node.kind).or_insert(0.0) += node_flux;
+        }
+    }
+
+    history.push(MetricsSample {
+        tick: tick.0,
+        currencies: pools.pools.iter().map(|(&c, &v)| (c, v)).collect(),
+        flux,
+        genes: genome.table.iter().map(|(&k, s)| (k, s.clone())).collect(),
+        cell_mass: masses.iter().map(|mass| mass.base + mass.extra).sum(),
+    });
+}
+
+// --- Plugin ---
+
+pub struct MetricsPlugin;
+
+impl Plugin for MetricsPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<MetricsHistory>()
+            .add_systems(FixedUpdate, record_metrics.after(simulate_cells));
+    }
+}
@@ -16,8 +16,11 @@
 //!     request and consume from the currency pools.
 
 use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
 use tracing::debug;
 
+use crate::metabolism::toxicity::CellHealth;
+
 // --- Currency Resource Definitions ---
 
 /// **ATP (Adenosine Triphosphate)**
@@ -56,7 +59,8 @@ pub struct FreeFattyAcids(pub f32);
 pub struct StorageBeads(pub f32);
 
 /// Defines the threshold of FreeFattyAcids above which the polymerization system
-/// should automatically activate to prevent cellular damage.
+/// should automatically activate to prevent cellular damage. Above it, the excess
+/// damages every `CellHealth`.
 #[derive(Resource, Debug, Default)]
 pub struct LipidToxicityThreshold(pub f32);
 
@@ -66,21 +70,23 @@ pub struct LipidToxicityThreshold(pub f32);
 pub struct Pyruvate(pub f32);
 
 /// **Organic Waste (Ethanol, Lactate, Acetate)**
-/// Byproducts of fermentation that can become toxic if not managed.
+/// Byproducts of fermentation that damage the cell above `ToxicityRules::waste_threshold`.
 #[derive(Resource, Debug, Default)]
 pub struct OrganicWaste(pub f32);
 
 // --- Components ---
 
 /// Represents the total mass of the cell, affecting physical properties like speed and drag.
-#[derive(Component, Debug)]
+/// Every cell also gets a [`CellHealth`].
+#[derive(Component, Debug, Clone, Serialize, Deserialize)]
+#[require(CellHealth)]
 pub struct CellMass {
     pub base: f32,
     pub extra: f32,
 }
 
 /// Manages the polymerization and depolymerization of storage molecules (e.g., fatty acid beads).
-#[derive(Component, Debug)]
+#[derive(Component, Debug, Clone, Serialize, Deserialize)]
 pub struct PolyMer {
     pub capacity: f32,
     pub target_fill: f32,
@@ -92,10 +98,13 @@ pub struct PolyMer {
 
 /// An enum representing the different types of metabolic currencies.
 /// This is used as a key in `FluxProfile` to define the input/output of each currency.
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
 pub enum Currency {
+    #[serde(rename = "atp")]
     ATP,
     ReducingPower,
+    #[serde(rename = "acetyl_coa")]
     AcetylCoA,
     CarbonSkeletons,
     FreeFattyAcids,
@@ -104,6 +113,28 @@ pub enum Currency {
     OrganicWaste,
 }
 
+impl Currency {
+    /// Every currency, in declaration order.
+    pub const ALL: [Currency; 8] = [
+        Currency::ATP,
+        Currency::ReducingPower,
+        Currency::AcetylCoA,
+        Currency::CarbonSkeletons,
+        Currency::FreeFattyAcids,
+        Currency::StorageBeads,
+        Currency::Pyruvate,
+        Currency::OrganicWaste,
+    ];
+
+    /// Number of currencies.
+    pub const COUNT: usize = Self::ALL.len();
+
+    /// Position of this currency in [`Currency::ALL`].
+    pub fn index(self) -> usize {
+        self as usize
+    }
+}
+
 // A trait for generic operations on currency resources.
 // This allows the `try_consume_currency` function to work with any currency type.
 // pub trait CurrencyResource: Resource + Default + std::fmt::Debug {
@@ -0,0 +1,181 @@
+//! Steering decisions of NPC cells, free of the ECS so they can be reasoned about alone.
+//!
+//! Each tick a cell looks at the cells within [`SteeringRules::sight_range`] and picks a
+//! [`Behaviour`]: prey flee from anything that could engulf them, predators hunt the
+//! nearest cell they can engulf that is not toxic, and a cell short of ATP seeks out
+//! nutrients. A cell with nothing better to do wanders.
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::environment::{Nutrient, NutrientGrid};
+
+/// Whether an NPC cell eats other cells or runs from them.
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Temperament {
+    Predator,
+    Prey,
+}
+
+/// What an NPC cell is doing.
+#[derive(Component, Debug, Clone, Copy, PartialEq, Default)]
+pub enum Behaviour {
+    /// Roll along [`Wander`]'s heading
+    #[default]
+    Wander,
+    /// Roll towards the patch with the most sugar
+    SeekNutrients,
+    Flee(Entity),
+    Hunt(Entity),
+}
+
+/// Heading of a wandering cell in radians, drifting a little every tick.
+#[derive(Component, Debug, Clone, Copy, PartialEq, Default)]
+pub struct Wander(pub f32);
+
+/// How NPC cells perceive each other and what engulfing does.
+#[derive(Resource, Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SteeringRules {
+    /// Cells further apart than this do not notice each other
+    pub sight_range: f32,
+    /// Cells with less ATP than this go looking for nutrients
+    pub hungry_below_atp: f32,
+    /// Centres closer than this are in contact
+    pub engulf_range: f32,
+    /// A cell can only engulf cells this many times lighter than itself
+    pub engulf_mass_ratio: f32,
+    /// Fraction of the prey's pools and bead mass one engulfing takes
+    pub engulf_fraction: f32,
+    /// Health a predator loses for engulfing a toxic cell
+    pub toxin_damage: f32,
+    /// Metabolic ticks before a predator can engulf the same cell again
+    pub engulf_cooldown_ticks: u32,
+    /// Most a wandering cell turns per second, in radians
+    pub wander_turn: f32,
+    /// Seed of the [`SteeringRng`](super::SteeringRng) wandering cells turn by
+    pub seed: u64,
+}
+
+impl Default for SteeringRules {
+    fn default() -> Self {
+        Self {
+            sight_range: 12.0,
+            hungry_below_atp: 30.0,
+            engulf_range: 1.2,
+            engulf_mass_ratio: 1.2,
+            engulf_fraction: 0.25,
+            toxin_damage: 20.0,
+            engulf_cooldown_ticks: 20,
+            wander_turn: 1.5,
+            seed: 7,
+        }
+    }
+}
+
+/// What a cell knows about another cell nearby.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Neighbour {
+    pub entity: Entity,
+    pub position: Vec3,
+    pub mass: f32,
+    /// Makes secondary metabolites that poison whoever engulfs it
+    pub toxic: bool,
+    /// `None` for the player's cell
+    pub temperament: Option<Temperament>,
+}
+
+/// The cell making a decision.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Observer {
+    pub entity: Entity,
+    pub position: Vec3,
+    pub mass: f32,
+    pub atp: f32,
+    pub temperament: Temperament,
+}
+
+impl SteeringRules {
+    /// Whether a cell of `predator_mass` is heavy enough to engulf one of `prey_mass`.
+    pub fn can_engulf(&self, predator_mass: f32, prey_mass: f32) -> bool {
+        predator_mass >= prey_mass * self.engulf_mass_ratio
+    }
+
+    /// What `me` should do about the cells around it.
+    pub fn decide(&self, me: &Observer, neighbours: &[Neighbour]) -> Behaviour {
+        let visible = neighbours.iter().filter(|other| {
+            other.entity != me.entity && other.position.distance(me.position) <= self.sight_range
+        });
+        let nearest = |candidates: &mut dyn Iterator<Item = &Neighbour>| {
+            candidates
+                .min_by(|a, b| {
+                    let a = a.position.distance_squared(me.position);
+                    let b = b.position.distance_squared(me.position);
+                    a.total_cmp(&b)
+                })
+                .map(|other| other.entity)
+        };
+
+        match me.temperament {
+            Temperament::Prey => {
+                let mut threats = visible.filter(|other| {
+                    other.temperament != Some(Temperament::Prey) && self.can_engulf(other.mass, me.mass)
+                });
+                if let Some(threat) = nearest(&mut threats) {
+                    return Behaviour::Flee(threat);
+                }
+            }
+            Temperament::Predator => {
+                let mut quarry = visible.filter(|other| {
+                    other.temperament != Some(Temperament::Predator)
+                        && !other.toxic
+                        && self.can_engulf(me.mass, other.mass)
+                });
+                if let Some(prey) = nearest(&mut quarry) {
+                    return Behaviour::Hunt(prey);
+                }
+            }
+        }
+
+        if me.atp < self.hungry_below_atp {
+            Behaviour::SeekNutrients
+        } else {
+            Behaviour::Wander
+        }
+    }
+}
+
+/// Direction on the ground (X, Z) from `from` towards `to`.
+pub fn towards(from: Vec3, to: Vec3) -> Vec2 {
+    (to.xz() - from.xz()).normalize_or_zero()
+}
+
+/// Direction on the ground (X, Z) of a wandering cell's heading.
+pub fn heading_direction(heading: f32) -> Vec2 {
+    Vec2::new(heading.cos(), heading.sin())
+}
+
+/// Direction towards whichever of the patch under `position` and its eight neighbours
+/// holds the most sugar; zero if the cell already sits on the best one.
+pub fn nutrient_direction(grid: &NutrientGrid, position: Vec3) -> Vec2 {
+    let Some(here) = grid.patch_at(position) else {
+        return Vec2::ZERO;
+    };
+    let mut best = (grid.get(here, Nutrient::Sugar), here);
+    for dz in [-1.0, 0.0, 1.0] {
+        for dx in [-1.0, 0.0, 1.0] {
+            let probe = position + Vec3::new(dx, 0.0, dz) * grid.patch_size;
+            if let Some(patch) = grid.patch_at(probe) {
+                let sugar = grid.get(patch, Nutrient::Sugar);
+                if sugar > best.0 {
+                    best = (sugar, patch);
+                }
+            }
+        }
+    }
+    if best.1 == here {
+        return Vec2::ZERO;
+    }
+    towards(position, grid.patch_center(best.1))
+}
@@ -0,0 +1,293 @@
+//! # NPC cells
+//!
+//! Cells the game steers instead of the player. Each is an ordinary [`Cell`] with its own
+//! genome and pools, given a [`Temperament`] and the same character controller physics as
+//! the player. Every metabolic tick in the 3D scene [`steer_npcs`] picks a [`Behaviour`]
+//! for it (see [`behaviour`]) and rolls it that way, powered and paid for by its flagellum
+//! like the player's. Wandering cells turn by a [`SteeringRng`] seeded from
+//! [`SteeringRules::seed`], so the same start always steers them the same way.
+//!
+//! Once per metabolic tick in the 3D scene, predators (and the player) engulf a lighter cell they touch:
+//! they take [`SteeringRules::engulf_fraction`] of its pools, storage beads included, and
+//! of the mass its beads add. They cannot engulf the same cell again for
+//! [`SteeringRules::engulf_cooldown_ticks`]. A cell expressing [`BlockKind::SecondaryMetabolites`] is
+//! toxic and costs whoever engulfs it [`SteeringRules::toxin_damage`] health.
+
+pub mod behaviour;
+
+use std::collections::HashMap;
+use std::f32::consts::PI;
+
+use avian3d::prelude::*;
+use bevy::prelude::*;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+use crate::blocks::genome::{BlockKind, Genome};
+use crate::blocks::motility::MotilityRules;
+use crate::environment::NutrientGrid;
+use crate::metabolism::cells::{simulate_cells, spawn_cell, Cell, PlayerCell};
+use crate::metabolism::toxicity::CellHealth;
+use crate::metabolism::CurrencyPools;
+use crate::molecules::CellMass;
+use crate::player::controller::{roll_torque, CharacterControllerBundle, MovementAcceleration};
+use crate::player::physics::CellPhysics;
+use crate::GameState;
+use behaviour::{
+    heading_direction, nutrient_direction, towards, Behaviour, Neighbour, Observer, SteeringRules,
+    Temperament, Wander,
+};
+
+/// Sent when `predator` engulfs part of `prey`.
+#[derive(Event, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CellEngulfed {
+    pub predator: Entity,
+    pub prey: Entity,
+}
+
+/// The random source NPC steering draws from.
+#[derive(Resource, Debug, Clone)]
+pub struct SteeringRng(pub StdRng);
+
+impl FromWorld for SteeringRng {
+    fn from_world(world: &mut World) -> Self {
+        let rules = world.get_resource_or_init::<SteeringRules>();
+        Self(StdRng::seed_from_u64(rules.seed))
+    }
+}
+
+/// Ticks left before a predator can engulf a prey again, by `(predator, prey)`.
+#[derive(Resource, Debug, Default, Clone)]
+pub struct EngulfCooldowns(pub HashMap<(Entity, Entity), u32>);
+
+/// Spawn an NPC cell with `genome` at `position`. Predators start out twice as heavy.
+pub fn spawn_npc(world: &mut World, temperament: Temperament, genome: Genome, position: Vec3) -> Entity {
+    let cell = spawn_cell(world, genome, CurrencyPools::with_defaults());
+    let (name, base_mass) = match temperament {
+        Temperament::Predator => ("Predator cell", 2.0),
+        Temperament::Prey => ("Prey cell", 1.0),
+    };
+    if let Some(mut mass) = world.get_mut::<CellMass>(cell) {
+        mass.base = base_mass;
+    }
+    world
+        .entity_mut(cell)
+        .insert((Name::new(name), Transform::from_translation(position)));
+    init_npc(world, cell, temperament);
+    cell
+}
+
+/// Give `cell` the steering and body of an NPC with `temperament`.
+pub fn init_npc(world: &mut World, cell: Entity, temperament: Temperament) {
+    let radius = 0.5;
+    world.entity_mut(cell).insert((
+        temperament,
+        Behaviour::default(),
+        // Spread the first headings out without needing a random source
+        Wander(cell.index() as f32 * 2.4),
+        CharacterControllerBundle::new(Collider::sphere(radius)).with_movement(0.5, 5.0, 7.0, PI * 0.45),
+    ));
+}
+
+/// Move `fraction` of every pool of `from` into `to`.
+pub fn transfer_pools(from: &mut CurrencyPools, to: &mut CurrencyPools, fraction: f32) {
+    let fraction = fraction.clamp(0.0, 1.0);
+    let mut taken = Vec::new();
+    for (&currency, amount) in from.pools.iter_mut() {
+        let take = *amount * fraction;
+        *amount -= take;
+        taken.push((currency, take));
+    }
+    for (currency, take) in taken {
+        to.modify(currency, take);
+    }
+}
+
+/// Whether a cell with `genome` poisons whoever engulfs it.
+fn is_toxic(genome: &Genome) -> bool {
+    genome.expression_level(BlockKind::SecondaryMetabolites) > 0.0
+}
+
+/// Every cell in the scene.
+type SceneCells<'a> = (
+    Entity,
+    &'a Transform,
+    &'a CellMass,
+    Option<&'a Temperament>,
+    &'a Genome,
+);
+
+/// An NPC's state that steering changes.
+type Steered<'a> = (
+    Entity,
+    &'a Temperament,
+    &'a mut Behaviour,
+    &'a mut Wander,
+    &'a mut CurrencyPools,
+    &'a Genome,
+    &'a MovementAcceleration,
+    &'a mut ExternalTorque,
+);
+
+/// The scene as engulfing sees it first, and the cells it then changes.
+type EngulfQueries<'w, 's> = ParamSet<
+    'w,
+    's,
+    (
+        Query<'static, 'static, SceneCells<'static>, With<Cell>>,
+        Query<'static, 'static, Engulfable<'static>, With<Cell>>,
+    ),
+>;
+
+/// What engulfing takes from and gives to a cell.
+type Engulfable<'a> = (&'a mut CellMass, &'a mut CurrencyPools, Option<&'a mut CellHealth>);
+
+fn neighbours(cells: &Query<SceneCells, With<Cell>>) -> Vec<Neighbour> {
+    cells
+        .iter()
+        .map(|(entity, transform, mass, temperament, genome)| Neighbour {
+            entity,
+            position: transform.translation,
+            mass: mass.base + mass.extra,
+            toxic: is_toxic(genome),
+            temperament: temperament.copied(),
+        })
+        .collect()
+}
+
+// --- Systems ---
+
+/// Decide what every NPC does and roll it that way, paying for the roll once per tick.
+#[allow(clippy::too_many_arguments)]
+pub fn steer_npcs(
+    rules: Res<SteeringRules>,
+    motility: Res<MotilityRules>,
+    physics: Res<CellPhysics>,
+    time: Res<Time>,
+    mut rng: ResMut<SteeringRng>,
+    grid: Option<Res<NutrientGrid>>,
+    cells: Query<SceneCells, With<Cell>>,
+    mut npcs: Query<Steered>,
+) {
+    let neighbours = neighbours(&cells);
+    let position_of = |entity: Entity| neighbours.iter().find(|n| n.entity == entity).map(|n| n.position);
+
+    for (entity, temperament, mut behaviour, mut wander, mut pools, genome, acceleration, mut torque) in &mut npcs {
+        let Some(me) = neighbours.iter().find(|n| n.entity == entity) else {
+            continue;
+        };
+        let observer = Observer {
+            entity,
+            position: me.position,
+            mass: me.mass,
+            atp: pools.get(crate::molecules::Currency::ATP),
+            temperament: *temperament,
+        };
+        let next = rules.decide(&observer, &neighbours);
+        if *behaviour != next {
+            *behaviour = next;
+        }
+
+        wander.0 += rng.0.gen_range(-1.0..=1.0) * rules.wander_turn * time.delta_secs();
+        let direction = match next {
+            Behaviour::Flee(threat) => position_of(threat).map_or(Vec2::ZERO, |threat| -towards(me.position, threat)),
+            Behaviour::Hunt(prey) => position_of(prey).map_or(Vec2::ZERO, |prey| towards(me.position, prey)),
+            Behaviour::SeekNutrients => grid
+                .as_deref()
+                .map(|grid| nutrient_direction(grid, me.position))
+                .filter(|direction| *direction != Vec2::ZERO)
+                .unwrap_or_else(|| heading_direction(wander.0)),
+            Behaviour::Wander => heading_direction(wander.0),
+        };
+
+        let power = motility.power(genome, &pools);
+        let wanted = roll_torque(direction, acceleration.0 * power);
+        let applied = physics.pay_for_torque(&mut pools, wanted);
+        torque.set_torque(applied);
+    }
+}
+
+/// Let every predator, and the player, engulf one lighter cell it touches and has not
+/// engulfed lately.
+pub fn engulf_cells(
+    rules: Res<SteeringRules>,
+    mut cooldowns: ResMut<EngulfCooldowns>,
+    player: Query<(), With<PlayerCell>>,
+    mut cells: EngulfQueries,
+    mut engulfed: EventWriter<CellEngulfed>,
+) {
+    cooldowns.0.retain(|_, ticks| {
+        *ticks = ticks.saturating_sub(1);
+        *ticks > 0
+    });
+    let mut candidates = neighbours(&cells.p0());
+    candidates.sort_by_key(|cell| cell.entity);
+    let predators: Vec<Neighbour> = candidates
+        .iter()
+        .filter(|cell| cell.temperament == Some(Temperament::Predator) || player.contains(cell.entity))
+        .copied()
+        .collect();
+
+    for predator in predators {
+        let prey = candidates
+            .iter()
+            .filter(|prey| {
+                prey.entity != predator.entity
+                    && prey.temperament != Some(Temperament::Predator)
+                    && prey.position.distance(predator.position) <= rules.engulf_range
+                    && rules.can_engulf(predator.mass, prey.mass)
+                    && !cooldowns.0.contains_key(&(predator.entity, prey.entity))
+            })
+            .min_by(|a, b| {
+                let a = a.position.distance_squared(predator.position);
+                let b = b.position.distance_squared(predator.position);
+                a.total_cmp(&b)
+            })
+            .copied();
+        let Some(prey) = prey else {
+            continue;
+        };
+        let mut bodies = cells.p1();
+        let Ok([(mut hunter_mass, mut hunter_pools, hunter_health), (mut prey_mass, mut prey_pools, _)]) =
+            bodies.get_many_mut([predator.entity, prey.entity])
+        else {
+            continue;
+        };
+
+        transfer_pools(&mut prey_pools, &mut hunter_pools, rules.engulf_fraction);
+        let beads = prey_mass.extra.max(0.0) * rules.engulf_fraction;
+        prey_mass.extra -= beads;
+        hunter_mass.extra += beads;
+        if prey.toxic {
+            if let Some(mut health) = hunter_health {
+                health.current = (health.current - rules.toxin_damage).max(0.0);
+            }
+        }
+        cooldowns.0.insert((predator.entity, prey.entity), rules.engulf_cooldown_ticks);
+        engulfed.send(CellEngulfed {
+            predator: predator.entity,
+            prey: prey.entity,
+        });
+    }
+}
+
+// --- Plugin ---
+
+pub struct NpcPlugin;
+
+impl Plugin for NpcPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<SteeringRules>()
+            .init_resource::<CellPhysics>()
+            .init_resource::<SteeringRng>()
+            .init_resource::<EngulfCooldowns>()
+            .add_event::<CellEngulfed>()
+            .add_systems(
+                FixedUpdate,
+                (steer_npcs, engulf_cells)
+                    .chain()
+                    .run_if(in_state(GameState::Scene3D))
+                    .before(simulate_cells),
+            );
+    }
+}
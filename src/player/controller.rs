@@ -1,15 +1,23 @@
+use crate::blocks::genome::Genome;
+use crate::blocks::motility::MotilityRules;
+use crate::metabolism::CurrencyPools;
+use crate::player::physics::{BaseDamping, CellPhysics};
 use crate::player::Player;
+use crate::replay::live_input_enabled;
+use crate::GameState;
 use avian3d::{math::*, prelude::*};
 use bevy::color::palettes::basic::{GREEN, RED, YELLOW};
 use bevy::color::LinearRgba;
 use bevy::gizmos::gizmos::Gizmos;
 use bevy::{ecs::query::Has, prelude::*};
 use leafwing_input_manager::prelude::*;
+use serde::{Deserialize, Serialize};
 use std::f32::consts::PI;
 
 pub struct CharacterControllerPlugin;
 
-#[derive(Actionlike, Clone, Debug, Copy, PartialEq, Eq, Hash, Reflect)]
+/// Actions of the player's cell; `Pan` turns the camera following it.
+#[derive(Actionlike, Clone, Debug, Copy, PartialEq, Eq, Hash, Reflect, Serialize, Deserialize)]
 pub enum Action {
     Jump,
     #[actionlike(DualAxis)]
@@ -23,8 +31,12 @@ impl Action {
         let dpad = VirtualDPad::new(KeyCode::KeyW, KeyCode::KeyS, KeyCode::KeyA, KeyCode::KeyD);
 
         InputMap::new([(Action::Jump, KeyCode::Space)])
+            .with(Action::Jump, GamepadButton::South)
             .with_dual_axis(Action::Move, dpad)
+            .with_dual_axis(Action::Move, GamepadStick::LEFT)
             .with_dual_axis(Action::Pan, MouseMove::default())
+            // Sticks report -1..1 per frame rather than pixels, and push up to look up
+            .with_dual_axis(Action::Pan, GamepadStick::RIGHT.inverted_y().sensitivity(8.0))
     }
 }
 
@@ -32,7 +44,15 @@ impl Plugin for CharacterControllerPlugin {
     fn build(&self, app: &mut App) {
         app.add_event::<MovementAction>()
             .add_plugins(InputManagerPlugin::<Action>::default())
-            .add_systems(Update, (movement_input, update_grounded, movement).chain());
+            .add_systems(
+                Update,
+                (
+                    movement_input.run_if(live_input_enabled.and(in_state(GameState::Scene3D))),
+                    update_grounded,
+                    movement,
+                )
+                    .chain(),
+            );
     }
 }
 
@@ -53,7 +73,7 @@ pub struct CharacterController;
 pub struct Grounded;
 /// The acceleration used for character movement.
 #[derive(Component)]
-pub struct MovementAcceleration(Scalar);
+pub struct MovementAcceleration(pub Scalar);
 
 /// The strength of a jump.
 #[derive(Component)]
@@ -81,6 +101,7 @@ pub struct CharacterControllerBundle {
 pub struct MovementBundle {
     acceleration: MovementAcceleration,
     damping: AngularDamping,
+    base_damping: BaseDamping,
     jump_impulse: JumpImpulse,
     max_slope_angle: MaxSlopeAngle,
 }
@@ -95,6 +116,7 @@ impl MovementBundle {
         Self {
             acceleration: MovementAcceleration(acceleration),
             damping: AngularDamping(damping),
+            base_damping: BaseDamping(damping),
             jump_impulse: JumpImpulse(jump_impulse),
             max_slope_angle: MaxSlopeAngle(max_slope_angle),
         }
@@ -107,6 +129,11 @@ impl Default for MovementBundle {
     }
 }
 
+/// Torque that rolls a ball along `direction` on the ground (X, Z) with `acceleration`.
+pub fn roll_torque(direction: Vector2, acceleration: Scalar) -> Vec3 {
+    Vec3::new(direction.y, 0.0, -direction.x) * acceleration
+}
+
 impl CharacterControllerBundle {
     pub fn new(collider: Collider) -> Self {
         // Create shape caster as a slightly smaller version of collider
@@ -229,16 +256,25 @@ fn update_grounded(
     }
 }
 
+/// What [`movement`] reads and drives on the player's controller.
+type PlayerController<'a> = (
+    &'a MovementAcceleration,
+    &'a JumpImpulse,
+    &'a mut ExternalTorque,
+    &'a mut LinearVelocity,
+    Has<Grounded>,
+    &'a Genome,
+    &'a mut CurrencyPools,
+);
+
 /// Responds to [`MovementAction`] events and moves character controllers accordingly.
+/// Every action is scaled by the power of the flagellum and paid for in ATP from the
+/// player's pools.
 fn movement(
     mut movement_event_reader: EventReader<MovementAction>,
-    mut controllers: Query<(
-        &MovementAcceleration,
-        &JumpImpulse,
-        &mut ExternalTorque,
-        &mut LinearVelocity,
-        Has<Grounded>,
-    )>,
+    motility: Res<MotilityRules>,
+    physics: Res<CellPhysics>,
+    mut controllers: Query<PlayerController, With<Player>>,
 ) {
     let mut active_spin = false;
     for (
@@ -247,21 +283,25 @@ fn movement(
         mut external_torque,
         mut linear_velocity,
         is_grounded,
+        genome,
+        mut pools,
     ) in &mut controllers
     {
         for event in movement_event_reader.read() {
+            let power = motility.power(genome, &pools);
             match event {
                 MovementAction::Move(direction) => {
-                    external_torque
-                        .apply_torque(
-                            Vec3::new(direction.y, 0.0, -direction.x) * movement_acceleration.0,
-                        )
-                        .with_persistence(false);
+                    let torque = roll_torque(*direction, movement_acceleration.0 * power);
+                    let torque = physics.pay_for_torque(&mut pools, torque);
+                    external_torque.apply_torque(torque).with_persistence(false);
                     active_spin = true;
                 }
                 MovementAction::Jump => {
                     if is_grounded {
-                        linear_velocity.y = jump_impulse.0;
+                        let strength = motility.pay_for_jump(&mut pools, power);
+                        if strength > 0.0 {
+                            linear_velocity.y = jump_impulse.0 * strength;
+                        }
                     }
                 }
             }
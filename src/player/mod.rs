@@ -1,9 +1,17 @@
 use avian3d::prelude::*;
 use bevy::prelude::*;
+use crate::input::InputBindings;
+use crate::metabolism::cells::{Cell, PlayerCell};
+use crate::molecules::{CellMass, PolyMer};
+use crate::terrain::Terrain;
 use leafwing_input_manager::prelude::*;
 use std::f32::consts::PI;
 pub mod controller;
+pub mod physics;
 
+/// The body of the player's cell in the 3D scene, which also carries its
+/// [`PlayerCell`] genome and pools. The body is disabled and hidden while the game is in
+/// another scene; the cell lives on.
 #[derive(Component)]
 pub struct Player;
 
@@ -12,6 +20,7 @@ pub struct PlayerPlugin;
 impl Plugin for PlayerPlugin {
     fn build(&self, app: &mut App) {
         app.add_plugins(controller::CharacterControllerPlugin)
+            .add_plugins(physics::CellPhysicsPlugin)
             .add_systems(Startup, spawn_player);
     }
 }
@@ -20,16 +29,29 @@ pub fn spawn_player(
     mut commands: Commands,
     mut meshes: ResMut<Assets<Mesh>>,
     mut materials: ResMut<Assets<StandardMaterial>>,
+    terrain: Option<Res<Terrain>>,
+    bindings: Option<Res<InputBindings>>,
 ) {
-    let player_transform = Transform::from_xyz(0.0, 1.0, 0.0);
+    let ground = terrain.map_or(0.0, |terrain| terrain.height_at(0.0, 0.0));
+    let player_transform = Transform::from_xyz(0.0, ground + 1.0, 0.0);
     let radius = 0.5;
+    let input_map = bindings.map_or_else(controller::Action::input_map, |bindings| bindings.player.clone());
 
     commands.spawn((
         Player,
+        Cell,
+        PlayerCell,
+        CellMass { base: 1.0, extra: 0.0 },
+        PolyMer {
+            capacity: 100.0,
+            target_fill: 50.0,
+            poly_rate: 20.0,
+            lipo_rate: 5.0,
+        },
         Mesh3d(meshes.add(Sphere::new(radius).mesh())),
         MeshMaterial3d(materials.add(Color::srgb(0.8, 0.7, 0.6))),
         player_transform,
-        InputManagerBundle::with_map(controller::Action::input_map()),
+        InputManagerBundle::with_map(input_map),
         controller::CharacterControllerBundle::new(Collider::sphere(radius)).with_movement(
             0.5,
             5.0,
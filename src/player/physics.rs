@@ -0,0 +1,99 @@
+//! # Cell physics
+//!
+//! A cell's body follows its metabolism. The rigid-body [`Mass`] of every cell is its
+//! [`CellMass`], to which every stored bead adds [`CellPhysics::mass_per_bead`], and that
+//! extra mass adds drag on top of its [`BaseDamping`].
+//! Rolling is paid for in ATP: every roll action costs [`CellPhysics::atp_per_torque`]
+//! for each unit of torque it applies, whatever the frame time, so a replay pays exactly
+//! what the recording did. Without the ATP to pay for it the torque fades out.
+
+use avian3d::prelude::*;
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::metabolism::CurrencyPools;
+use crate::molecules::{CellMass, Currency};
+
+/// How a cell's metabolism shapes its body.
+#[derive(Resource, Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CellPhysics {
+    /// Rigid-body mass of one unit of [`CellMass`]
+    pub mass_per_unit: f32,
+    /// [`CellMass`] one storage bead adds
+    pub mass_per_bead: f32,
+    /// Lightest a body gets, however much mass its cell loses
+    pub min_mass: f32,
+    /// Angular damping each unit of extra (bead) mass adds
+    pub damping_per_extra_mass: f32,
+    /// ATP one roll action spends for every unit of torque it applies
+    pub atp_per_torque: f32,
+}
+
+impl Default for CellPhysics {
+    fn default() -> Self {
+        Self {
+            mass_per_unit: 1.0,
+            mass_per_bead: 0.02,
+            min_mass: 0.1,
+            damping_per_extra_mass: 0.05,
+            atp_per_torque: 0.01,
+        }
+    }
+}
+
+/// Angular damping of a body before its extra mass adds drag.
+#[derive(Component, Debug, Clone, Copy, PartialEq)]
+pub struct BaseDamping(pub f32);
+
+impl CellPhysics {
+    pub fn mass(&self, cell_mass: &CellMass) -> Mass {
+        Mass(((cell_mass.base + cell_mass.extra) * self.mass_per_unit).max(self.min_mass))
+    }
+
+    pub fn damping(&self, base: f32, cell_mass: &CellMass) -> AngularDamping {
+        AngularDamping(base + cell_mass.extra.max(0.0) * self.damping_per_extra_mass)
+    }
+
+    /// The part of `torque`, applied by one roll action, that the ATP in `pools` pays for.
+    /// The ATP is taken from the pools.
+    pub fn pay_for_torque(&self, pools: &mut CurrencyPools, torque: Vec3) -> Vec3 {
+        let cost = torque.length() * self.atp_per_torque;
+        if cost <= 0.0 {
+            return torque;
+        }
+        let paid = cost.min(pools.get(Currency::ATP));
+        pools.modify(Currency::ATP, -paid);
+        torque * (paid / cost)
+    }
+}
+
+/// Bodies that are new or whose [`CellMass`] changed since the last sync.
+type ChangedCellBodies = (With<RigidBody>, Or<(Changed<CellMass>, Added<RigidBody>)>);
+
+// --- Systems ---
+
+/// Give every body the mass and drag of its [`CellMass`] whenever that changes.
+pub fn sync_cell_mass(
+    mut commands: Commands,
+    physics: Res<CellPhysics>,
+    cells: Query<(Entity, &CellMass, Option<&BaseDamping>), ChangedCellBodies>,
+) {
+    for (entity, cell_mass, base) in &cells {
+        let base = base.map_or(0.0, |base| base.0);
+        commands
+            .entity(entity)
+            .insert((physics.mass(cell_mass), physics.damping(base, cell_mass)));
+    }
+}
+
+// --- Plugin ---
+
+pub struct CellPhysicsPlugin;
+
+impl Plugin for CellPhysicsPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<CellPhysics>()
+            .add_systems(Update, sync_cell_mass);
+    }
+}
@@ -0,0 +1,533 @@
+//! # Session Replays
+//!
+//! Records what the player did (genome commands and undos, scene transitions, movement
+//! actions) plus the random gene mutations the session rolled, each stamped with the metabolic
+//! tick it happened on, relative to the start of the recording. A [`ReplayLog`] also stores a
+//! [`SimulationSnapshot`] of the starting state and the player's
+//! [`CurrencyPools::state_hash`](crate::metabolism::CurrencyPools::state_hash) at the end,
+//! so playing it back on any app can verify the simulation reached the same state.
+//!
+//! Playback advances one metabolic tick per step and applies recorded inputs between ticks
+//! exactly where they happened. Live keyboard input, random mutations and the automatic
+//! fixed clock are suspended while it runs, which also pauses physics in windowed builds.
+//!
+//! ## Usage
+//!
+//! ```rust,no_run
+//! use metabolistic3d::replay::{self, ReplayLog};
+//!
+//! let log = ReplayLog::load_from_file("replays/crash_report.json").unwrap();
+//! let outcome = replay::play_headless(&log).unwrap();
+//! assert!(outcome.matches(), "replay diverged: {:?}", outcome);
+//! ```
+//!
+//! In game, [`GameAction::ToggleRecording`] (`F9`) starts and stops recording to
+//! [`DEFAULT_REPLAY_PATH`] and [`GameAction::PlayReplay`] (`F10`) plays that file back.
+
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use bevy::prelude::*;
+use bevy::time::TimeUpdateStrategy;
+use leafwing_input_manager::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::blocks::genome::{
+    apply_mutation, BlockKind, GeneMutationEvent, GeneState, Genome, GenomeCommand,
+    GenomeHistory, GenomeHistoryCommand, MutationConfig,
+};
+use crate::input::GameAction;
+use crate::metabolism::cells::{player_cell, player_pools};
+use crate::metabolism::{run_ticks, MetabolicTick};
+use crate::player::controller::MovementAction;
+use crate::snapshot::{SimulationSnapshot, SnapshotError};
+use crate::{GameState, MetabolisticApp};
+
+/// Version of the replay format written by [`ReplayLog::to_json`].
+pub const REPLAY_VERSION: u32 = 1;
+
+/// Where the in-game recorder saves and plays back its session.
+pub const DEFAULT_REPLAY_PATH: &str = "replays/last_session.json";
+
+/// Frames to wait after injecting inputs before the next tick: one for `Update` to
+/// apply them, one for `PreUpdate` to propagate genome and state changes.
+const SETTLE_FRAMES: u8 = 2;
+
+// --- Data format ---
+
+/// A recorded session: starting state, timestamped inputs and the expected end state.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReplayLog {
+    pub version: u32,
+    pub start: SimulationSnapshot,
+    pub inputs: Vec<RecordedInput>,
+    /// Ticks simulated between the start and the end of the recording.
+    pub final_tick: u64,
+    pub final_currency_hash: u64,
+}
+
+/// An input and the tick (relative to the recording start) it was issued on.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RecordedInput {
+    pub tick: u64,
+    #[serde(flatten)]
+    pub input: ReplayInput,
+}
+
+/// Everything that can change the simulation from outside the metabolic systems.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "input", rename_all = "snake_case")]
+pub enum ReplayInput {
+    Genome { command: GenomeCommand },
+    GenomeHistory { command: GenomeHistoryCommand },
+    StateTransition { state: GameState },
+    Move { x: f32, y: f32 },
+    Jump,
+    /// A random mutation rolled by `mutation_system` during recording.
+    Mutation { gene: BlockKind, target: GeneState },
+}
+
+/// Result of a finished playback.
+#[derive(Resource, Debug, Clone, PartialEq, Eq)]
+pub struct ReplayOutcome {
+    pub final_tick: u64,
+    pub expected_hash: u64,
+    pub actual_hash: u64,
+}
+
+impl ReplayOutcome {
+    /// True when playback ended with exactly the recorded currency pools.
+    pub fn matches(&self) -> bool {
+        self.expected_hash == self.actual_hash
+    }
+}
+
+/// Errors that can occur while loading or playing a replay
+#[derive(Debug)]
+pub enum ReplayError {
+    Io(std::io::Error),
+    MalformedJson(serde_json::Error),
+    Snapshot(SnapshotError),
+    UnsupportedVersion(u32),
+}
+
+impl std::fmt::Display for ReplayError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ReplayError::Io(err) => write!(f, "replay I/O error: {}", err),
+            ReplayError::MalformedJson(err) => write!(f, "malformed replay JSON: {}", err),
+            ReplayError::Snapshot(err) => write!(f, "invalid replay start state: {}", err),
+            ReplayError::UnsupportedVersion(version) => write!(
+                f,
+                "unsupported replay version {} (latest supported is {})",
+                version, REPLAY_VERSION
+            ),
+        }
+    }
+}
+
+impl std::error::Error for ReplayError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            ReplayError::Io(err) => Some(err),
+            ReplayError::MalformedJson(err) => Some(err),
+            ReplayError::Snapshot(err) => Some(err),
+            ReplayError::UnsupportedVersion(_) => None,
+        }
+    }
+}
+
+impl From<std::io::Error> for ReplayError {
+    fn from(err: std::io::Error) -> Self {
+        ReplayError::Io(err)
+    }
+}
+
+impl From<serde_json::Error> for ReplayError {
+    fn from(err: serde_json::Error) -> Self {
+        ReplayError::MalformedJson(err)
+    }
+}
+
+impl From<SnapshotError> for ReplayError {
+    fn from(err: SnapshotError) -> Self {
+        ReplayError::Snapshot(err)
+    }
+}
+
+impl ReplayLog {
+    /// Serialize the log to a JSON string
+    pub fn to_json(&self) -> Result<String, serde_json::Error> {
+        serde_json::to_string_pretty(self)
+    }
+
+    /// Deserialize a log from a JSON string
+    pub fn from_json(json: &str) -> Result<Self, ReplayError> {
+        let value: serde_json::Value = serde_json::from_str(json)?;
+        if let Some(version) = value.get("version").and_then(|v| v.as_u64()) {
+            if version > REPLAY_VERSION as u64 {
+                return Err(ReplayError::UnsupportedVersion(
+                    u32::try_from(version).unwrap_or(u32::MAX),
+                ));
+            }
+        }
+        Ok(serde_json::from_value(value)?)
+    }
+
+    /// Write the log to `path`, creating parent directories as needed
+    pub fn save_to_file(&self, path: impl AsRef<Path>) -> Result<(), ReplayError> {
+        let path = path.as_ref();
+        if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(path, self.to_json()?)?;
+        Ok(())
+    }
+
+    /// Read a log from `path`
+    pub fn load_from_file(path: impl AsRef<Path>) -> Result<Self, ReplayError> {
+        Self::from_json(&std::fs::read_to_string(path)?)
+    }
+}
+
+impl ReplayInput {
+    /// Feed the input into `world` the same way live input would arrive.
+    pub fn apply(&self, world: &mut World) {
+        match self {
+            ReplayInput::Genome { command } => {
+                world.send_event(*command);
+            }
+            ReplayInput::GenomeHistory { command } => {
+                world.send_event(*command);
+            }
+            ReplayInput::StateTransition { state } => {
+                if let Some(mut next_state) = world.get_resource_mut::<NextState<GameState>>() {
+                    next_state.set(state.clone());
+                }
+            }
+            ReplayInput::Move { x, y } => {
+                world.send_event(MovementAction::Move(Vec2::new(*x, *y)));
+            }
+            ReplayInput::Jump => {
+                world.send_event(MovementAction::Jump);
+            }
+            ReplayInput::Mutation { gene, target } => {
+                let genome = player_cell(world).and_then(|cell| world.get_mut::<Genome>(cell));
+                if let Some(mut genome) = genome {
+                    apply_mutation(&mut genome, *gene, target);
+                }
+            }
+        }
+    }
+}
+
+// --- Resources & Events ---
+
+/// Present while a session is being recorded.
+#[derive(Resource, Debug)]
+pub struct ReplayRecorder {
+    start_tick: u64,
+    start: SimulationSnapshot,
+    inputs: Vec<RecordedInput>,
+}
+
+/// Present while a replay is being played back.
+#[derive(Resource)]
+pub struct ReplayPlayback {
+    log: ReplayLog,
+    tick: u64,
+    next_input: usize,
+    settle_frames: u8,
+    suspended_mutations: Option<MutationConfig>,
+    suspended_time_strategy: Option<TimeUpdateStrategy>,
+}
+
+/// Start recording, or stop and write the session to the given path.
+#[derive(Event, Debug, Clone)]
+pub enum RecordingRequest {
+    Start,
+    Stop(PathBuf),
+}
+
+/// Request the replay stored at `path` to be played back.
+#[derive(Event, Debug, Clone)]
+pub struct PlaybackRequest(pub PathBuf);
+
+// --- Recording & playback ---
+
+/// Begin recording from the current state of `world`.
+///
+/// The genome undo history is not part of a snapshot, so it is cleared here and at the start
+/// of playback; an undo in the recording can then only reach edits made during it.
+pub fn start_recording(world: &mut World) {
+    world.insert_resource(GenomeHistory::default());
+    let start = SimulationSnapshot::capture(world);
+    let start_tick = world.resource::<MetabolicTick>().0;
+    world.insert_resource(ReplayRecorder {
+        start_tick,
+        start,
+        inputs: Vec::new(),
+    });
+}
+
+/// Stop recording and return the finished log, if a recording was running.
+pub fn stop_recording(world: &mut World) -> Option<ReplayLog> {
+    let recorder = world.remove_resource::<ReplayRecorder>()?;
+    Some(ReplayLog {
+        version: REPLAY_VERSION,
+        start: recorder.start,
+        inputs: recorder.inputs,
+        final_tick: world.resource::<MetabolicTick>().0 - recorder.start_tick,
+        final_currency_hash: player_pools(world).state_hash(),
+    })
+}
+
+/// Restore the log's starting state into `world` and begin playing it back.
+pub fn start_playback(world: &mut World, log: ReplayLog) -> Result<(), ReplayError> {
+    if log.version > REPLAY_VERSION {
+        return Err(ReplayError::UnsupportedVersion(log.version));
+    }
+    log.start.apply(world)?;
+    world.insert_resource(GenomeHistory::default());
+    world.remove_resource::<ReplayOutcome>();
+
+    let suspended_mutations = world.remove_resource::<MutationConfig>();
+    world.insert_resource(MutationConfig::deterministic());
+    let suspended_time_strategy = world.remove_resource::<TimeUpdateStrategy>();
+    world.insert_resource(TimeUpdateStrategy::ManualDuration(Duration::ZERO));
+
+    world.insert_resource(ReplayPlayback {
+        log,
+        tick: 0,
+        next_input: 0,
+        // Let the restored snapshot settle before injecting anything
+        settle_frames: SETTLE_FRAMES,
+        suspended_mutations,
+        suspended_time_strategy,
+    });
+    Ok(())
+}
+
+/// Play `log` back on a fresh headless app and report whether it ended where it was recorded.
+pub fn play_headless(log: &ReplayLog) -> Result<ReplayOutcome, ReplayError> {
+    let mut app = MetabolisticApp::new_headless();
+    app.update();
+    start_playback(app.world_mut(), log.clone())?;
+    while app.world().contains_resource::<ReplayPlayback>() {
+        app.update();
+    }
+    Ok(app
+        .world_mut()
+        .remove_resource::<ReplayOutcome>()
+        .expect("finished playback always records an outcome"))
+}
+
+/// Run condition: false while a replay is feeding inputs, so live input stays out of the way.
+pub fn live_input_enabled(playback: Option<Res<ReplayPlayback>>) -> bool {
+    playback.is_none()
+}
+
+// --- Systems ---
+
+/// Log this frame's genome commands, undo/redo requests, mutations and movement. Runs in `Last`, after every
+/// input source has run for the frame.
+///
+/// Always drains its readers, so a new recording never picks up inputs from before it started.
+fn record_inputs(
+    recorder: Option<ResMut<ReplayRecorder>>,
+    tick: Res<MetabolicTick>,
+    mut genome_commands: EventReader<GenomeCommand>,
+    mut history_commands: EventReader<GenomeHistoryCommand>,
+    mut mutations: EventReader<GeneMutationEvent>,
+    mut movements: EventReader<MovementAction>,
+) {
+    let mut inputs = Vec::new();
+
+    for mutation in mutations.read() {
+        inputs.push(ReplayInput::Mutation {
+            gene: mutation.block_kind,
+            target: mutation.target.clone(),
+        });
+    }
+    for command in genome_commands.read() {
+        inputs.push(ReplayInput::Genome { command: *command });
+    }
+    for command in history_commands.read() {
+        inputs.push(ReplayInput::GenomeHistory { command: *command });
+    }
+    for movement in movements.read() {
+        inputs.push(match movement {
+            MovementAction::Move(direction) => ReplayInput::Move {
+                x: direction.x,
+                y: direction.y,
+            },
+            MovementAction::Jump => ReplayInput::Jump,
+        });
+    }
+    if let Some(mut recorder) = recorder {
+        let tick = tick.0 - recorder.start_tick;
+        recorder
+            .inputs
+            .extend(inputs.into_iter().map(|input| RecordedInput { tick, input }));
+    }
+}
+
+/// Log scene changes. Runs in `PreUpdate`, where a transition requested during the previous
+/// frame (or between frames) is still pending and no tick has run since it was requested.
+fn record_state_transitions(
+    mut recorder: ResMut<ReplayRecorder>,
+    tick: Res<MetabolicTick>,
+    next_state: Res<NextState<GameState>>,
+) {
+    if let NextState::Pending(state) = next_state.as_ref() {
+        let tick = tick.0 - recorder.start_tick;
+        recorder.inputs.push(RecordedInput {
+            tick,
+            input: ReplayInput::StateTransition {
+                state: state.clone(),
+            },
+        });
+    }
+}
+
+/// Process pending recording and playback requests with exclusive world access.
+pub fn handle_replay_requests(world: &mut World) {
+    let requests: Vec<RecordingRequest> = world
+        .resource_mut::<Events<RecordingRequest>>()
+        .drain()
+        .collect();
+    for request in requests {
+        match request {
+            RecordingRequest::Start => {
+                start_recording(world);
+                info!("Started recording replay");
+            }
+            RecordingRequest::Stop(path) => match stop_recording(world) {
+                Some(log) => match log.save_to_file(&path) {
+                    Ok(()) => info!(
+                        "Saved replay of {} ticks to {}",
+                        log.final_tick,
+                        path.display()
+                    ),
+                    Err(err) => error!("Failed to save replay to {}: {}", path.display(), err),
+                },
+                None => warn!("No replay is being recorded"),
+            },
+        }
+    }
+
+    let playbacks: Vec<PlaybackRequest> = world
+        .resource_mut::<Events<PlaybackRequest>>()
+        .drain()
+        .collect();
+    for PlaybackRequest(path) in playbacks {
+        let result = ReplayLog::load_from_file(&path).and_then(|log| start_playback(world, log));
+        match result {
+            Ok(()) => info!("Playing back replay from {}", path.display()),
+            Err(err) => error!("Failed to play back {}: {}", path.display(), err),
+        }
+    }
+}
+
+/// Advance playback by one step: settle, inject the current tick's inputs, or run a tick.
+pub fn advance_playback(world: &mut World) {
+    let Some(mut playback) = world.remove_resource::<ReplayPlayback>() else {
+        return;
+    };
+
+    if playback.settle_frames > 0 {
+        playback.settle_frames -= 1;
+        world.insert_resource(playback);
+        return;
+    }
+
+    let due = playback.log.inputs[playback.next_input..]
+        .iter()
+        .take_while(|recorded| recorded.tick <= playback.tick)
+        .count();
+    if due > 0 {
+        let start = playback.next_input;
+        for recorded in &playback.log.inputs[start..start + due] {
+            recorded.input.apply(world);
+        }
+        playback.next_input += due;
+        playback.settle_frames = SETTLE_FRAMES;
+        world.insert_resource(playback);
+        return;
+    }
+
+    if playback.tick < playback.log.final_tick {
+        run_ticks(world, 1);
+        playback.tick += 1;
+        world.insert_resource(playback);
+        return;
+    }
+
+    let outcome = ReplayOutcome {
+        final_tick: playback.tick,
+        expected_hash: playback.log.final_currency_hash,
+        actual_hash: player_pools(world).state_hash(),
+    };
+    if outcome.matches() {
+        info!("Replay finished after {} ticks; currency pools match", outcome.final_tick);
+    } else {
+        error!(
+            "Replay diverged after {} ticks: expected currency hash {:016x}, got {:016x}",
+            outcome.final_tick, outcome.expected_hash, outcome.actual_hash
+        );
+    }
+
+    if let Some(config) = playback.suspended_mutations {
+        world.insert_resource(config);
+    }
+    if let Some(strategy) = playback.suspended_time_strategy {
+        world.insert_resource(strategy);
+    }
+    world.insert_resource(outcome);
+}
+
+/// [`GameAction::ToggleRecording`] (`F9`) toggles recording, [`GameAction::PlayReplay`]
+/// (`F10`) plays back the last recorded session.
+pub fn replay_hotkeys(
+    actions: Res<ActionState<GameAction>>,
+    recorder: Option<Res<ReplayRecorder>>,
+    mut recording_writer: EventWriter<RecordingRequest>,
+    mut playback_writer: EventWriter<PlaybackRequest>,
+) {
+    if actions.just_pressed(&GameAction::ToggleRecording) {
+        if recorder.is_some() {
+            recording_writer.send(RecordingRequest::Stop(DEFAULT_REPLAY_PATH.into()));
+        } else {
+            recording_writer.send(RecordingRequest::Start);
+        }
+    }
+    if actions.just_pressed(&GameAction::PlayReplay) && recorder.is_none() {
+        playback_writer.send(PlaybackRequest(DEFAULT_REPLAY_PATH.into()));
+    }
+}
+
+// --- Plugin ---
+
+pub struct ReplayPlugin;
+
+impl Plugin for ReplayPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<RecordingRequest>()
+            .add_event::<PlaybackRequest>()
+            // Movement is only sent by the windowed player, but replays carry it everywhere
+            .add_event::<MovementAction>()
+            .add_systems(
+                PreUpdate,
+                record_state_transitions.run_if(resource_exists::<ReplayRecorder>),
+            )
+            .add_systems(
+                Last,
+                (
+                    record_inputs,
+                    handle_replay_requests,
+                    advance_playback.run_if(resource_exists::<ReplayPlayback>),
+                )
+                    .chain(),
+            );
+    }
+}
@@ -0,0 +1,529 @@
+//! # Headless Scenarios
+//!
+//! A scenario file describes a starting point for the simulation (currency pools, genome,
+//! block rates and the cell environment) plus how many metabolic ticks to run. Scenarios are
+//! executed on [`MetabolisticApp::new_headless`], so no window is ever opened, and produce a
+//! [`CurrencyTimeSeries`] that can be written as CSV or JSON for balancing work.
+//!
+//! Currencies not listed in the scenario keep their `CurrencyPools::with_defaults` amounts;
+//! rates and the genome that are left out keep whatever the plugins install.
+//!
+//! Scenarios double as regression tests. A timeline of [`TimedAction`]s changes the
+//! simulation mid-run, and [`Expectation`]s are checked against the recorded series:
+//!
+//! ```json
+//! {
+//!   "ticks": 100,
+//!   "actions": [
+//!     { "tick": 10, "action": "express_gene", "gene": "fermentation" },
+//!     { "tick": 50, "action": "set_fermentation_rate", "rate": 2.0 }
+//!   ],
+//!   "expectations": [
+//!     { "expect": "at_tick", "tick": 100, "currency": "atp", "min": 120.0, "max": 160.0 },
+//!     { "expect": "always", "currency": "organic_waste", "max": 40.0 }
+//!   ]
+//! }
+//! ```
+//!
+//! Actions scheduled for tick `n` are applied once tick `n` has been recorded, so they first
+//! affect tick `n + 1`. Files in `tests/scenarios/` are run by `cargo test`.
+//!
+//! ## Usage
+//!
+//! ```rust,no_run
+//! use metabolistic3d::scenario::Scenario;
+//!
+//! let scenario = Scenario::load_from_file("scenarios/fermentation.json").unwrap();
+//! let series = scenario.run().unwrap();
+//! series.save_to_file("out/fermentation.csv").unwrap();
+//! ```
+//!
+//! The `simulate` binary wraps this for batch runs:
+//! `cargo run --bin simulate -- scenarios/*.json --out-dir out`.
+
+use std::collections::BTreeMap;
+use std::path::Path;
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::blocks::fermentation::FermentationRate;
+use crate::blocks::genome::{BlockKind, Genome, GenomeLoadError, GenomeSaveData};
+use crate::blocks::vesicle_export::VesicleExportRate;
+use crate::metabolism::cells::{player_cell, player_genome_mut, player_pools_mut};
+use crate::metabolism::{run_ticks, CurrencyPools, MetabolicTick};
+use crate::metrics::MetricsHistory;
+use crate::molecules::{CellMass, Currency, LipidToxicityThreshold, PolyMer};
+use crate::MetabolisticApp;
+
+// --- Data format ---
+
+/// Initial state and run length for one headless simulation.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Scenario {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    /// Number of metabolic ticks to simulate.
+    pub ticks: u64,
+    /// Starting amounts, applied on top of the default pools.
+    #[serde(default)]
+    pub currencies: BTreeMap<Currency, f32>,
+    /// Genome in `GenomeSaveData` form (any saved version); the starter genome if absent.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub genome: Option<serde_json::Value>,
+    #[serde(default)]
+    pub rates: BlockRates,
+    #[serde(default)]
+    pub environment: Environment,
+    /// Changes applied while the scenario runs, in tick order.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub actions: Vec<TimedAction>,
+    /// Checks made against the recorded time series.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub expectations: Vec<Expectation>,
+}
+
+/// Per-block rate resources; `None` keeps the plugin default.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct BlockRates {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fermentation: Option<f32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub vesicle_export: Option<f32>,
+}
+
+/// The cell's surroundings: toxicity limits and the storage-carrying cell bodies.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Environment {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub lipid_toxicity_threshold: Option<f32>,
+    #[serde(default)]
+    pub cells: Vec<CellSetup>,
+}
+
+/// A cell body spawned before the run starts.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CellSetup {
+    pub cell_mass: CellMass,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub polymer: Option<PolyMer>,
+}
+
+/// An action scheduled on the scenario timeline.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TimedAction {
+    pub tick: u64,
+    #[serde(flatten)]
+    pub action: ScenarioAction,
+}
+
+/// A change a designer can make while a scenario runs.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "action", rename_all = "snake_case")]
+pub enum ScenarioAction {
+    /// Express a gene, adding it to the genome first if it is missing.
+    ExpressGene { gene: BlockKind },
+    SilenceGene { gene: BlockKind },
+    MutateGene { gene: BlockKind },
+    RepairGene { gene: BlockKind },
+    SetCurrency { currency: Currency, amount: f32 },
+    /// Add `amount` (negative to remove) to a currency pool.
+    AddCurrency { currency: Currency, amount: f32 },
+    SetFermentationRate { rate: f32 },
+    SetVesicleExportRate { rate: f32 },
+    SetLipidToxicityThreshold { threshold: f32 },
+}
+
+/// A condition on the currency time series; `min` and `max` are inclusive and optional.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "expect", rename_all = "snake_case")]
+pub enum Expectation {
+    /// The currency is within bounds at one tick.
+    AtTick {
+        tick: u64,
+        currency: Currency,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        min: Option<f32>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        max: Option<f32>,
+    },
+    /// The currency stays within bounds on every tick from `from` to `until` (inclusive).
+    Always {
+        currency: Currency,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        min: Option<f32>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        max: Option<f32>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        from: Option<u64>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        until: Option<u64>,
+    },
+}
+
+/// An expectation that did not hold, with the first tick it failed on.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ExpectationFailure {
+    /// Position of the expectation in [`Scenario::expectations`].
+    pub index: usize,
+    pub tick: Option<u64>,
+    pub message: String,
+}
+
+impl std::fmt::Display for ExpectationFailure {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.tick {
+            Some(tick) => write!(
+                f,
+                "expectation #{} failed at tick {}: {}",
+                self.index, tick, self.message
+            ),
+            None => write!(f, "expectation #{} failed: {}", self.index, self.message),
+        }
+    }
+}
+
+/// Errors that can occur while loading or running a scenario
+#[derive(Debug)]
+pub enum ScenarioError {
+    Io(std::io::Error),
+    MalformedJson(serde_json::Error),
+    Genome(GenomeLoadError),
+}
+
+impl std::fmt::Display for ScenarioError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ScenarioError::Io(err) => write!(f, "scenario I/O error: {}", err),
+            ScenarioError::MalformedJson(err) => write!(f, "malformed scenario JSON: {}", err),
+            ScenarioError::Genome(err) => write!(f, "invalid genome in scenario: {}", err),
+        }
+    }
+}
+
+impl std::error::Error for ScenarioError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            ScenarioError::Io(err) => Some(err),
+            ScenarioError::MalformedJson(err) => Some(err),
+            ScenarioError::Genome(err) => Some(err),
+        }
+    }
+}
+
+impl From<std::io::Error> for ScenarioError {
+    fn from(err: std::io::Error) -> Self {
+        ScenarioError::Io(err)
+    }
+}
+
+impl From<serde_json::Error> for ScenarioError {
+    fn from(err: serde_json::Error) -> Self {
+        ScenarioError::MalformedJson(err)
+    }
+}
+
+impl From<GenomeLoadError> for ScenarioError {
+    fn from(err: GenomeLoadError) -> Self {
+        ScenarioError::Genome(err)
+    }
+}
+
+// --- Time series ---
+
+/// Currency pool contents sampled once per tick; tick 0 is the state before the first tick.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct CurrencyTimeSeries {
+    pub samples: Vec<CurrencySample>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CurrencySample {
+    pub tick: u64,
+    pub currencies: BTreeMap<Currency, f32>,
+}
+
+impl CurrencySample {
+    /// Sample the current pools of the player's cell in `world`.
+    pub fn capture(world: &World) -> Self {
+        let tick = world.get_resource::<MetabolicTick>().map(|t| t.0).unwrap_or(0);
+        let currencies = player_cell(world)
+            .and_then(|cell| world.get::<CurrencyPools>(cell))
+            .map(|pools| pools.pools.iter().map(|(&c, &v)| (c, v)).collect())
+            .unwrap_or_default();
+        Self { tick, currencies }
+    }
+}
+
+impl CurrencyTimeSeries {
+    /// Every currency that appears in any sample, in `Currency` order.
+    pub fn currencies(&self) -> Vec<Currency> {
+        let mut currencies: Vec<Currency> = self
+            .samples
+            .iter()
+            .flat_map(|s| s.currencies.keys().copied())
+            .collect();
+        currencies.sort();
+        currencies.dedup();
+        currencies
+    }
+
+    /// One row per tick, one column per currency (snake_case names, as in JSON).
+    pub fn to_csv(&self) -> String {
+        let currencies = self.currencies();
+        let mut csv = String::from("tick");
+        for currency in &currencies {
+            csv.push(',');
+            csv.push_str(&currency_column(*currency));
+        }
+        csv.push('\n');
+        for sample in &self.samples {
+            csv.push_str(&sample.tick.to_string());
+            for currency in &currencies {
+                let amount = sample.currencies.get(currency).copied().unwrap_or(0.0);
+                csv.push_str(&format!(",{}", amount));
+            }
+            csv.push('\n');
+        }
+        csv
+    }
+
+    /// Serialize the series to a JSON string
+    pub fn to_json(&self) -> Result<String, serde_json::Error> {
+        serde_json::to_string_pretty(self)
+    }
+
+    /// Write the series to `path` as JSON if it ends in `.json`, CSV otherwise.
+    pub fn save_to_file(&self, path: impl AsRef<Path>) -> Result<(), ScenarioError> {
+        let path = path.as_ref();
+        if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+            std::fs::create_dir_all(parent)?;
+        }
+        let contents = match path.extension().and_then(|e| e.to_str()) {
+            Some("json") => self.to_json()?,
+            _ => self.to_csv(),
+        };
+        std::fs::write(path, contents)?;
+        Ok(())
+    }
+}
+
+fn currency_column(currency: Currency) -> String {
+    serde_json::to_value(currency)
+        .ok()
+        .and_then(|v| v.as_str().map(str::to_string))
+        .unwrap_or_else(|| format!("{:?}", currency))
+}
+
+// --- Timeline & expectations ---
+
+impl ScenarioAction {
+    /// Apply the action to `world`. Genome changes reach the metabolic nodes on the next update.
+    pub fn apply(&self, world: &mut World) {
+        match *self {
+            ScenarioAction::ExpressGene { gene } => {
+                let mut genome = player_genome_mut(world);
+                if genome.get_gene_state(&gene).is_none() {
+                    genome.add_gene(gene);
+                }
+                genome.express_gene(gene);
+            }
+            ScenarioAction::SilenceGene { gene } => {
+                player_genome_mut(world).silence_gene(gene);
+            }
+            ScenarioAction::MutateGene { gene } => {
+                player_genome_mut(world).mutate_gene(gene);
+            }
+            ScenarioAction::RepairGene { gene } => {
+                player_genome_mut(world).repair_gene(gene);
+            }
+            ScenarioAction::SetCurrency { currency, amount } => {
+                player_pools_mut(world).set(currency, amount);
+            }
+            ScenarioAction::AddCurrency { currency, amount } => {
+                player_pools_mut(world).modify(currency, amount);
+            }
+            ScenarioAction::SetFermentationRate { rate } => {
+                world.insert_resource(FermentationRate(rate));
+            }
+            ScenarioAction::SetVesicleExportRate { rate } => {
+                world.insert_resource(VesicleExportRate(rate));
+            }
+            ScenarioAction::SetLipidToxicityThreshold { threshold } => {
+                world.insert_resource(LipidToxicityThreshold(threshold));
+            }
+        }
+    }
+}
+
+impl Expectation {
+    /// Check the expectation against `series`, returning the first violation.
+    pub fn check(&self, series: &CurrencyTimeSeries) -> Result<(), (Option<u64>, String)> {
+        match *self {
+            Expectation::AtTick { tick, currency, min, max } => {
+                let sample = series
+                    .samples
+                    .iter()
+                    .find(|s| s.tick == tick)
+                    .ok_or_else(|| (None, format!("tick {} was never reached", tick)))?;
+                check_bounds(sample, currency, min, max).map_err(|msg| (Some(tick), msg))
+            }
+            Expectation::Always { currency, min, max, from, until } => {
+                let from = from.unwrap_or(0);
+                let until = until.unwrap_or(u64::MAX);
+                series
+                    .samples
+                    .iter()
+                    .filter(|s| s.tick >= from && s.tick <= until)
+                    .try_for_each(|sample| {
+                        check_bounds(sample, currency, min, max)
+                            .map_err(|msg| (Some(sample.tick), msg))
+                    })
+            }
+        }
+    }
+}
+
+fn check_bounds(
+    sample: &CurrencySample,
+    currency: Currency,
+    min: Option<f32>,
+    max: Option<f32>,
+) -> Result<(), String> {
+    let amount = sample.currencies.get(&currency).copied().unwrap_or(0.0);
+    let name = currency_column(currency);
+    if let Some(min) = min.filter(|&min| amount < min) {
+        return Err(format!("{} is {}, expected at least {}", name, amount, min));
+    }
+    if let Some(max) = max.filter(|&max| amount > max) {
+        return Err(format!("{} is {}, expected at most {}", name, amount, max));
+    }
+    Ok(())
+}
+
+// --- Running ---
+
+impl Scenario {
+    /// Deserialize a scenario from a JSON string
+    pub fn from_json(json: &str) -> Result<Self, ScenarioError> {
+        Ok(serde_json::from_str(json)?)
+    }
+
+    /// Serialize the scenario to a JSON string
+    pub fn to_json(&self) -> Result<String, serde_json::Error> {
+        serde_json::to_string_pretty(self)
+    }
+
+    /// Read a scenario from `path`
+    pub fn load_from_file(path: impl AsRef<Path>) -> Result<Self, ScenarioError> {
+        Self::from_json(&std::fs::read_to_string(path)?)
+    }
+
+    /// Apply the initial state to `world`, which must already have run its startup systems.
+    ///
+    /// Genome changes reach the metabolic nodes on the next `App::update`.
+    pub fn apply(&self, world: &mut World) -> Result<(), ScenarioError> {
+        // Validate the genome before touching the world so a bad file leaves it intact
+        let genome: Option<Genome> = match &self.genome {
+            Some(value) => Some(GenomeSaveData::from_value(value.clone())?.into()),
+            None => None,
+        };
+
+        let mut pools = CurrencyPools::with_defaults();
+        for (&currency, &amount) in &self.currencies {
+            pools.set(currency, amount);
+        }
+        *player_pools_mut(world) = pools;
+
+        if let Some(genome) = genome {
+            *player_genome_mut(world) = genome;
+        }
+        if let Some(rate) = self.rates.fermentation {
+            world.insert_resource(FermentationRate(rate));
+        }
+        if let Some(rate) = self.rates.vesicle_export {
+            world.insert_resource(VesicleExportRate(rate));
+        }
+        if let Some(threshold) = self.environment.lipid_toxicity_threshold {
+            world.insert_resource(LipidToxicityThreshold(threshold));
+        }
+        for cell in &self.environment.cells {
+            let mut entity = world.spawn(cell.cell_mass.clone());
+            if let Some(polymer) = &cell.polymer {
+                entity.insert(polymer.clone());
+            }
+        }
+        Ok(())
+    }
+
+    /// Build a headless app with this scenario applied, ready for its first tick.
+    pub fn build_app(&self) -> Result<App, ScenarioError> {
+        let mut app = MetabolisticApp::new_headless();
+        app.update();
+        self.apply(app.world_mut())?;
+        // Propagate the genome to the metabolic nodes
+        app.update();
+        Ok(app)
+    }
+
+    /// Run the scenario for its configured number of ticks, applying the action timeline,
+    /// and record every tick.
+    pub fn run(&self) -> Result<CurrencyTimeSeries, ScenarioError> {
+        self.run_with_metrics().map(|(series, _)| series)
+    }
+
+    /// Like [`Scenario::run`], also returning the [`MetricsHistory`] of every tick run.
+    pub fn run_with_metrics(&self) -> Result<(CurrencyTimeSeries, MetricsHistory), ScenarioError> {
+        let mut app = self.build_app()?;
+        let capacity = usize::try_from(self.ticks).unwrap_or(usize::MAX);
+        app.insert_resource(MetricsHistory::with_capacity(capacity));
+
+        let mut actions: Vec<&TimedAction> = self.actions.iter().collect();
+        // Stable, so actions on the same tick keep their file order
+        actions.sort_by_key(|a| a.tick);
+        let mut actions = actions.into_iter().peekable();
+
+        let mut series = CurrencyTimeSeries::default();
+        for tick in 0..=self.ticks {
+            if tick > 0 {
+                run_ticks(app.world_mut(), 1);
+            }
+            series.samples.push(CurrencySample::capture(app.world()));
+
+            let mut applied = false;
+            while let Some(timed) = actions.next_if(|a| a.tick <= tick) {
+                timed.action.apply(app.world_mut());
+                applied = true;
+            }
+            if applied {
+                // Let genome and tick rate changes propagate without advancing the fixed clock
+                app.update();
+            }
+        }
+        let metrics = app
+            .world_mut()
+            .remove_resource::<MetricsHistory>()
+            .unwrap_or_default();
+        Ok((series, metrics))
+    }
+
+    /// Check every expectation against `series`.
+    pub fn evaluate(&self, series: &CurrencyTimeSeries) -> Vec<ExpectationFailure> {
+        self.expectations
+            .iter()
+            .enumerate()
+            .filter_map(|(index, expectation)| {
+                expectation.check(series).err().map(|(tick, message)| ExpectationFailure {
+                    index,
+                    tick,
+                    message,
+                })
+            })
+            .collect()
+    }
+
+    /// Run the scenario and return any failed expectations.
+    pub fn check(&self) -> Result<Vec<ExpectationFailure>, ScenarioError> {
+        Ok(self.evaluate(&self.run()?))
+    }
+}
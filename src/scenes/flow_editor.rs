@@ -0,0 +1,572 @@
+//! # Flow Editor
+//!
+//! The node-graph canvas described in `flowmap_design.md`. Every [`MetabolicNode`] is drawn
+//! as a box with an input port for each currency its [`FluxProfile`] consumes and an output
+//! port for each currency it produces. Dragging from an output port onto an input port of
+//! the same currency wires the two nodes with a [`FlowEdge`].
+//!
+//! Edits are staged in a [`FlowDraft`] while metabolism keeps running on the live graph.
+//! Pressing "Apply" (or [`GameAction::ApplyFlowEdit`]) commits the draft: edge entities are spawned and despawned
+//! to match it and [`FlowDirty`] is set, so the next metabolic tick runs `rebuild_graph`.
+//! The editor systems run every frame in their own [`EditorSchedule`].
+
+use std::collections::HashMap;
+
+use bevy::ecs::schedule::ScheduleLabel;
+use bevy::prelude::*;
+use bevy_egui::{egui, EguiContexts};
+use leafwing_input_manager::prelude::*;
+
+use crate::input::GameAction;
+use crate::metabolism::cells::CellMember;
+use crate::metabolism::{BlockStatus, FlowDirty, FlowEdge, FluxProfile, MetabolicEdge, MetabolicNode};
+use crate::molecules::Currency;
+use crate::GameState;
+
+const NODE_WIDTH: f32 = 190.0;
+const HEADER_HEIGHT: f32 = 24.0;
+const PORT_ROW_HEIGHT: f32 = 20.0;
+const PORT_RADIUS: f32 = 6.0;
+const NODES_PER_ROW: usize = 4;
+
+/// Flow editor plugin
+pub struct FlowEditorPlugin;
+
+impl Plugin for FlowEditorPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<FlowCanvasLayout>()
+            .add_event::<FlowEditorCommand>()
+            .add_schedule(build_editor_schedule())
+            .add_systems(OnEnter(GameState::FlowEditor), setup_editor_ui)
+            .add_systems(
+                Update,
+                run_editor_schedule.run_if(in_state(GameState::FlowEditor)),
+            )
+            .add_systems(OnExit(GameState::FlowEditor), teardown_editor_ui);
+    }
+}
+
+/// Per-frame schedule for the editor UI; only runs while in [`GameState::FlowEditor`].
+#[derive(ScheduleLabel, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct EditorSchedule;
+
+fn build_editor_schedule() -> Schedule {
+    let mut schedule = Schedule::new(EditorSchedule);
+    schedule.add_systems((editor_input_system, render_canvas_system, commit_edits_system).chain());
+    schedule
+}
+
+fn run_editor_schedule(world: &mut World) {
+    world.run_schedule(EditorSchedule);
+}
+
+// --- Draft ---
+
+/// The currencies a node consumes (input ports) and produces (output ports).
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct NodePorts {
+    pub inputs: Vec<Currency>,
+    pub outputs: Vec<Currency>,
+}
+
+impl NodePorts {
+    /// Read the ports off a flux profile, in `Currency` order.
+    pub fn from_profile(profile: &FluxProfile) -> Self {
+        let mut ports = Self::default();
+        for (&currency, &amount) in &profile.0 {
+            if amount < 0.0 {
+                ports.inputs.push(currency);
+            } else if amount > 0.0 {
+                ports.outputs.push(currency);
+            }
+        }
+        ports.inputs.sort();
+        ports.outputs.sort();
+        ports
+    }
+}
+
+/// Why a wire could not be added to a [`FlowDraft`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FlowEditError {
+    /// A node cannot feed itself
+    SelfLoop,
+    /// The source node has no output port for the currency
+    NotProduced(Currency),
+    /// The target node has no input port for the currency
+    NotConsumed(Currency),
+    /// The two ports are already wired
+    Duplicate,
+}
+
+impl std::fmt::Display for FlowEditError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            FlowEditError::SelfLoop => write!(f, "a block cannot feed itself"),
+            FlowEditError::NotProduced(currency) => {
+                write!(f, "source block does not produce {:?}", currency)
+            }
+            FlowEditError::NotConsumed(currency) => {
+                write!(f, "target block does not consume {:?}", currency)
+            }
+            FlowEditError::Duplicate => write!(f, "those ports are already wired"),
+        }
+    }
+}
+
+impl std::error::Error for FlowEditError {}
+
+/// Copy-on-write staging area for wiring edits.
+///
+/// Holds the wires that were live when the draft was taken; the first edit copies them
+/// and later edits change only the copy, so an untouched draft costs nothing.
+#[derive(Resource, Debug, Clone, Default)]
+pub struct FlowDraft {
+    live: Vec<FlowEdge>,
+    edited: Option<Vec<FlowEdge>>,
+}
+
+impl FlowDraft {
+    /// Start a draft from the given live wires.
+    pub fn new(live: Vec<FlowEdge>) -> Self {
+        Self { live, edited: None }
+    }
+
+    /// Start a draft from the wires currently in `world`.
+    pub fn from_world(world: &mut World) -> Self {
+        let mut query = world.query_filtered::<&FlowEdge, With<MetabolicEdge>>();
+        Self::new(query.iter(world).copied().collect())
+    }
+
+    /// The wires as they would be after committing.
+    pub fn edges(&self) -> &[FlowEdge] {
+        self.edited.as_deref().unwrap_or(&self.live)
+    }
+
+    /// Wires in the draft that are not live yet.
+    pub fn added(&self) -> Vec<FlowEdge> {
+        self.edges()
+            .iter()
+            .filter(|edge| !self.live.contains(edge))
+            .copied()
+            .collect()
+    }
+
+    /// Live wires the draft removes.
+    pub fn removed(&self) -> Vec<FlowEdge> {
+        self.live
+            .iter()
+            .filter(|edge| !self.edges().contains(edge))
+            .copied()
+            .collect()
+    }
+
+    pub fn is_modified(&self) -> bool {
+        !self.added().is_empty() || !self.removed().is_empty()
+    }
+
+    /// Wire `edge.from`'s output port to `edge.to`'s input port for `edge.currency`.
+    pub fn connect(
+        &mut self,
+        edge: FlowEdge,
+        from: &NodePorts,
+        to: &NodePorts,
+    ) -> Result<(), FlowEditError> {
+        if edge.from == edge.to {
+            return Err(FlowEditError::SelfLoop);
+        }
+        if !from.outputs.contains(&edge.currency) {
+            return Err(FlowEditError::NotProduced(edge.currency));
+        }
+        if !to.inputs.contains(&edge.currency) {
+            return Err(FlowEditError::NotConsumed(edge.currency));
+        }
+        if self.edges().contains(&edge) {
+            return Err(FlowEditError::Duplicate);
+        }
+        self.edges_mut().push(edge);
+        Ok(())
+    }
+
+    /// Remove a wire from the draft; false if it was not there.
+    pub fn disconnect(&mut self, edge: &FlowEdge) -> bool {
+        if !self.edges().contains(edge) {
+            return false;
+        }
+        self.edges_mut().retain(|e| e != edge);
+        true
+    }
+
+    /// Drop every staged edit.
+    pub fn revert(&mut self) {
+        self.edited = None;
+    }
+
+    fn edges_mut(&mut self) -> &mut Vec<FlowEdge> {
+        let live = &self.live;
+        self.edited.get_or_insert_with(|| live.clone())
+    }
+}
+
+/// Canvas position of each node's top-left corner; kept between editor visits.
+#[derive(Resource, Debug, Clone, Default)]
+pub struct FlowCanvasLayout(pub HashMap<Entity, Vec2>);
+
+/// Commit or discard the staged edits, handled by [`commit_edits_system`].
+#[derive(Event, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FlowEditorCommand {
+    Apply,
+    Revert,
+}
+
+/// What a commit changed in the live graph.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct FlowCommit {
+    pub added: usize,
+    pub removed: usize,
+}
+
+/// Make the live wiring in `world` match its [`FlowDraft`], then start a fresh draft.
+///
+/// Wires whose nodes were despawned while editing are dropped. Sets [`FlowDirty`] when
+/// anything changed, so the graph is rebuilt on the next metabolic tick.
+pub fn commit_flow_draft(world: &mut World) -> FlowCommit {
+    let Some(draft) = world.get_resource::<FlowDraft>() else {
+        return FlowCommit::default();
+    };
+    let wanted = draft.edges().to_vec();
+
+    let mut query = world.query_filtered::<(Entity, &FlowEdge), With<MetabolicEdge>>();
+    let live: Vec<(Entity, FlowEdge)> = query.iter(world).map(|(e, w)| (e, *w)).collect();
+
+    let mut commit = FlowCommit::default();
+    for (entity, wire) in &live {
+        if !wanted.contains(wire) {
+            world.entity_mut(*entity).despawn_recursive();
+            commit.removed += 1;
+        }
+    }
+    for wire in wanted {
+        let exists = live.iter().any(|(_, w)| *w == wire);
+        let endpoints_alive =
+            world.entities().contains(wire.from) && world.entities().contains(wire.to);
+        if !exists && endpoints_alive {
+            world.spawn((
+                MetabolicEdge,
+                wire,
+                Name::new(format!("Flow Edge: {:?}", wire.currency)),
+            ));
+            commit.added += 1;
+        }
+    }
+
+    if commit != FlowCommit::default() {
+        world.resource_mut::<FlowDirty>().0 = true;
+    }
+    let draft = FlowDraft::from_world(world);
+    world.insert_resource(draft);
+    commit
+}
+
+// --- Systems ---
+
+/// A marker component for entities that belong to the flow editor scene.
+#[derive(Component)]
+struct FlowEditorEntity;
+
+fn setup_editor_ui(world: &mut World) {
+    let draft = FlowDraft::from_world(world);
+    world.insert_resource(draft);
+    world.spawn((Camera2d, FlowEditorEntity));
+}
+
+fn teardown_editor_ui(
+    mut commands: Commands,
+    draft: Option<Res<FlowDraft>>,
+    entities: Query<Entity, With<FlowEditorEntity>>,
+) {
+    if let Some(draft) = draft {
+        if draft.is_modified() {
+            warn!("Discarded unapplied flow editor changes");
+        }
+    }
+    for entity in &entities {
+        commands.entity(entity).despawn_recursive();
+    }
+    commands.remove_resource::<FlowDraft>();
+}
+
+/// [`GameAction::ApplyFlowEdit`] applies the draft, [`GameAction::RevertFlowEdit`] reverts it.
+fn editor_input_system(
+    actions: Res<ActionState<GameAction>>,
+    mut editor_commands: EventWriter<FlowEditorCommand>,
+) {
+    if actions.just_pressed(&GameAction::ApplyFlowEdit) {
+        editor_commands.send(FlowEditorCommand::Apply);
+    }
+    if actions.just_pressed(&GameAction::RevertFlowEdit) {
+        editor_commands.send(FlowEditorCommand::Revert);
+    }
+}
+
+/// Apply or revert the draft with exclusive world access.
+pub fn commit_edits_system(world: &mut World) {
+    let commands: Vec<FlowEditorCommand> = world
+        .resource_mut::<Events<FlowEditorCommand>>()
+        .drain()
+        .collect();
+    for command in commands {
+        match command {
+            FlowEditorCommand::Apply => {
+                let commit = commit_flow_draft(world);
+                info!(
+                    "Applied flow edits: {} wires added, {} removed",
+                    commit.added, commit.removed
+                );
+            }
+            FlowEditorCommand::Revert => {
+                if let Some(mut draft) = world.get_resource_mut::<FlowDraft>() {
+                    draft.revert();
+                }
+            }
+        }
+    }
+}
+
+/// An output port being dragged towards an input port.
+#[derive(Debug, Clone, Copy)]
+struct PendingWire {
+    from: Entity,
+    currency: Currency,
+}
+
+/// A node as laid out on the canvas this frame.
+struct NodeView {
+    entity: Entity,
+    kind_label: String,
+    status: BlockStatus,
+    ports: NodePorts,
+    rect: egui::Rect,
+}
+
+impl NodeView {
+    fn input_pos(&self, currency: Currency) -> Option<egui::Pos2> {
+        let row = self.ports.inputs.iter().position(|&c| c == currency)?;
+        Some(egui::pos2(self.rect.left(), self.port_y(row)))
+    }
+
+    fn output_pos(&self, currency: Currency) -> Option<egui::Pos2> {
+        let row = self.ports.outputs.iter().position(|&c| c == currency)?;
+        Some(egui::pos2(self.rect.right(), self.port_y(row)))
+    }
+
+    fn port_y(&self, row: usize) -> f32 {
+        self.rect.top() + HEADER_HEIGHT + PORT_ROW_HEIGHT * (row as f32 + 0.5)
+    }
+}
+
+fn render_canvas_system(
+    mut contexts: EguiContexts,
+    mut draft: ResMut<FlowDraft>,
+    mut layout: ResMut<FlowCanvasLayout>,
+    nodes: Query<(Entity, &MetabolicNode, Option<&FluxProfile>), Without<CellMember>>,
+    mut editor_commands: EventWriter<FlowEditorCommand>,
+    mut pending: Local<Option<PendingWire>>,
+    mut message: Local<String>,
+) {
+    let mut sorted: Vec<(Entity, &MetabolicNode, Option<&FluxProfile>)> = nodes.iter().collect();
+    sorted.sort_by_key(|(entity, node, _)| (node.kind, *entity));
+
+    egui::CentralPanel::default().show(contexts.ctx_mut(), |ui| {
+        ui.horizontal(|ui| {
+            let modified = draft.is_modified();
+            if ui.add_enabled(modified, egui::Button::new("Apply")).clicked() {
+                editor_commands.send(FlowEditorCommand::Apply);
+            }
+            if ui.add_enabled(modified, egui::Button::new("Revert")).clicked() {
+                editor_commands.send(FlowEditorCommand::Revert);
+            }
+            ui.label(format!(
+                "{} wires staged, {} to remove",
+                draft.added().len(),
+                draft.removed().len()
+            ));
+            if !message.is_empty() {
+                ui.colored_label(egui::Color32::from_rgb(230, 160, 40), message.as_str());
+            }
+        });
+        ui.separator();
+
+        let (canvas, _) = ui.allocate_exact_size(ui.available_size(), egui::Sense::hover());
+        let painter = ui.painter_at(canvas);
+
+        // Lay out nodes, placing new ones on a grid and moving dragged ones
+        let mut views = Vec::with_capacity(sorted.len());
+        for (index, (entity, node, profile)) in sorted.iter().enumerate() {
+            let ports = profile.map(NodePorts::from_profile).unwrap_or_default();
+            let rows = ports.inputs.len().max(ports.outputs.len()).max(1);
+            let position = *layout.0.entry(*entity).or_insert_with(|| {
+                Vec2::new(
+                    40.0 + (index % NODES_PER_ROW) as f32 * (NODE_WIDTH + 80.0),
+                    30.0 + (index / NODES_PER_ROW) as f32 * 180.0,
+                )
+            });
+            let rect = egui::Rect::from_min_size(
+                canvas.min + egui::vec2(position.x, position.y),
+                egui::vec2(NODE_WIDTH, HEADER_HEIGHT + rows as f32 * PORT_ROW_HEIGHT),
+            );
+
+            let header = egui::Rect::from_min_size(rect.min, egui::vec2(NODE_WIDTH, HEADER_HEIGHT));
+            let response = ui.interact(header, ui.id().with(("flow_node", *entity)), egui::Sense::drag());
+            if response.dragged() {
+                let delta = response.drag_delta();
+                if let Some(position) = layout.0.get_mut(entity) {
+                    *position += Vec2::new(delta.x, delta.y);
+                }
+            }
+
+            views.push(NodeView {
+                entity: *entity,
+                kind_label: format!("{:?}", node.kind),
+                status: node.status,
+                ports,
+                rect,
+            });
+        }
+        let view_of = |entity: Entity| views.iter().find(|view| view.entity == entity);
+
+        // Wires, with a remove button at their midpoint
+        let mut to_remove = Vec::new();
+        for edge in draft.edges() {
+            let (Some(from), Some(to)) = (view_of(edge.from), view_of(edge.to)) else {
+                continue;
+            };
+            let (Some(start), Some(end)) = (from.output_pos(edge.currency), to.input_pos(edge.currency))
+            else {
+                continue;
+            };
+            let stroke = egui::Stroke::new(2.0, currency_color(edge.currency));
+            if from.status == BlockStatus::Active && to.status == BlockStatus::Active {
+                painter.line_segment([start, end], stroke);
+            } else {
+                // Dashed when either end is not running, as in the design doc
+                painter.extend(egui::Shape::dashed_line(&[start, end], stroke, 6.0, 4.0));
+            }
+
+            let midpoint = start + (end - start) * 0.5;
+            let button = egui::Rect::from_center_size(midpoint, egui::vec2(14.0, 14.0));
+            let response = ui.interact(button, ui.id().with(("flow_wire", *edge)), egui::Sense::click());
+            painter.circle_filled(midpoint, 7.0, egui::Color32::from_gray(40));
+            painter.text(
+                midpoint,
+                egui::Align2::CENTER_CENTER,
+                "x",
+                egui::FontId::proportional(11.0),
+                egui::Color32::WHITE,
+            );
+            if response.clicked() {
+                to_remove.push(*edge);
+            }
+        }
+
+        // Nodes and their ports
+        let mut drag_started = None;
+        for view in &views {
+            painter.rect_filled(view.rect, 4.0, status_color(view.status));
+            painter.text(
+                view.rect.left_top() + egui::vec2(8.0, HEADER_HEIGHT / 2.0),
+                egui::Align2::LEFT_CENTER,
+                &view.kind_label,
+                egui::FontId::proportional(14.0),
+                egui::Color32::WHITE,
+            );
+            for &currency in &view.ports.inputs {
+                let pos = view.input_pos(currency).expect("port belongs to node");
+                painter.circle_filled(pos, PORT_RADIUS, currency_color(currency));
+                painter.text(
+                    pos + egui::vec2(PORT_RADIUS + 4.0, 0.0),
+                    egui::Align2::LEFT_CENTER,
+                    format!("{:?}", currency),
+                    egui::FontId::proportional(11.0),
+                    egui::Color32::WHITE,
+                );
+            }
+            for &currency in &view.ports.outputs {
+                let pos = view.output_pos(currency).expect("port belongs to node");
+                painter.circle_filled(pos, PORT_RADIUS, currency_color(currency));
+                painter.text(
+                    pos - egui::vec2(PORT_RADIUS + 4.0, 0.0),
+                    egui::Align2::RIGHT_CENTER,
+                    format!("{:?}", currency),
+                    egui::FontId::proportional(11.0),
+                    egui::Color32::WHITE,
+                );
+                let port = egui::Rect::from_center_size(pos, egui::Vec2::splat(PORT_RADIUS * 2.5));
+                let id = ui.id().with(("flow_port", view.entity, currency));
+                if ui.interact(port, id, egui::Sense::drag()).drag_started() {
+                    drag_started = Some(PendingWire {
+                        from: view.entity,
+                        currency,
+                    });
+                }
+            }
+        }
+        if drag_started.is_some() {
+            *pending = drag_started;
+        }
+
+        // The wire being dragged follows the pointer and connects where it is released
+        if let Some(wire) = *pending {
+            let pointer = ui.input(|input| input.pointer.interact_pos());
+            let start = view_of(wire.from).and_then(|view| view.output_pos(wire.currency));
+            if let (Some(start), Some(pointer)) = (start, pointer) {
+                painter.line_segment([start, pointer], egui::Stroke::new(2.0, currency_color(wire.currency)));
+            }
+            if ui.input(|input| input.pointer.any_released()) {
+                *pending = None;
+                let target = pointer.and_then(|pointer| {
+                    views.iter().find(|view| {
+                        view.input_pos(wire.currency)
+                            .is_some_and(|pos| pos.distance(pointer) <= PORT_RADIUS * 2.0)
+                    })
+                });
+                if let (Some(target), Some(source)) = (target, view_of(wire.from)) {
+                    let edge = FlowEdge {
+                        from: wire.from,
+                        to: target.entity,
+                        currency: wire.currency,
+                    };
+                    *message = match draft.connect(edge, &source.ports, &target.ports) {
+                        Ok(()) => String::new(),
+                        Err(err) => err.to_string(),
+                    };
+                }
+            }
+        }
+
+        for edge in to_remove {
+            draft.disconnect(&edge);
+        }
+    });
+}
+
+fn status_color(status: BlockStatus) -> egui::Color32 {
+    match status {
+        BlockStatus::Active => egui::Color32::from_rgb(46, 125, 50),
+        BlockStatus::Mutated => egui::Color32::from_rgb(191, 128, 20),
+        BlockStatus::Silent => egui::Color32::from_gray(90),
+    }
+}
+
+fn currency_color(currency: Currency) -> egui::Color32 {
+    match currency {
+        Currency::ATP => egui::Color32::from_rgb(250, 210, 60),
+        Currency::ReducingPower => egui::Color32::from_rgb(90, 170, 250),
+        Currency::AcetylCoA => egui::Color32::from_rgb(240, 120, 60),
+        Currency::CarbonSkeletons => egui::Color32::from_rgb(160, 110, 70),
+        Currency::FreeFattyAcids => egui::Color32::from_rgb(200, 230, 90),
+        Currency::StorageBeads => egui::Color32::from_rgb(220, 220, 180),
+        Currency::Pyruvate => egui::Color32::from_rgb(230, 90, 160),
+        Currency::OrganicWaste => egui::Color32::from_rgb(140, 140, 140),
+    }
+}
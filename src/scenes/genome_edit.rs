@@ -1,9 +1,12 @@
 use crate::{
     blocks::genome::{self, BlockKind, GeneState},
+    input::GameAction,
+    metabolism::cells::PlayerCell,
     GameState,
 };
 use bevy::color::palettes::basic::{BLUE, GRAY, GREEN, LIME, MAROON, PURPLE, RED, YELLOW};
 use bevy::prelude::*;
+use leafwing_input_manager::prelude::ActionState;
 use std::f32::consts::TAU;
 
 /// Genome editing scene plugin
@@ -16,6 +19,7 @@ impl Plugin for GenomeEditPlugin {
                 Update,
                 (
                     navigate_genome,
+                    edit_selected_gene,
                     highlight_selection,
                     rotate_genome_ring,
                 ).run_if(in_state(GameState::GenomeEditing)),
@@ -69,6 +73,7 @@ fn get_block_color(block_kind: BlockKind, state: &GeneState) -> Color {
         BlockKind::SecondaryMetabolites => Color::srgb(1.0, 0.5, 0.8), // Pink
         BlockKind::AromaticPrecursorSynthesis => Color::srgb(0.5, 0.8, 1.0), // Light blue
         BlockKind::Polymerization => Color::srgb(0.8, 0.6, 0.4), // Brown
+        BlockKind::Motility => Color::srgb(0.9, 0.9, 0.9), // White
     };
 
     // Modify color based on gene state
@@ -82,11 +87,14 @@ fn get_block_color(block_kind: BlockKind, state: &GeneState) -> Color {
 /// Sets up the entire genome editing scene, including the camera, lighting, and the genome ring itself.
 fn setup_genome_scene(
     mut commands: Commands,
-    genome: Res<genome::Genome>,
+    genome: Query<&genome::Genome, With<PlayerCell>>,
     mut materials: ResMut<Assets<StandardMaterial>>,
     asset_server: Res<AssetServer>,
 ) {
-    let blocks: Vec<BlockKind> = genome.table.keys().copied().collect();
+    let genome = genome.get_single().ok();
+    let blocks: Vec<BlockKind> = genome
+        .map(|genome| genome.table.keys().copied().collect())
+        .unwrap_or_default();
 
     commands.insert_resource(GenomeSceneState {
         selected: 0,
@@ -143,7 +151,7 @@ fn setup_genome_scene(
                 let z = ring_radius * angle.sin();
 
                 let state = genome
-                    .get_gene_state(&block_kind)
+                    .and_then(|genome| genome.get_gene_state(&block_kind))
                     .unwrap_or(&GeneState::Silent);
                 let color = get_block_color(block_kind, state);
 
@@ -183,11 +191,11 @@ fn rotate_genome_ring(
     }
 }
 
-/// System to handle keyboard navigation for selecting genome sections.
-fn navigate_genome(input: Res<ButtonInput<KeyCode>>, mut scene_state: ResMut<GenomeSceneState>) {
-    if input.just_pressed(KeyCode::ArrowRight) {
+/// System to select genome sections with the gene navigation actions (arrow keys or D-pad).
+fn navigate_genome(actions: Res<ActionState<GameAction>>, mut scene_state: ResMut<GenomeSceneState>) {
+    if actions.just_pressed(&GameAction::NextGene) {
         scene_state.selected = (scene_state.selected + 1) % scene_state.blocks.len();
-    } else if input.just_pressed(KeyCode::ArrowLeft) {
+    } else if actions.just_pressed(&GameAction::PreviousGene) {
         if scene_state.selected == 0 {
             scene_state.selected = scene_state.blocks.len() - 1;
         } else {
@@ -196,14 +204,44 @@ fn navigate_genome(input: Res<ButtonInput<KeyCode>>, mut scene_state: ResMut<Gen
     }
 }
 
+/// System to edit the selected gene through the shared [`genome::GenomeCommand`] path:
+/// `Space` toggles expression and `R` repairs a mutated gene (see [`GameAction`]).
+fn edit_selected_gene(
+    actions: Res<ActionState<GameAction>>,
+    scene_state: Res<GenomeSceneState>,
+    genome: Query<&genome::Genome, With<PlayerCell>>,
+    mut genome_commands: EventWriter<genome::GenomeCommand>,
+) {
+    let Some(&block_kind) = scene_state.blocks.get(scene_state.selected) else {
+        return;
+    };
+    let Ok(genome) = genome.get_single() else {
+        return;
+    };
+
+    if actions.just_pressed(&GameAction::ToggleGene) {
+        let command = match genome.get_gene_state(&block_kind) {
+            Some(GeneState::Expressed) => genome::GenomeCommand::Silence(block_kind),
+            _ => genome::GenomeCommand::Express(block_kind),
+        };
+        genome_commands.send(command);
+    }
+    if actions.just_pressed(&GameAction::RepairGene) {
+        genome_commands.send(genome::GenomeCommand::Repair(block_kind));
+    }
+}
+
 /// System to update the material properties of genome sections based on the current selection.
 fn highlight_selection(
     mut materials: ResMut<Assets<StandardMaterial>>,
     // Query for the material handle and section data of each visible helix.
     query: Query<(&MeshMaterial3d<StandardMaterial>, &GenomeSection)>,
     scene_state: Res<GenomeSceneState>,
-    genome: Res<genome::Genome>,
+    genome: Query<&genome::Genome, With<PlayerCell>>,
 ) {
+    let Ok(genome) = genome.get_single() else {
+        return;
+    };
     for (material_handle, section) in query.iter() {
         // Get a mutable reference to the material asset itself from the handle.
         if let Some(mat) = materials.get_mut(&material_handle.0) {
@@ -1,5 +1,6 @@
 use bevy::prelude::*;
 
+use crate::snapshot::{LoadSimulationRequest, SaveSimulationRequest, DEFAULT_SNAPSHOT_PATH};
 use crate::GameState;
 
 pub struct MainMenuPlugin;
@@ -20,6 +21,9 @@ enum MenuButton {
     Scene3D,
     Scene2D,
     GenomeEditing,
+    FlowEditor,
+    SaveGame,
+    LoadGame,
 }
 
 fn setup_menu(mut commands: Commands) {
@@ -58,6 +62,9 @@ fn setup_menu(mut commands: Commands) {
             create_button(parent, "3D Scene", MenuButton::Scene3D);
             create_button(parent, "2D Scene", MenuButton::Scene2D);
             create_button(parent, "Genome Editor", MenuButton::GenomeEditing);
+            create_button(parent, "Flow Editor", MenuButton::FlowEditor);
+            create_button(parent, "Save Game", MenuButton::SaveGame);
+            create_button(parent, "Load Game", MenuButton::LoadGame);
         });
 }
 
@@ -94,6 +101,8 @@ fn button_system(
         (Changed<Interaction>, With<Button>),
     >,
     mut next_state: ResMut<NextState<GameState>>,
+    mut save_writer: EventWriter<SaveSimulationRequest>,
+    mut load_writer: EventWriter<LoadSimulationRequest>,
 ) {
     for (interaction, menu_button, mut color) in &mut interaction_query {
         match *interaction {
@@ -103,6 +112,13 @@ fn button_system(
                     MenuButton::Scene3D => next_state.set(GameState::Scene3D),
                     MenuButton::Scene2D => next_state.set(GameState::Scene2D),
                     MenuButton::GenomeEditing => next_state.set(GameState::GenomeEditing),
+                    MenuButton::FlowEditor => next_state.set(GameState::FlowEditor),
+                    MenuButton::SaveGame => {
+                        save_writer.send(SaveSimulationRequest(DEFAULT_SNAPSHOT_PATH.into()));
+                    }
+                    MenuButton::LoadGame => {
+                        load_writer.send(LoadSimulationRequest(DEFAULT_SNAPSHOT_PATH.into()));
+                    }
                 }
             }
             Interaction::Hovered => {
@@ -1,3 +1,4 @@
+pub mod flow_editor;
 pub mod genome_edit;
 pub mod menu;
 pub mod scene_2d;
@@ -1,5 +1,7 @@
+use crate::input::GameAction;
 use crate::GameState;
 use bevy::prelude::*;
+use leafwing_input_manager::prelude::*;
 use crate::blocks::genome::BlockKind;
 
 /// 2D top-down pseudo scene plugin
@@ -88,36 +90,24 @@ fn setup_2d_scene(
 
     info!("2D scene setup complete");
     info!("Controls:");
-    info!("  WASD - Move in 2D plane");
+    info!("  WASD / left stick - Move in 2D plane");
     info!("  Escape - Return to menu");
 }
 
-/// Handle 2D movement using keyboard input
+/// Handle 2D movement from [`GameAction::Move2D`]
 fn handle_2d_movement(
-    input: Res<ButtonInput<KeyCode>>,
+    actions: Res<ActionState<GameAction>>,
     time: Res<Time>,
     mut player_query: Query<(&mut Transform, &Player2D)>,
 ) {
+    // Up on the stick moves away from the camera
+    let axis = actions.clamped_axis_pair(&GameAction::Move2D).clamp_length_max(1.0);
+    let movement = Vec3::new(axis.x, 0.0, -axis.y);
+    if movement == Vec3::ZERO {
+        return;
+    }
     for (mut transform, player) in player_query.iter_mut() {
-        let mut movement = Vec3::ZERO;
-
-        if input.pressed(KeyCode::KeyW) {
-            movement.z -= 1.0;
-        }
-        if input.pressed(KeyCode::KeyS) {
-            movement.z += 1.0;
-        }
-        if input.pressed(KeyCode::KeyA) {
-            movement.x -= 1.0;
-        }
-        if input.pressed(KeyCode::KeyD) {
-            movement.x += 1.0;
-        }
-
-        if movement.length() > 0.0 {
-            movement = movement.normalize();
-            transform.translation += movement * player.speed * time.delta_secs();
-        }
+        transform.translation += movement * player.speed * time.delta_secs();
     }
 }
 
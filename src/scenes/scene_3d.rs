@@ -1,15 +1,20 @@
-use crate::{camera, player, GameState};
+use crate::{camera, player, terrain, GameState};
 use avian3d::prelude::*;
 use bevy::prelude::*;
-use crate::blocks::genome::BlockKind;
+use crate::blocks::genome::{create_starter_genome, BlockKind};
+use crate::metabolism::cells::{Cell, PlayerCell};
+use crate::npc::behaviour::Temperament;
+use crate::npc::spawn_npc;
 
 /// 3D rolling scene plugin
 pub struct Scene3DPlugin;
 
 impl Plugin for Scene3DPlugin {
     fn build(&self, app: &mut App) {
-        app.add_systems(OnEnter(GameState::Scene3D), setup_3d_scene)
+        app.add_systems(OnEnter(GameState::Scene3D), (setup_3d_scene, unstow_bodies))
             // Player and camera systems are handled by their respective plugins
+            .add_systems(Update, show_cells.run_if(in_state(GameState::Scene3D)))
+            .add_systems(Update, stow_bodies.run_if(not(in_state(GameState::Scene3D))))
             .add_systems(OnExit(GameState::Scene3D), cleanup_3d_scene)
             // Add 3D-specific plugins
             .add_plugins(player::PlayerPlugin);
@@ -29,24 +34,14 @@ fn setup_3d_scene(
     mut commands: Commands,
     mut meshes: ResMut<Assets<Mesh>>,
     mut materials: ResMut<Assets<StandardMaterial>>,
+    terrain: Res<terrain::Terrain>,
 ) {
     info!("Setting up 3D rolling scene");
 
-    // Create the floor
-    let floor_size = 500.0;
-    commands.spawn((
-        Mesh3d(meshes.add(Plane3d::default().mesh().size(floor_size, floor_size))),
-        MeshMaterial3d(materials.add(Color::srgb(0.3, 0.5, 0.3))),
-        Transform::from_xyz(0.0, 0.0, 0.0),
-        RigidBody::Static,
-        Collider::cuboid(floor_size / 2.0, 0.1, floor_size / 2.0),
-        Friction {
-            dynamic_coefficient: 1.0,
-            static_coefficient: 1.0,
-            combine_rule: CoefficientCombine::Multiply,
-        },
-        Scene3DEntity,
-    ));
+    // Create the ground
+    for chunk in terrain::spawn_terrain(&mut commands, &mut meshes, &mut materials, &terrain) {
+        commands.entity(chunk).insert(Scene3DEntity);
+    }
 
     // Add lighting
     commands.spawn((
@@ -79,19 +74,103 @@ fn setup_3d_scene(
     commands.entity(fermentation_entity).insert(Scene3DEntity);
     commands.entity(amino_entity).insert(Scene3DEntity);
 
+    commands.queue(spawn_npcs);
+
     info!("3D scene setup complete");
     info!("Controls:");
-    info!("  WASD - Move player");
-    info!("  Mouse - Look around");
-    info!("  Space - Jump");
-    info!("  Escape - Return to menu");
+    info!("  WASD / left stick - Move player");
+    info!("  Mouse / right stick - Look around");
+    info!("  Space / South - Jump");
+    info!("  Escape / Start - Return to menu");
+}
+
+/// Populate the scene with a few predators and prey around the player, once; their
+/// bodies are put away with the player's when the scene is left.
+fn spawn_npcs(world: &mut World) {
+    if world.query::<&Temperament>().iter(world).next().is_some() {
+        return;
+    }
+    let ground = |world: &World, x: f32, z: f32| {
+        world
+            .get_resource::<terrain::Terrain>()
+            .map_or(0.0, |terrain| terrain.height_at(x, z))
+    };
+    let cells = [
+        (Temperament::Predator, false, Vec2::new(12.0, 8.0)),
+        (Temperament::Predator, false, Vec2::new(-15.0, -10.0)),
+        (Temperament::Prey, false, Vec2::new(6.0, -5.0)),
+        (Temperament::Prey, false, Vec2::new(-6.0, 7.0)),
+        (Temperament::Prey, true, Vec2::new(9.0, 3.0)),
+        (Temperament::Prey, true, Vec2::new(-10.0, -3.0)),
+    ];
+    for (temperament, toxic, at) in cells {
+        let mut genome = create_starter_genome();
+        if toxic {
+            genome.add_gene(BlockKind::SecondaryMetabolites);
+            genome.express_gene(BlockKind::SecondaryMetabolites);
+        }
+        let position = Vec3::new(at.x, ground(world, at.x, at.y) + 1.0, at.y);
+        spawn_npc(world, temperament, genome, position);
+    }
+}
+
+/// Cells in the scene that have not been drawn yet.
+type CellsWithoutMesh = (With<Cell>, With<Transform>, Without<Mesh3d>, Without<PlayerCell>);
+
+/// Draw cells that appeared in the scene, and give those without one, such as daughters
+/// of a division, a body. Predators are red and prey green.
+fn show_cells(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    cells: Query<(Entity, Option<&Temperament>, Has<RigidBody>), CellsWithoutMesh>,
+) {
+    let radius = 0.5;
+    for (cell, temperament, has_body) in &cells {
+        let color = match temperament {
+            Some(Temperament::Predator) => Color::srgb(0.8, 0.3, 0.3),
+            Some(Temperament::Prey) => Color::srgb(0.4, 0.8, 0.4),
+            None => Color::srgb(0.6, 0.7, 0.8),
+        };
+        let mut cell = commands.entity(cell);
+        cell.insert((
+            Mesh3d(meshes.add(Sphere::new(radius).mesh())),
+            MeshMaterial3d(materials.add(color)),
+        ));
+        if !has_body {
+            cell.insert((RigidBody::Dynamic, Collider::sphere(radius)));
+        }
+    }
+}
+
+/// Bodies that belong to a cell, the player's or an NPC's, living on outside the scene.
+type CellBodies = (With<Cell>, With<RigidBody>);
+
+/// Take the bodies of cells out of physics and sight while the game is in another scene,
+/// where the ground they rest on is gone. The cells themselves, the player's genome and
+/// pools with them, keep metabolising.
+fn stow_bodies(mut commands: Commands, bodies: Query<Entity, (CellBodies, Without<RigidBodyDisabled>)>) {
+    for entity in &bodies {
+        commands
+            .entity(entity)
+            .insert((RigidBodyDisabled, ColliderDisabled, Visibility::Hidden));
+    }
+}
+
+/// Put the stowed bodies back where they were.
+fn unstow_bodies(mut commands: Commands, bodies: Query<Entity, (CellBodies, With<RigidBodyDisabled>)>) {
+    for entity in &bodies {
+        commands
+            .entity(entity)
+            .remove::<(RigidBodyDisabled, ColliderDisabled)>()
+            .insert(Visibility::Inherited);
+    }
 }
 
-/// Clean up 3D scene entities when leaving
+/// Clean up 3D scene entities when leaving; cell bodies are stowed instead.
 fn cleanup_3d_scene(
     mut commands: Commands,
     scene_entities: Query<Entity, With<Scene3DEntity>>,
-    player_entities: Query<Entity, With<player::Player>>,
     camera_entities: Query<Entity, With<Camera3D>>,
 ) {
     info!("Cleaning up 3D scene");
@@ -101,11 +180,6 @@ fn cleanup_3d_scene(
         commands.entity(entity).despawn_recursive();
     }
 
-    // Remove player entities
-    for entity in player_entities.iter() {
-        commands.entity(entity).despawn_recursive();
-    }
-
     // Remove any remaining cameras (safety check)
     for entity in camera_entities.iter() {
         commands.entity(entity).despawn_recursive();
@@ -1,5 +1,7 @@
+use crate::input::GameAction;
 use crate::{blocks::genome, GameState};
 use bevy::prelude::*;
+use leafwing_input_manager::prelude::ActionState;
 
 /// Shared resources and systems that persist across all game states
 pub fn setup_shared_resources(mut commands: Commands) {
@@ -10,78 +12,63 @@ pub fn setup_shared_resources(mut commands: Commands) {
     // Note: Metabolic block entities will be spawned by individual scenes as needed
 }
 
-/// Input system for state transitions
+/// Switch scenes with the scene actions (`1`-`4` and `Escape` by default)
 pub fn state_transition_input(
-    input: Res<ButtonInput<KeyCode>>,
+    actions: Res<ActionState<GameAction>>,
     current_state: Res<State<GameState>>,
     mut next_state: ResMut<NextState<GameState>>,
 ) {
-    // Press '1' for 3D scene
-    if input.just_pressed(KeyCode::Digit1) {
-        if current_state.get() != &GameState::Scene3D {
-            next_state.set(GameState::Scene3D);
-            info!("Switching to 3D scene");
-        }
-    }
-
-    // Press '2' for 2D scene
-    if input.just_pressed(KeyCode::Digit2) {
-        if current_state.get() != &GameState::Scene2D {
-            next_state.set(GameState::Scene2D);
-            info!("Switching to 2D scene");
-        }
-    }
-
-    // Press '3' for genome editing scene
-    if input.just_pressed(KeyCode::Digit3) {
-        if current_state.get() != &GameState::GenomeEditing {
-            next_state.set(GameState::GenomeEditing);
-            info!("Opening genome editor");
-        }
-    }
-
-    // Press 'Escape' for main menu
-    if input.just_pressed(KeyCode::Escape) {
-        if current_state.get() != &GameState::MainMenu {
-            next_state.set(GameState::MainMenu);
-            info!("Returning to main menu");
+    let scenes = [
+        (GameAction::Scene3D, GameState::Scene3D, "Switching to 3D scene"),
+        (GameAction::Scene2D, GameState::Scene2D, "Switching to 2D scene"),
+        (GameAction::GenomeEditor, GameState::GenomeEditing, "Opening genome editor"),
+        (GameAction::FlowEditor, GameState::FlowEditor, "Opening flow editor"),
+        (GameAction::MainMenu, GameState::MainMenu, "Returning to main menu"),
+    ];
+    for (action, scene, message) in scenes {
+        if actions.just_pressed(&action) && current_state.get() != &scene {
+            next_state.set(scene);
+            info!("{}", message);
         }
     }
 }
 
 /// Demo system to showcase genome functionality (works in all states)
 pub fn genome_demo_system(
-    mut genome: ResMut<genome::Genome>,
-    input: Res<ButtonInput<KeyCode>>,
+    actions: Res<ActionState<GameAction>>,
+    mut genome_commands: EventWriter<genome::GenomeCommand>,
     mut commands: Commands,
 ) {
-    // Press 'G' to express sugar catabolism gene
-    if input.just_pressed(KeyCode::KeyG) {
-        if genome.express_gene(genome::BlockKind::SugarCatabolism) {
-            info!("Expressed SugarCatabolism gene!");
-        } else {
-            warn!("Failed to express SugarCatabolism gene - already expressed or not present");
-        }
+    // 'G' expresses the sugar catabolism gene
+    if actions.just_pressed(&GameAction::ExpressSugarCatabolism) {
+        genome_commands.send(genome::GenomeCommand::Express(genome::BlockKind::SugarCatabolism));
     }
 
-    // Press 'H' to silence fermentation gene
-    if input.just_pressed(KeyCode::KeyH) {
-        if genome.silence_gene(genome::BlockKind::Fermentation) {
-            info!("Silenced Fermentation gene!");
-        } else {
-            warn!("Failed to silence Fermentation gene - not expressed or not present");
-        }
+    // 'H' silences the fermentation gene
+    if actions.just_pressed(&GameAction::SilenceFermentation) {
+        genome_commands.send(genome::GenomeCommand::Silence(genome::BlockKind::Fermentation));
     }
 
-    // Press 'J' to add a new gene
-    if input.just_pressed(KeyCode::KeyJ) {
-        genome.add_gene(genome::BlockKind::LightCapture);
-        info!("Added LightCapture gene to genome!");
+    // 'J' adds a new gene
+    if actions.just_pressed(&GameAction::AddLightCapture) {
+        genome_commands.send(genome::GenomeCommand::Add(genome::BlockKind::LightCapture));
     }
 
-    // Press 'K' to spawn metabolic block entities
-    if input.just_pressed(KeyCode::KeyK) {
+    // 'K' spawns metabolic block entities
+    if actions.just_pressed(&GameAction::SpawnRespiration) {
         genome::spawn_metabolic_block(&mut commands, genome::BlockKind::Respiration);
         info!("Spawned Respiration metabolic block entity!");
     }
 }
+
+/// `Ctrl+Z` undoes the last genome command, `Ctrl+Y` (or `Ctrl+Shift+Z`) redoes it
+pub fn genome_history_input(
+    actions: Res<ActionState<GameAction>>,
+    mut history_commands: EventWriter<genome::GenomeHistoryCommand>,
+) {
+    if actions.just_pressed(&GameAction::Redo) {
+        history_commands.send(genome::GenomeHistoryCommand::Redo);
+    } else if actions.just_pressed(&GameAction::Undo) {
+        history_commands.send(genome::GenomeHistoryCommand::Undo);
+    }
+}
@@ -0,0 +1,793 @@
+//! # Simulation Snapshots
+//!
+//! Captures the complete simulation state (currency pools, genome, metabolic block entities
+//! and their components, compartments, block rates and the current scene) into a single versioned JSON
+//! document, and restores it into a running or headless app.
+//!
+//! The player's genome, pools and body are stored at the top level, and its body is
+//! restored onto the player's own entity rather than respawned. The [`NutrientGrid`] is
+//! stored there too, so cells go on absorbing exactly what they would have. Every other [`Cell`] keeps
+//! its genome, pools and vitality on its own entity, and the blocks, wires and compartments
+//! of such a cell record which cell they are a [`CellMember`] of.
+//!
+//! Entities are given dense snapshot ids (`0..n`): entities restored from an earlier snapshot
+//! keep their [`SnapshotId`] order, anything spawned since follows in ascending `Entity` order.
+//! Loading despawns the current simulation entities and respawns them in id order, so the
+//! same snapshot always produces the same entity layout. The resulting id -> entity mapping
+//! is returned to the caller and stored in [`SnapshotEntityMap`].
+//!
+//! Scene decoration (meshes, cameras, the genome-controlled `blocks::genome::MetabolicBlock`
+//! markers spawned by each scene) is not captured; scenes rebuild it on `OnEnter`.
+//!
+//! ## Usage
+//!
+//! ```rust,no_run
+//! use metabolistic3d::{snapshot, MetabolisticApp};
+//!
+//! let mut app = MetabolisticApp::new_headless();
+//! app.update();
+//! snapshot::save_simulation(app.world_mut(), "saves/bug_report.json").unwrap();
+//! snapshot::load_simulation(app.world_mut(), "saves/bug_report.json").unwrap();
+//! ```
+//!
+//! In game, the main menu sends [`SaveSimulationRequest`] / [`LoadSimulationRequest`]
+//! events for [`DEFAULT_SNAPSHOT_PATH`].
+
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Path, PathBuf};
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::blocks::fermentation::{FermentationBlock, FermentationRate};
+use crate::blocks::genome::{Genome, GenomeLoadError, GenomeSaveData};
+use crate::blocks::vesicle_export::{VesicleExportBlock, VesicleExportRate};
+use crate::environment::{NutrientGrid, NutrientLevels};
+use crate::metabolism::cells::{
+    ensure_player_cell, player_cell, Cell, CellMember, CellMetabolism, PlayerCell,
+};
+use crate::metabolism::compartments::{
+    ensure_compartments, CellCompartments, Compartment, CompartmentKind, CompartmentPools,
+    InCompartment, TransportDirection, Transporter,
+};
+use crate::metabolism::toxicity::CellHealth;
+use crate::metabolism::solver::PendingFluxSolve;
+use crate::metabolism::vitality::{CellVitality, StarvationTicks};
+use crate::metabolism::{
+    CurrencyPools, FlowDirty, FlowEdge, FluxProfile, MetabolicBlock, MetabolicEdge, MetabolicNode,
+};
+use crate::molecules::{CellMass, Currency, LipidToxicityThreshold, PolyMer};
+use crate::npc::behaviour::Temperament;
+use crate::npc::init_npc;
+use crate::GameState;
+
+/// Version of the snapshot format written by [`SimulationSnapshot::to_json`].
+pub const SNAPSHOT_VERSION: u32 = 1;
+
+/// Where the main menu saves and loads its quick snapshot.
+pub const DEFAULT_SNAPSHOT_PATH: &str = "saves/quicksave.json";
+
+// --- Data format ---
+
+/// Complete, self-contained simulation state.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SimulationSnapshot {
+    pub version: u32,
+    /// Scene to return to on load; never `MainMenu` unless no scene was ever entered.
+    pub scene: GameState,
+    /// Currency pool contents, ordered by currency for stable output.
+    pub currencies: BTreeMap<Currency, f32>,
+    /// Stored as raw JSON so genome migrations also apply to snapshot files.
+    pub genome: serde_json::Value,
+    pub fermentation_rate: Option<f32>,
+    pub vesicle_export_rate: Option<f32>,
+    pub lipid_toxicity_threshold: Option<f32>,
+    #[serde(default)]
+    pub vitality: CellVitality,
+    /// Consecutive ticks an essential pool had been empty
+    #[serde(default)]
+    pub starvation_ticks: u32,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub player_mass: Option<CellMass>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub player_polymer: Option<PolyMer>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub player_health: Option<CellHealth>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub player_position: Option<[f32; 3]>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub nutrients: Option<NutrientGridSnapshot>,
+    pub entities: Vec<EntitySnapshot>,
+    /// Wires drawn in the flow editor, between entities of this snapshot
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub flow_edges: Vec<FlowEdgeSnapshot>,
+}
+
+/// The simulation-relevant components of a single entity.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct EntitySnapshot {
+    /// Dense snapshot-local id; stable across save/load, unrelated to `Entity` bits.
+    pub id: u32,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    #[serde(default)]
+    pub metabolic_block: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub node: Option<MetabolicNode>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub flux_profile: Option<BTreeMap<Currency, f32>>,
+    #[serde(default)]
+    pub fermentation_block: bool,
+    #[serde(default)]
+    pub vesicle_export_block: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cell_mass: Option<CellMass>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub polymer: Option<PolyMer>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub health: Option<CellHealth>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub compartment: Option<CompartmentSnapshot>,
+    /// Snapshot id of the compartment this block works in
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub in_compartment: Option<u32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub transporter: Option<TransporterSnapshot>,
+    /// Snapshot id of the cell this entity is a member of, unless it is the player's
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub member_of: Option<u32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cell: Option<CellSnapshot>,
+}
+
+/// A [`Cell`] other than the player's.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CellSnapshot {
+    pub currencies: BTreeMap<Currency, f32>,
+    /// Stored as raw JSON so genome migrations also apply to it.
+    pub genome: serde_json::Value,
+    #[serde(default)]
+    pub vitality: CellVitality,
+    #[serde(default)]
+    pub starvation_ticks: u32,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub temperament: Option<Temperament>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub position: Option<[f32; 3]>,
+}
+
+/// A [`NutrientGrid`] with the levels and baselines of its patches, row by row.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct NutrientGridSnapshot {
+    pub origin: [f32; 2],
+    pub patch_size: f32,
+    pub width: usize,
+    pub depth: usize,
+    pub levels: Vec<NutrientLevels>,
+    pub baseline: Vec<NutrientLevels>,
+}
+
+impl From<&NutrientGrid> for NutrientGridSnapshot {
+    fn from(grid: &NutrientGrid) -> Self {
+        Self {
+            origin: grid.origin.to_array(),
+            patch_size: grid.patch_size,
+            width: grid.width,
+            depth: grid.depth,
+            levels: grid.levels().to_vec(),
+            baseline: grid.baselines().to_vec(),
+        }
+    }
+}
+
+impl NutrientGridSnapshot {
+    /// The grid this snapshot describes, or an error if its patches do not fill it.
+    pub fn to_grid(&self) -> Result<NutrientGrid, SnapshotError> {
+        NutrientGrid::from_patches(
+            Vec2::from_array(self.origin),
+            self.patch_size,
+            self.width,
+            self.depth,
+            self.levels.clone(),
+            self.baseline.clone(),
+        )
+        .ok_or_else(|| {
+            SnapshotError::MalformedJson(serde::de::Error::custom(
+                "nutrient grid must have `width * depth` levels and baselines",
+            ))
+        })
+    }
+}
+
+/// A [`Compartment`] and, for organelles, the contents of its pools.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CompartmentSnapshot {
+    pub kind: CompartmentKind,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pools: Option<BTreeMap<Currency, f32>>,
+}
+
+/// A [`Transporter`] with its compartment given as a snapshot id.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct TransporterSnapshot {
+    pub compartment: u32,
+    pub currency: Currency,
+    pub direction: TransportDirection,
+    pub rate: f32,
+    pub atp_cost: f32,
+}
+
+/// A [`FlowEdge`] with its endpoints given as snapshot ids.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FlowEdgeSnapshot {
+    pub from: u32,
+    pub to: u32,
+    pub currency: Currency,
+    /// Snapshot id of the cell the wire is a member of, unless it is the player's
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub member_of: Option<u32>,
+}
+
+/// Errors that can occur while saving or loading a snapshot
+#[derive(Debug)]
+pub enum SnapshotError {
+    Io(std::io::Error),
+    MalformedJson(serde_json::Error),
+    Genome(GenomeLoadError),
+    UnsupportedVersion(u32),
+}
+
+impl std::fmt::Display for SnapshotError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SnapshotError::Io(err) => write!(f, "snapshot I/O error: {}", err),
+            SnapshotError::MalformedJson(err) => write!(f, "malformed snapshot JSON: {}", err),
+            SnapshotError::Genome(err) => write!(f, "invalid genome in snapshot: {}", err),
+            SnapshotError::UnsupportedVersion(version) => write!(
+                f,
+                "unsupported snapshot version {} (latest supported is {})",
+                version, SNAPSHOT_VERSION
+            ),
+        }
+    }
+}
+
+impl std::error::Error for SnapshotError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            SnapshotError::Io(err) => Some(err),
+            SnapshotError::MalformedJson(err) => Some(err),
+            SnapshotError::Genome(err) => Some(err),
+            SnapshotError::UnsupportedVersion(_) => None,
+        }
+    }
+}
+
+impl From<std::io::Error> for SnapshotError {
+    fn from(err: std::io::Error) -> Self {
+        SnapshotError::Io(err)
+    }
+}
+
+impl From<serde_json::Error> for SnapshotError {
+    fn from(err: serde_json::Error) -> Self {
+        SnapshotError::MalformedJson(err)
+    }
+}
+
+impl From<GenomeLoadError> for SnapshotError {
+    fn from(err: GenomeLoadError) -> Self {
+        SnapshotError::Genome(err)
+    }
+}
+
+// --- Components, Resources & Events ---
+
+/// Snapshot id an entity was restored with; keeps re-saves stable despite entity recycling.
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct SnapshotId(pub u32);
+
+/// Snapshot id -> live entity mapping produced by the most recent load.
+#[derive(Resource, Default, Debug, Clone)]
+pub struct SnapshotEntityMap(pub HashMap<u32, Entity>);
+
+/// The last non-menu scene, so saving from the main menu still records where the player was.
+#[derive(Resource, Default, Debug, Clone)]
+pub struct LastScene(pub Option<GameState>);
+
+/// Request a snapshot of the running simulation to be written to `path`.
+#[derive(Event, Debug, Clone)]
+pub struct SaveSimulationRequest(pub PathBuf);
+
+/// Request the simulation to be replaced by the snapshot stored at `path`.
+#[derive(Event, Debug, Clone)]
+pub struct LoadSimulationRequest(pub PathBuf);
+
+// --- Capture & restore ---
+
+impl SimulationSnapshot {
+    /// Capture the current simulation state from `world`.
+    pub fn capture(world: &mut World) -> Self {
+        let player = player_cell(world);
+        let currencies = player
+            .and_then(|cell| world.get::<CurrencyPools>(cell))
+            .map(|pools| pools.pools.iter().map(|(&c, &v)| (c, v)).collect())
+            .unwrap_or_default();
+
+        let genome = player
+            .and_then(|cell| world.get::<Genome>(cell))
+            .map(GenomeSaveData::from)
+            .unwrap_or_else(|| GenomeSaveData::from(&Genome::default()));
+        let genome = serde_json::to_value(genome).expect("genome save data is always valid JSON");
+
+        let scene = current_scene(world);
+        let body = player.map(|cell| world.entity(cell));
+        let player_mass = body.and_then(|body| body.get::<CellMass>().cloned());
+        let player_polymer = body.and_then(|body| body.get::<PolyMer>().cloned());
+        let player_health = body.and_then(|body| body.get::<CellHealth>().copied());
+        let player_position = body
+            .and_then(|body| body.get::<Transform>())
+            .map(|transform| transform.translation.to_array());
+
+        let mut query = world.query_filtered::<(Entity, Option<&SnapshotId>), (Or<(
+            With<MetabolicNode>,
+            With<MetabolicBlock>,
+            With<FermentationBlock>,
+            With<VesicleExportBlock>,
+            With<CellMass>,
+            With<PolyMer>,
+            With<Compartment>,
+            With<Transporter>,
+        )>, Without<PlayerCell>)>();
+        let mut entities: Vec<(Option<SnapshotId>, Entity)> = query
+            .iter(world)
+            .map(|(entity, id)| (id.copied(), entity))
+            .collect();
+        // Restored entities first (by their previous id), then new ones by Entity
+        entities.sort_by_key(|&(id, entity)| (id.is_none(), id, entity));
+
+        let ids: HashMap<Entity, u32> = entities
+            .iter()
+            .enumerate()
+            .map(|(id, &(_, entity))| (entity, id as u32))
+            .collect();
+
+        // Only wires whose both ends are part of the snapshot can be restored
+        let mut query =
+            world.query_filtered::<(&FlowEdge, Option<&CellMember>), With<MetabolicEdge>>();
+        let mut flow_edges: Vec<FlowEdgeSnapshot> = query
+            .iter(world)
+            .filter_map(|(edge, member)| {
+                Some(FlowEdgeSnapshot {
+                    from: *ids.get(&edge.from)?,
+                    to: *ids.get(&edge.to)?,
+                    currency: edge.currency,
+                    member_of: member.and_then(|member| ids.get(&member.0).copied()),
+                })
+            })
+            .collect();
+        flow_edges.sort_by_key(|edge| (edge.from, edge.to, edge.currency));
+
+        let entities = entities
+            .into_iter()
+            .enumerate()
+            .map(|(id, (_, entity))| {
+                let entity_ref = world.entity(entity);
+                EntitySnapshot {
+                    id: id as u32,
+                    name: entity_ref.get::<Name>().map(|n| n.as_str().to_string()),
+                    metabolic_block: entity_ref.contains::<MetabolicBlock>(),
+                    node: entity_ref.get::<MetabolicNode>().cloned(),
+                    flux_profile: entity_ref
+                        .get::<FluxProfile>()
+                        .map(|p| p.0.iter().map(|(&c, &v)| (c, v)).collect()),
+                    fermentation_block: entity_ref.contains::<FermentationBlock>(),
+                    vesicle_export_block: entity_ref.contains::<VesicleExportBlock>(),
+                    cell_mass: entity_ref.get::<CellMass>().cloned(),
+                    polymer: entity_ref.get::<PolyMer>().cloned(),
+                    health: entity_ref.get::<CellHealth>().copied(),
+                    compartment: entity_ref.get::<Compartment>().map(|compartment| CompartmentSnapshot {
+                        kind: compartment.kind,
+                        pools: entity_ref
+                            .get::<CompartmentPools>()
+                            .map(|pools| pools.0.pools.iter().map(|(&c, &v)| (c, v)).collect()),
+                    }),
+                    in_compartment: entity_ref
+                        .get::<InCompartment>()
+                        .and_then(|compartment| ids.get(&compartment.0).copied()),
+                    transporter: entity_ref.get::<Transporter>().and_then(|transporter| {
+                        Some(TransporterSnapshot {
+                            compartment: *ids.get(&transporter.compartment)?,
+                            currency: transporter.currency,
+                            direction: transporter.direction,
+                            rate: transporter.rate,
+                            atp_cost: transporter.atp_cost,
+                        })
+                    }),
+                    member_of: entity_ref
+                        .get::<CellMember>()
+                        .and_then(|member| ids.get(&member.0).copied()),
+                    cell: capture_cell(entity_ref),
+                }
+            })
+            .collect();
+
+        Self {
+            version: SNAPSHOT_VERSION,
+            scene,
+            currencies,
+            genome,
+            fermentation_rate: world.get_resource::<FermentationRate>().map(|r| r.0),
+            vesicle_export_rate: world.get_resource::<VesicleExportRate>().map(|r| r.0),
+            lipid_toxicity_threshold: world.get_resource::<LipidToxicityThreshold>().map(|r| r.0),
+            vitality: world.get_resource::<CellVitality>().copied().unwrap_or_default(),
+            starvation_ticks: world.get_resource::<StarvationTicks>().map_or(0, |s| s.0),
+            player_mass,
+            player_polymer,
+            player_health,
+            player_position,
+            nutrients: world.get_resource::<NutrientGrid>().map(NutrientGridSnapshot::from),
+            entities,
+            flow_edges,
+        }
+    }
+
+    /// Replace the simulation state in `world` with this snapshot.
+    ///
+    /// Returns the snapshot id -> entity mapping, which is also stored in [`SnapshotEntityMap`].
+    pub fn apply(&self, world: &mut World) -> Result<HashMap<u32, Entity>, SnapshotError> {
+        if self.version > SNAPSHOT_VERSION {
+            return Err(SnapshotError::UnsupportedVersion(self.version));
+        }
+        // Validate the genome before touching the world so a bad file leaves it intact
+        let genome: Genome = GenomeSaveData::from_value(self.genome.clone())?.into();
+        let mut cell_genomes = HashMap::new();
+        for snapshot in &self.entities {
+            if let Some(cell) = &snapshot.cell {
+                let genome: Genome = GenomeSaveData::from_value(cell.genome.clone())?.into();
+                cell_genomes.insert(snapshot.id, genome);
+            }
+        }
+        let nutrients = self.nutrients.as_ref().map(NutrientGridSnapshot::to_grid).transpose()?;
+
+        let mut pools = CurrencyPools::default();
+        for (&currency, &amount) in &self.currencies {
+            pools.set(currency, amount);
+        }
+        // Moved onto the player's cell, which is spawned if the world has none yet
+        world.insert_resource(pools);
+        world.insert_resource(genome);
+        ensure_player_cell(world);
+        let player = player_cell(world).expect("the player's cell was just ensured");
+        let mut body = world.entity_mut(player);
+        if let Some(cell_mass) = &self.player_mass {
+            body.insert(cell_mass.clone());
+        }
+        if let Some(polymer) = &self.player_polymer {
+            body.insert(polymer.clone());
+        }
+        if let Some(health) = self.player_health {
+            body.insert(health);
+        }
+        if let Some(position) = self.player_position {
+            body.insert(Transform::from_translation(Vec3::from_array(position)));
+        }
+        if let Some(grid) = nutrients {
+            world.insert_resource(grid);
+        }
+
+        if let Some(rate) = self.fermentation_rate {
+            world.insert_resource(FermentationRate(rate));
+        }
+        if let Some(rate) = self.vesicle_export_rate {
+            world.insert_resource(VesicleExportRate(rate));
+        }
+        if let Some(threshold) = self.lipid_toxicity_threshold {
+            world.insert_resource(LipidToxicityThreshold(threshold));
+        }
+        world.insert_resource(self.vitality);
+        world.insert_resource(StarvationTicks(self.starvation_ticks));
+
+        // Remove the current simulation entities before respawning from the snapshot
+        let mut query = world.query_filtered::<Entity, (Or<(
+            With<MetabolicNode>,
+            With<MetabolicBlock>,
+            With<FermentationBlock>,
+            With<VesicleExportBlock>,
+            With<CellMass>,
+            With<PolyMer>,
+            With<Compartment>,
+            With<Transporter>,
+        )>, Without<PlayerCell>)>();
+        let mut existing: Vec<Entity> = query.iter(world).collect();
+        let mut wires = world.query_filtered::<Entity, With<FlowEdge>>();
+        existing.extend(wires.iter(world));
+        for entity in existing {
+            world.entity_mut(entity).despawn_recursive();
+        }
+
+        let mut ordered: Vec<&EntitySnapshot> = self.entities.iter().collect();
+        ordered.sort_by_key(|e| e.id);
+
+        let mut mapping = HashMap::with_capacity(ordered.len());
+        for snapshot in ordered {
+            let mut entity = world.spawn(SnapshotId(snapshot.id));
+            if let Some(name) = &snapshot.name {
+                entity.insert(Name::new(name.clone()));
+            }
+            if snapshot.metabolic_block {
+                entity.insert(MetabolicBlock);
+            }
+            if let Some(node) = &snapshot.node {
+                entity.insert(node.clone());
+            }
+            if let Some(profile) = &snapshot.flux_profile {
+                entity.insert(FluxProfile(profile.iter().map(|(&c, &v)| (c, v)).collect()));
+            }
+            if snapshot.fermentation_block {
+                entity.insert(FermentationBlock);
+            }
+            if snapshot.vesicle_export_block {
+                entity.insert(VesicleExportBlock);
+            }
+            if let Some(cell_mass) = &snapshot.cell_mass {
+                entity.insert(cell_mass.clone());
+            }
+            if let Some(polymer) = &snapshot.polymer {
+                entity.insert(polymer.clone());
+            }
+            if let Some(health) = snapshot.health {
+                entity.insert(health);
+            }
+            if let Some(compartment) = &snapshot.compartment {
+                entity.insert(Compartment {
+                    kind: compartment.kind,
+                });
+                if let Some(amounts) = &compartment.pools {
+                    let mut pools = CurrencyPools::default();
+                    for (&currency, &amount) in amounts {
+                        pools.set(currency, amount);
+                    }
+                    entity.insert(CompartmentPools(pools));
+                }
+            }
+            mapping.insert(snapshot.id, entity.id());
+        }
+
+        // Compartment references need every entity spawned first
+        for snapshot in &self.entities {
+            let entity = mapping[&snapshot.id];
+            if let Some(&compartment) = snapshot.in_compartment.and_then(|id| mapping.get(&id)) {
+                world.entity_mut(entity).insert(InCompartment(compartment));
+            }
+            if let Some(transporter) = snapshot.transporter {
+                if let Some(&compartment) = mapping.get(&transporter.compartment) {
+                    world.entity_mut(entity).insert(Transporter {
+                        compartment,
+                        currency: transporter.currency,
+                        direction: transporter.direction,
+                        rate: transporter.rate,
+                        atp_cost: transporter.atp_cost,
+                    });
+                }
+            }
+            if let Some(&cell) = snapshot.member_of.and_then(|id| mapping.get(&id)) {
+                world.entity_mut(entity).insert(CellMember(cell));
+            }
+        }
+        // Snapshots from before compartments existed still get a full set
+        ensure_compartments(world);
+
+        for snapshot in &self.entities {
+            let Some(cell) = &snapshot.cell else {
+                continue;
+            };
+            let genome = cell_genomes.remove(&snapshot.id).unwrap_or_default();
+            let compartments = self
+                .entities
+                .iter()
+                .filter(|member| member.member_of == Some(snapshot.id))
+                .filter_map(|member| Some((member.compartment.as_ref()?.kind, mapping[&member.id])))
+                .collect();
+            let mut pools = CurrencyPools::default();
+            for (&currency, &amount) in &cell.currencies {
+                pools.set(currency, amount);
+            }
+            let entity = mapping[&snapshot.id];
+            let mut entity_mut = world.entity_mut(entity);
+            entity_mut.insert((
+                Cell,
+                genome,
+                pools,
+                CellMetabolism {
+                    flow_dirty: FlowDirty(true),
+                    vitality: cell.vitality,
+                    starvation: StarvationTicks(cell.starvation_ticks),
+                    compartments: CellCompartments(compartments),
+                    ..Default::default()
+                },
+            ));
+            if let Some(position) = cell.position {
+                entity_mut.insert(Transform::from_translation(Vec3::from_array(position)));
+            }
+            if let Some(temperament) = cell.temperament {
+                init_npc(world, entity, temperament);
+            }
+        }
+
+        for edge in &self.flow_edges {
+            if let (Some(&from), Some(&to)) = (mapping.get(&edge.from), mapping.get(&edge.to)) {
+                let mut wire = world.spawn((
+                    MetabolicEdge,
+                    FlowEdge {
+                        from,
+                        to,
+                        currency: edge.currency,
+                    },
+                    Name::new(format!("Flow Edge: {:?}", edge.currency)),
+                ));
+                if let Some(&cell) = edge.member_of.and_then(|id| mapping.get(&id)) {
+                    wire.insert(CellMember(cell));
+                }
+            }
+        }
+
+        // Dependencies reference the old entities; force a rebuild on the next metabolic tick
+        if let Some(mut dirty) = world.get_resource_mut::<FlowDirty>() {
+            dirty.0 = true;
+        }
+        // So would a background flux solve started before the load
+        if let Some(mut pending) = world.get_resource_mut::<PendingFluxSolve>() {
+            pending.0 = None;
+        }
+
+        if let Some(mut last_scene) = world.get_resource_mut::<LastScene>() {
+            last_scene.0 = Some(self.scene.clone());
+        }
+        if self.scene != GameState::MainMenu {
+            if let Some(mut next_state) = world.get_resource_mut::<NextState<GameState>>() {
+                next_state.set(self.scene.clone());
+            }
+        }
+
+        world.insert_resource(SnapshotEntityMap(mapping.clone()));
+        Ok(mapping)
+    }
+
+    /// Serialize the snapshot to a JSON string
+    pub fn to_json(&self) -> Result<String, serde_json::Error> {
+        serde_json::to_string_pretty(self)
+    }
+
+    /// Deserialize a snapshot from a JSON string
+    pub fn from_json(json: &str) -> Result<Self, SnapshotError> {
+        let value: serde_json::Value = serde_json::from_str(json)?;
+        if let Some(version) = value.get("version").and_then(|v| v.as_u64()) {
+            if version > SNAPSHOT_VERSION as u64 {
+                return Err(SnapshotError::UnsupportedVersion(
+                    u32::try_from(version).unwrap_or(u32::MAX),
+                ));
+            }
+        }
+        Ok(serde_json::from_value(value)?)
+    }
+
+    /// Write the snapshot to `path`, creating parent directories as needed
+    pub fn save_to_file(&self, path: impl AsRef<Path>) -> Result<(), SnapshotError> {
+        let path = path.as_ref();
+        if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(path, self.to_json()?)?;
+        Ok(())
+    }
+
+    /// Read a snapshot from `path`
+    pub fn load_from_file(path: impl AsRef<Path>) -> Result<Self, SnapshotError> {
+        Self::from_json(&std::fs::read_to_string(path)?)
+    }
+}
+
+/// Capture the simulation in `world` and write it to `path`.
+pub fn save_simulation(world: &mut World, path: impl AsRef<Path>) -> Result<(), SnapshotError> {
+    SimulationSnapshot::capture(world).save_to_file(path)
+}
+
+/// Load the snapshot at `path` into `world`, returning the snapshot id -> entity mapping.
+pub fn load_simulation(
+    world: &mut World,
+    path: impl AsRef<Path>,
+) -> Result<HashMap<u32, Entity>, SnapshotError> {
+    SimulationSnapshot::load_from_file(path)?.apply(world)
+}
+
+/// The state of `entity` if it is a cell other than the player's.
+fn capture_cell(entity: EntityRef) -> Option<CellSnapshot> {
+    if !entity.contains::<Cell>() || entity.contains::<PlayerCell>() {
+        return None;
+    }
+    let genome = GenomeSaveData::from(entity.get::<Genome>()?);
+    let metabolism = entity.get::<CellMetabolism>();
+    Some(CellSnapshot {
+        currencies: entity
+            .get::<CurrencyPools>()?
+            .pools
+            .iter()
+            .map(|(&c, &v)| (c, v))
+            .collect(),
+        genome: serde_json::to_value(genome).expect("genome save data is always valid JSON"),
+        vitality: metabolism.map(|m| m.vitality).unwrap_or_default(),
+        starvation_ticks: metabolism.map_or(0, |m| m.starvation.0),
+        temperament: entity.get::<Temperament>().copied(),
+        position: entity.get::<Transform>().map(|t| t.translation.to_array()),
+    })
+}
+
+fn current_scene(world: &World) -> GameState {
+    let current = world.get_resource::<State<GameState>>().map(|s| s.get().clone());
+    match current {
+        Some(GameState::MainMenu) | None => world
+            .get_resource::<LastScene>()
+            .and_then(|s| s.0.clone())
+            .unwrap_or_default(),
+        Some(state) => state,
+    }
+}
+
+// --- Systems ---
+
+/// Remember the last scene the player was in, so menu saves capture it.
+fn track_last_scene(state: Res<State<GameState>>, mut last_scene: ResMut<LastScene>) {
+    if state.is_changed() && *state.get() != GameState::MainMenu {
+        last_scene.0 = Some(state.get().clone());
+    }
+}
+
+/// Process pending save/load requests with exclusive world access.
+pub fn handle_snapshot_requests(world: &mut World) {
+    let saves: Vec<SaveSimulationRequest> = world
+        .resource_mut::<Events<SaveSimulationRequest>>()
+        .drain()
+        .collect();
+    for SaveSimulationRequest(path) in saves {
+        match save_simulation(world, &path) {
+            Ok(()) => info!("Saved simulation snapshot to {}", path.display()),
+            Err(err) => error!("Failed to save snapshot to {}: {}", path.display(), err),
+        }
+    }
+
+    let loads: Vec<LoadSimulationRequest> = world
+        .resource_mut::<Events<LoadSimulationRequest>>()
+        .drain()
+        .collect();
+    for LoadSimulationRequest(path) in loads {
+        match load_simulation(world, &path) {
+            Ok(mapping) => info!(
+                "Loaded simulation snapshot from {} ({} entities)",
+                path.display(),
+                mapping.len()
+            ),
+            Err(err) => error!("Failed to load snapshot from {}: {}", path.display(), err),
+        }
+    }
+}
+
+// --- Plugin ---
+
+pub struct SnapshotPlugin;
+
+impl Plugin for SnapshotPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<SnapshotEntityMap>()
+            .init_resource::<LastScene>()
+            .add_event::<SaveSimulationRequest>()
+            .add_event::<LoadSimulationRequest>()
+            .add_systems(
+                Update,
+                (
+                    track_last_scene.run_if(resource_exists::<State<GameState>>),
+                    handle_snapshot_requests,
+                ),
+            );
+    }
+}
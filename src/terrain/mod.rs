@@ -1,15 +1,328 @@
+//! # Terrain
+//!
+//! Procedural ground for the 3D scene. A [`Terrain`] turns its [`TerrainSettings`] into a
+//! heightmap of seeded fractal noise and tags every point of it with a [`Biome`]: low
+//! ground settles into anoxic mud, high ground rises into sunny shallows, and a second
+//! noise layer scatters nutrient-rich vents across both.
+//!
+//! The 3D scene spawns the terrain as square [`TerrainChunk`]s, each with its own mesh and
+//! trimesh collider. Biomes also set the baseline of every patch of the
+//! [`NutrientGrid`], so what the ground looks like is what a cell finds to eat there.
+
+pub mod noise;
+
+use avian3d::prelude::*;
 use bevy::prelude::*;
+use bevy::render::mesh::{Indices, PrimitiveTopology};
+use bevy::render::render_asset::RenderAssetUsages;
+use serde::{Deserialize, Serialize};
+
+use crate::environment::{EnvironmentRules, Nutrient, NutrientGrid, NutrientLevels};
+use noise::ValueNoise;
+
+/// What kind of place a point of the terrain is.
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Biome {
+    Seabed,
+    /// Rich in sugar and ammonium, dim
+    NutrientVent,
+    /// Bright and well oxygenated
+    SunnyShallows,
+    /// Low ground with little oxygen, where sugar and waste settle
+    AnoxicMud,
+}
+
+impl Biome {
+    /// How much of each nutrient's baseline this biome has, by [`Nutrient::index`].
+    pub fn nutrient_factors(self) -> NutrientLevels {
+        let mut factors = [1.0; Nutrient::COUNT];
+        let mut scale = |nutrient: Nutrient, factor: f32| factors[nutrient.index()] = factor;
+        match self {
+            Biome::Seabed => {}
+            Biome::NutrientVent => {
+                scale(Nutrient::Sugar, 3.0);
+                scale(Nutrient::Ammonium, 3.0);
+                scale(Nutrient::Light, 0.5);
+            }
+            Biome::SunnyShallows => {
+                scale(Nutrient::Oxygen, 1.5);
+                scale(Nutrient::Light, 3.0);
+            }
+            Biome::AnoxicMud => {
+                scale(Nutrient::Sugar, 1.5);
+                scale(Nutrient::Oxygen, 0.1);
+                scale(Nutrient::Light, 0.3);
+            }
+        }
+        factors
+    }
+
+    /// Colour the biome is painted in.
+    pub fn color(self) -> Color {
+        match self {
+            Biome::Seabed => Color::srgb(0.3, 0.5, 0.3),
+            Biome::NutrientVent => Color::srgb(0.7, 0.35, 0.2),
+            Biome::SunnyShallows => Color::srgb(0.75, 0.75, 0.5),
+            Biome::AnoxicMud => Color::srgb(0.3, 0.25, 0.2),
+        }
+    }
+}
+
+/// How the terrain is generated.
+#[derive(Resource, Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TerrainSettings {
+    pub seed: u64,
+    /// Chunks along each side of the terrain, which is centred on the origin
+    pub chunks: u32,
+    /// Side length of a chunk in world units
+    pub chunk_size: f32,
+    /// Quads along each side of a chunk's mesh
+    pub chunk_resolution: u32,
+    /// Height of the highest possible ground
+    pub height_scale: f32,
+    /// Size in world units of the largest hills
+    pub feature_size: f32,
+    /// Noise layers of ever finer detail on top of the largest hills
+    pub octaves: u32,
+    /// Ground below this fraction of `height_scale` is mud
+    pub mud_below: f32,
+    /// Ground above this fraction of `height_scale` is shallows
+    pub shallows_above: f32,
+    /// Size in world units of the patches vents can appear in
+    pub vent_size: f32,
+    /// Vent noise above this value is a vent; higher means rarer vents
+    pub vent_threshold: f32,
+}
+
+impl Default for TerrainSettings {
+    fn default() -> Self {
+        Self {
+            seed: 42,
+            chunks: 10,
+            chunk_size: 50.0,
+            chunk_resolution: 16,
+            height_scale: 6.0,
+            feature_size: 80.0,
+            octaves: 4,
+            mud_below: 0.35,
+            shallows_above: 0.65,
+            vent_size: 25.0,
+            vent_threshold: 0.85,
+        }
+    }
+}
 
-// Generating terrain procedurally
+/// A heightmap with biomes, generated from [`TerrainSettings`].
+#[derive(Resource, Debug, Clone, PartialEq)]
+pub struct Terrain {
+    pub settings: TerrainSettings,
+    relief: ValueNoise,
+    vents: ValueNoise,
+}
+
+impl FromWorld for Terrain {
+    fn from_world(world: &mut World) -> Self {
+        Self::new(world.get_resource::<TerrainSettings>().cloned().unwrap_or_default())
+    }
+}
+
+/// Vertices and triangles of one chunk, relative to its corner.
+#[derive(Debug, Clone, Default)]
+pub struct ChunkGeometry {
+    pub positions: Vec<Vec3>,
+    pub normals: Vec<Vec3>,
+    pub biomes: Vec<Biome>,
+    pub triangles: Vec<[u32; 3]>,
+}
+
+impl Terrain {
+    pub fn new(settings: TerrainSettings) -> Self {
+        Self {
+            relief: ValueNoise::new(settings.seed),
+            vents: ValueNoise::new(settings.seed ^ 0x5645_4e54),
+            settings,
+        }
+    }
+
+    /// Side length of the whole terrain.
+    pub fn size(&self) -> f32 {
+        self.settings.chunks as f32 * self.settings.chunk_size
+    }
+
+    /// Corner with the lowest X and Z.
+    pub fn origin(&self) -> Vec2 {
+        Vec2::splat(-self.size() / 2.0)
+    }
+
+    /// Ground height at `(x, z)` as a fraction of `height_scale`.
+    fn relief(&self, x: f32, z: f32) -> f32 {
+        let scale = self.settings.feature_size.max(f32::EPSILON);
+        self.relief.fractal(x / scale, z / scale, self.settings.octaves)
+    }
 
+    /// Ground height at `(x, z)`.
+    pub fn height_at(&self, x: f32, z: f32) -> f32 {
+        self.relief(x, z) * self.settings.height_scale
+    }
+
+    pub fn biome_at(&self, x: f32, z: f32) -> Biome {
+        let vent_scale = self.settings.vent_size.max(f32::EPSILON);
+        if self.vents.sample(x / vent_scale, z / vent_scale) > self.settings.vent_threshold {
+            return Biome::NutrientVent;
+        }
+        let relief = self.relief(x, z);
+        if relief < self.settings.mud_below {
+            Biome::AnoxicMud
+        } else if relief > self.settings.shallows_above {
+            Biome::SunnyShallows
+        } else {
+            Biome::Seabed
+        }
+    }
+
+    /// Coordinates of every chunk.
+    pub fn chunk_coords(&self) -> impl Iterator<Item = UVec2> {
+        let chunks = self.settings.chunks;
+        (0..chunks).flat_map(move |z| (0..chunks).map(move |x| UVec2::new(x, z)))
+    }
+
+    /// World position of a chunk's corner with the lowest X and Z, at height zero.
+    pub fn chunk_origin(&self, coord: UVec2) -> Vec3 {
+        let corner = self.origin() + coord.as_vec2() * self.settings.chunk_size;
+        Vec3::new(corner.x, 0.0, corner.y)
+    }
+
+    /// Sample the heightmap over a chunk. Neighbouring chunks share their edge vertices, so
+    /// the ground has no seams.
+    pub fn chunk_geometry(&self, coord: UVec2) -> ChunkGeometry {
+        let resolution = self.settings.chunk_resolution.max(1);
+        let step = self.settings.chunk_size / resolution as f32;
+        let corner = self.chunk_origin(coord);
+        let mut geometry = ChunkGeometry::default();
+
+        for z in 0..=resolution {
+            for x in 0..=resolution {
+                let local = Vec2::new(x as f32, z as f32) * step;
+                let (wx, wz) = (corner.x + local.x, corner.z + local.y);
+                geometry.positions.push(Vec3::new(local.x, self.height_at(wx, wz), local.y));
+                // Central differences, so normals match across chunk edges
+                let dx = self.height_at(wx + step, wz) - self.height_at(wx - step, wz);
+                let dz = self.height_at(wx, wz + step) - self.height_at(wx, wz - step);
+                geometry.normals.push(Vec3::new(-dx, 2.0 * step, -dz).normalize());
+                geometry.biomes.push(self.biome_at(wx, wz));
+            }
+        }
+
+        let row = resolution + 1;
+        for z in 0..resolution {
+            for x in 0..resolution {
+                let i = z * row + x;
+                geometry.triangles.push([i, i + row, i + 1]);
+                geometry.triangles.push([i + 1, i + row, i + row + 1]);
+            }
+        }
+        geometry
+    }
+}
+
+impl ChunkGeometry {
+    /// A mesh of the chunk, with each vertex painted in the colour of its biome.
+    pub fn mesh(&self) -> Mesh {
+        let colors: Vec<[f32; 4]> = self
+            .biomes
+            .iter()
+            .map(|biome| biome.color().to_linear().to_f32_array())
+            .collect();
+        Mesh::new(PrimitiveTopology::TriangleList, RenderAssetUsages::default())
+            .with_inserted_attribute(Mesh::ATTRIBUTE_POSITION, self.positions.clone())
+            .with_inserted_attribute(Mesh::ATTRIBUTE_NORMAL, self.normals.clone())
+            .with_inserted_attribute(Mesh::ATTRIBUTE_COLOR, colors)
+            .with_inserted_indices(Indices::U32(self.triangles.iter().flatten().copied().collect()))
+    }
+
+    pub fn collider(&self) -> Collider {
+        Collider::trimesh(self.positions.clone(), self.triangles.clone())
+    }
+}
+
+/// One square piece of the ground.
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TerrainChunk {
+    pub coord: UVec2,
+}
+
+/// Spawn every chunk of `terrain` with its mesh and collider.
+pub fn spawn_terrain(
+    commands: &mut Commands,
+    meshes: &mut Assets<Mesh>,
+    materials: &mut Assets<StandardMaterial>,
+    terrain: &Terrain,
+) -> Vec<Entity> {
+    // Vertex colours carry the biomes, so every chunk can share a white material
+    let material = materials.add(StandardMaterial {
+        perceptual_roughness: 0.9,
+        ..default()
+    });
+    terrain
+        .chunk_coords()
+        .map(|coord| {
+            let geometry = terrain.chunk_geometry(coord);
+            commands
+                .spawn((
+                    TerrainChunk { coord },
+                    Name::new(format!("Terrain chunk {}, {}", coord.x, coord.y)),
+                    Mesh3d(meshes.add(geometry.mesh())),
+                    MeshMaterial3d(material.clone()),
+                    Transform::from_translation(terrain.chunk_origin(coord)),
+                    RigidBody::Static,
+                    geometry.collider(),
+                    Friction {
+                        dynamic_coefficient: 1.0,
+                        static_coefficient: 1.0,
+                        combine_rule: CoefficientCombine::Multiply,
+                    },
+                ))
+                .id()
+        })
+        .collect()
+}
+
+// --- Systems ---
+
+/// Give every patch of the nutrient grid the baseline of the biome at its centre, and
+/// start it there.
+pub fn apply_biomes(
+    terrain: Res<Terrain>,
+    rules: Res<EnvironmentRules>,
+    mut grid: ResMut<NutrientGrid>,
+) {
+    let baseline = rules.baseline_levels();
+    for patch in 0..grid.patch_count() {
+        let center = grid.patch_center(patch);
+        let factors = terrain.biome_at(center.x, center.z).nutrient_factors();
+        for nutrient in Nutrient::ALL {
+            let amount = baseline[nutrient.index()] * factors[nutrient.index()];
+            grid.set_baseline(patch, nutrient, amount);
+            grid.set(patch, nutrient, amount);
+        }
+    }
+}
+
+// --- Plugin ---
+
+/// Generates the [`Terrain`] and lets its biomes shape the environment. Add it after the
+/// [`EnvironmentPlugin`](crate::environment::EnvironmentPlugin).
 pub struct TerrainPlugin;
 
 impl Plugin for TerrainPlugin {
     fn build(&self, app: &mut App) {
-        app.add_systems(Startup, generate_terrain);
+        app.init_resource::<TerrainSettings>()
+            .init_resource::<Terrain>()
+            .add_systems(
+                Startup,
+                apply_biomes.run_if(resource_exists::<NutrientGrid>.and(resource_exists::<EnvironmentRules>)),
+            );
     }
 }
-
-fn generate_terrain(mut commands: Commands) {
-    commands.spawn(TerrainBundle::default());
-}
\ No newline at end of file
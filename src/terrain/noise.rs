@@ -0,0 +1,62 @@
+//! Seeded value noise, so the same seed always grows the same terrain.
+
+/// Smoothly interpolated random values on an integer lattice, in `0.0..=1.0`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ValueNoise {
+    pub seed: u64,
+}
+
+impl ValueNoise {
+    pub fn new(seed: u64) -> Self {
+        Self { seed }
+    }
+
+    /// Noise at `(x, z)`; one lattice cell per unit.
+    pub fn sample(&self, x: f32, z: f32) -> f32 {
+        let (x0, z0) = (x.floor(), z.floor());
+        let (tx, tz) = (smoothstep(x - x0), smoothstep(z - z0));
+        let (ix, iz) = (x0 as i64, z0 as i64);
+
+        let a = self.lattice(ix, iz);
+        let b = self.lattice(ix + 1, iz);
+        let c = self.lattice(ix, iz + 1);
+        let d = self.lattice(ix + 1, iz + 1);
+        let near = a + (b - a) * tx;
+        let far = c + (d - c) * tx;
+        near + (far - near) * tz
+    }
+
+    /// `octaves` layers of noise, each at double the frequency and half the weight of the
+    /// last, normalised back to `0.0..=1.0`.
+    pub fn fractal(&self, x: f32, z: f32, octaves: u32) -> f32 {
+        let mut total = 0.0;
+        let mut weight = 1.0;
+        let mut weights = 0.0;
+        let mut frequency = 1.0;
+        for octave in 0..octaves.max(1) {
+            // Offset every octave, so their lattices do not line up at the origin
+            let shift = octave as f32 * 17.31;
+            total += self.sample(x * frequency + shift, z * frequency - shift) * weight;
+            weights += weight;
+            weight *= 0.5;
+            frequency *= 2.0;
+        }
+        total / weights
+    }
+
+    /// Random value of a lattice point.
+    fn lattice(&self, x: i64, z: i64) -> f32 {
+        // SplitMix64 over the seed and both coordinates
+        let mut hash = self.seed
+            ^ (x as u64).wrapping_mul(0x9e37_79b9_7f4a_7c15)
+            ^ (z as u64).wrapping_mul(0xc2b2_ae3d_27d4_eb4f);
+        hash = (hash ^ (hash >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        hash = (hash ^ (hash >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        hash ^= hash >> 31;
+        (hash >> 40) as f32 / (1u64 << 24) as f32
+    }
+}
+
+fn smoothstep(t: f32) -> f32 {
+    t * t * (3.0 - 2.0 * t)
+}
@@ -0,0 +1,97 @@
+//! # Cell Tests
+//!
+//! Cells besides the player's keep their own genome, pools and metabolic graph, and are
+//! simulated every tick alongside the player's without touching it.
+
+use bevy::prelude::*;
+use metabolistic3d::blocks::fermentation::FermentationBlock;
+use metabolistic3d::blocks::genome::{BlockKind, Genome};
+use metabolistic3d::metabolism::cells::{player_pools, spawn_cell, Cell, CellMember, CellMetabolism, PlayerCell};
+use metabolistic3d::metabolism::toxicity::CellHealth;
+use metabolistic3d::metabolism::{run_ticks, BlockStatus, CurrencyPools, MetabolicNode};
+use metabolistic3d::molecules::Currency;
+use metabolistic3d::MetabolisticApp;
+
+fn fermenting_genome() -> Genome {
+    let mut genome = Genome::default();
+    genome.add_gene(BlockKind::Fermentation);
+    genome.express_gene(BlockKind::Fermentation);
+    genome
+}
+
+fn pools(app: &App, cell: Entity) -> &CurrencyPools {
+    app.world().get::<CurrencyPools>(cell).unwrap()
+}
+
+fn fermentation_of(app: &mut App, cell: Entity) -> Entity {
+    app.world_mut()
+        .query_filtered::<(Entity, &CellMember), With<FermentationBlock>>()
+        .iter(app.world())
+        .find(|(_, member)| member.0 == cell)
+        .map(|(entity, _)| entity)
+        .unwrap()
+}
+
+#[test]
+fn test_player_cell_is_one_of_many() {
+    let mut app = MetabolisticApp::new_headless();
+    app.update();
+    let cell = spawn_cell(app.world_mut(), Genome::default(), CurrencyPools::with_defaults());
+
+    let mut cells = app.world_mut().query_filtered::<Entity, With<Cell>>();
+    assert_eq!(cells.iter(app.world()).count(), 2);
+    let mut player = app.world_mut().query_filtered::<Entity, (With<Cell>, With<PlayerCell>)>();
+    let player = player.single(app.world());
+    // Every cell carries its own genome and pools, the player's included
+    for cell in [player, cell] {
+        assert!(app.world().get::<Genome>(cell).is_some());
+        assert!(app.world().get::<CurrencyPools>(cell).is_some());
+    }
+    assert!(!app.world().contains_resource::<Genome>());
+    assert!(!app.world().contains_resource::<CurrencyPools>());
+}
+
+#[test]
+fn test_cells_run_their_own_genomes_on_their_own_pools() {
+    let mut app = MetabolisticApp::new_headless();
+    app.update();
+    let fermenting = spawn_cell(app.world_mut(), fermenting_genome(), CurrencyPools::with_defaults());
+    let idle = spawn_cell(app.world_mut(), Genome::default(), CurrencyPools::with_defaults());
+    let player_before = player_pools(app.world()).get(Currency::Pyruvate);
+
+    run_ticks(app.world_mut(), 3);
+
+    // Expression reaches the fermenting cell's block on its first tick
+    let block = fermentation_of(&mut app, fermenting);
+    assert_eq!(app.world().get::<MetabolicNode>(block).unwrap().status, BlockStatus::Active);
+    let block = fermentation_of(&mut app, idle);
+    assert_eq!(app.world().get::<MetabolicNode>(block).unwrap().status, BlockStatus::Silent);
+
+    assert_eq!(pools(&app, fermenting).get(Currency::Pyruvate), 22.0);
+    assert_eq!(pools(&app, fermenting).get(Currency::ATP), 103.0);
+    assert_eq!(pools(&app, idle).get(Currency::Pyruvate), 25.0);
+    assert_eq!(
+        player_pools(app.world()).get(Currency::Pyruvate),
+        player_before
+    );
+
+    // Each cell's graph holds only its own blocks
+    let block = fermentation_of(&mut app, fermenting);
+    let graph = &app.world().get::<CellMetabolism>(fermenting).unwrap().graph;
+    assert_eq!(graph.nodes, vec![block]);
+}
+
+#[test]
+fn test_toxicity_only_harms_the_poisoned_cell() {
+    let mut app = MetabolisticApp::new_headless();
+    app.update();
+    let mut poisoned = CurrencyPools::with_defaults();
+    poisoned.set(Currency::OrganicWaste, 80.0);
+    let cell = spawn_cell(app.world_mut(), Genome::default(), poisoned);
+    let healthy = spawn_cell(app.world_mut(), Genome::default(), CurrencyPools::with_defaults());
+
+    run_ticks(app.world_mut(), 1);
+    let health = |entity| app.world().get::<CellHealth>(entity).unwrap().current;
+    assert!(health(cell) < 100.0);
+    assert_eq!(health(healthy), 100.0);
+}
@@ -0,0 +1,129 @@
+//! # Compartment Tests
+//!
+//! Organelles keep their own pools: blocks inside them are solved against those pools and
+//! transporters carry currencies across the membrane for a fee in cytosolic ATP.
+
+use std::collections::HashMap;
+
+use bevy::prelude::*;
+use metabolistic3d::blocks::genome::BlockKind;
+use metabolistic3d::metabolism::cells::{player_genome_mut, player_pools, player_pools_mut};
+use metabolistic3d::metabolism::compartments::{
+    CellCompartments, CompartmentKind, CompartmentPools, InCompartment, TransportDirection,
+    Transporter,
+};
+use metabolistic3d::metabolism::{run_ticks, BlockStatus, CurrencyPools, FluxProfile, MetabolicNode};
+use metabolistic3d::molecules::Currency;
+use metabolistic3d::snapshot::SimulationSnapshot;
+use metabolistic3d::MetabolisticApp;
+
+/// A mitochondrion that imports pyruvate, respires it into ATP and exports some of it.
+fn respiring_cell() -> (App, Entity) {
+    let mut app = MetabolisticApp::new_headless();
+    app.update();
+    let mitochondrion = app
+        .world()
+        .resource::<CellCompartments>()
+        .get(CompartmentKind::Mitochondrion)
+        .unwrap();
+    player_genome_mut(app.world_mut())
+        .express_gene(BlockKind::AminoAcidBiosynthesis);
+    app.world_mut().spawn((
+        MetabolicNode {
+            kind: BlockKind::AminoAcidBiosynthesis,
+            status: BlockStatus::Active,
+        },
+        FluxProfile(HashMap::from([(Currency::Pyruvate, -1.0), (Currency::ATP, 3.0)])),
+        InCompartment(mitochondrion),
+    ));
+    app.world_mut().spawn(Transporter {
+        compartment: mitochondrion,
+        currency: Currency::Pyruvate,
+        direction: TransportDirection::Import,
+        rate: 2.0,
+        atp_cost: 0.5,
+    });
+    (app, mitochondrion)
+}
+
+fn organelle(app: &App, compartment: Entity) -> &CurrencyPools {
+    &app.world().get::<CompartmentPools>(compartment).unwrap().0
+}
+
+#[test]
+fn test_cell_starts_with_every_compartment() {
+    let mut app = MetabolisticApp::new_headless();
+    app.update();
+    let compartments = app.world().resource::<CellCompartments>().clone();
+    for kind in CompartmentKind::ALL {
+        let entity = compartments.get(kind).unwrap();
+        let has_pools = app.world().get::<CompartmentPools>(entity).is_some();
+        assert_eq!(has_pools, kind != CompartmentKind::Cytosol, "{:?}", kind);
+    }
+}
+
+#[test]
+fn test_organelle_blocks_use_their_own_pools() {
+    let (mut app, mitochondrion) = respiring_cell();
+    let cytosol_before = player_pools(app.world()).clone();
+
+    run_ticks(app.world_mut(), 1);
+    let cytosol = player_pools(app.world());
+    assert_eq!(
+        cytosol.get(Currency::Pyruvate),
+        cytosol_before.get(Currency::Pyruvate) - 2.0
+    );
+    // The fee is paid in the cytosol, the ATP made stays in the mitochondrion
+    assert_eq!(cytosol.get(Currency::ATP), cytosol_before.get(Currency::ATP) - 1.0);
+    assert_eq!(organelle(&app, mitochondrion).get(Currency::ATP), 3.0);
+    assert_eq!(organelle(&app, mitochondrion).get(Currency::Pyruvate), 1.0);
+
+    app.world_mut().spawn(Transporter {
+        compartment: mitochondrion,
+        currency: Currency::ATP,
+        direction: TransportDirection::Export,
+        rate: 2.0,
+        atp_cost: 0.0,
+    });
+    run_ticks(app.world_mut(), 1);
+    // Transport runs before the solve: 1 + 2 - 1 pyruvate, 3 - 2 + 3 ATP
+    assert_eq!(organelle(&app, mitochondrion).get(Currency::Pyruvate), 2.0);
+    assert_eq!(organelle(&app, mitochondrion).get(Currency::ATP), 4.0);
+}
+
+#[test]
+fn test_transport_stops_without_atp_for_the_fee() {
+    let (mut app, mitochondrion) = respiring_cell();
+    player_pools_mut(app.world_mut()).set(Currency::ATP, 0.0);
+    run_ticks(app.world_mut(), 1);
+    assert_eq!(organelle(&app, mitochondrion).get(Currency::Pyruvate), 0.0);
+    assert_eq!(organelle(&app, mitochondrion).get(Currency::ATP), 0.0);
+}
+
+#[test]
+fn test_snapshot_round_trip_keeps_compartments() {
+    let (mut app, _) = respiring_cell();
+    run_ticks(app.world_mut(), 1);
+    let snapshot = SimulationSnapshot::capture(app.world_mut());
+
+    let mut restored = MetabolisticApp::new_headless();
+    restored.update();
+    snapshot.apply(restored.world_mut()).unwrap();
+    let mitochondrion = restored
+        .world()
+        .resource::<CellCompartments>()
+        .get(CompartmentKind::Mitochondrion)
+        .unwrap();
+    assert_eq!(organelle(&restored, mitochondrion).get(Currency::ATP), 3.0);
+    let mut transporters = restored.world_mut().query::<&Transporter>();
+    assert_eq!(transporters.single(restored.world()).compartment, mitochondrion);
+    let mut assigned = restored.world_mut().query::<&InCompartment>();
+    assert_eq!(assigned.single(restored.world()).0, mitochondrion);
+
+    let organelles = restored
+        .world_mut()
+        .query::<&CompartmentPools>()
+        .iter(restored.world())
+        .count();
+    assert_eq!(organelles, 3);
+}
@@ -7,9 +7,10 @@
 
 use proptest::prelude::*;
 use approx::assert_relative_eq;
+use metabolistic3d::metabolism::cells::{player_pools, player_pools_mut};
+use metabolistic3d::metabolism::run_ticks;
 use metabolistic3d::molecules::*;
 use metabolistic3d::blocks::fermentation::FermentationBlock;
-use metabolistic3d::metabolism::CurrencyPools;
 use metabolistic3d::molecules::{PolyMer, CellMass};
 use metabolistic3d::blocks::vesicle_export::VesicleExportBlock;
 use metabolistic3d::MetabolisticApp;
@@ -30,27 +31,27 @@ proptest! {
         consumption_attempts in prop::collection::vec(consumption_amount(), 1..20)
     ) {
         let mut app = MetabolisticApp::new_headless();
-        let mut currency_pools = app.world_mut().resource_mut::<CurrencyPools>();
+        let mut currency_pools = player_pools_mut(app.world_mut());
         currency_pools.set(Currency::ATP, initial_atp);
         currency_pools.set(Currency::ReducingPower, initial_rp);
         
         // Attempt various consumption operations
         for &amount in &consumption_attempts {
-            let atp_before = app.world().resource::<CurrencyPools>().get(Currency::ATP);
-            let rp_before = app.world().resource::<CurrencyPools>().get(Currency::ReducingPower);
+            let atp_before = player_pools(app.world()).get(Currency::ATP);
+            let rp_before = player_pools(app.world()).get(Currency::ReducingPower);
             
             // Try to consume ATP (should fail gracefully when insufficient)
-            if app.world().resource::<CurrencyPools>().can_consume(Currency::ATP, amount) {
-                app.world_mut().resource_mut::<CurrencyPools>().modify(Currency::ATP, -amount);
+            if player_pools(app.world()).can_consume(Currency::ATP, amount) {
+                player_pools_mut(app.world_mut()).modify(Currency::ATP, -amount);
             }
             
             // Try to consume ReducingPower (should fail gracefully when insufficient)
-            if app.world().resource::<CurrencyPools>().can_consume(Currency::ReducingPower, amount) {
-                app.world_mut().resource_mut::<CurrencyPools>().modify(Currency::ReducingPower, -amount);
+            if player_pools(app.world()).can_consume(Currency::ReducingPower, amount) {
+                player_pools_mut(app.world_mut()).modify(Currency::ReducingPower, -amount);
             }
             
-            let atp_after = app.world().resource::<CurrencyPools>().get(Currency::ATP);
-            let rp_after = app.world().resource::<CurrencyPools>().get(Currency::ReducingPower);
+            let atp_after = player_pools(app.world()).get(Currency::ATP);
+            let rp_after = player_pools(app.world()).get(Currency::ReducingPower);
             
             // Verify currencies never go negative
             prop_assert!(atp_after >= 0.0, "ATP went negative: {}", atp_after);
@@ -73,7 +74,7 @@ proptest! {
         let mut app = MetabolisticApp::new_headless();
         
         // Initialize resources
-        let mut currency_pools = app.world_mut().resource_mut::<CurrencyPools>();
+        let mut currency_pools = player_pools_mut(app.world_mut());
         currency_pools.set(Currency::Pyruvate, pyruvate_amount);
         currency_pools.set(Currency::ReducingPower, reducing_power_amount);
         currency_pools.set(Currency::ATP, 0.0);
@@ -109,7 +110,7 @@ proptest! {
         let mut app = MetabolisticApp::new_headless();
         
         // Initialize resources with sufficient amounts for testing
-        let mut currency_pools = app.world_mut().resource_mut::<CurrencyPools>();
+        let mut currency_pools = player_pools_mut(app.world_mut());
         currency_pools.set(Currency::Pyruvate, initial_pyruvate);
         currency_pools.set(Currency::ReducingPower, initial_rp);
         currency_pools.set(Currency::ATP, 0.0);
@@ -127,7 +128,7 @@ proptest! {
             app.world_mut().run_schedule(FixedUpdate);
         }
         
-        let currency_pools = app.world().resource::<CurrencyPools>();
+        let currency_pools = player_pools(app.world());
         let final_pyruvate = currency_pools.get(Currency::Pyruvate);
         let final_rp = currency_pools.get(Currency::ReducingPower);
         let final_atp = currency_pools.get(Currency::ATP);
@@ -158,7 +159,7 @@ proptest! {
         let mut app = MetabolisticApp::new_headless();
         
         // Set up conditions for polymerization (FFA above threshold)
-        let mut currency_pools = app.world_mut().resource_mut::<CurrencyPools>();
+        let mut currency_pools = player_pools_mut(app.world_mut());
         currency_pools.set(Currency::FreeFattyAcids, initial_ffa);
         currency_pools.set(Currency::StorageBeads, initial_storage);
         currency_pools.set(Currency::ATP, 100.0); // Sufficient ATP
@@ -180,8 +181,9 @@ proptest! {
         // Run simulation
         for _ in 0..simulation_steps {
             app.update();
+            run_ticks(app.world_mut(), 1);
             
-            let currency_pools = app.world().resource::<CurrencyPools>();
+            let currency_pools = player_pools(app.world());
             let current_ffa = currency_pools.get(Currency::FreeFattyAcids);
             let current_storage = currency_pools.get(Currency::StorageBeads);
             let current_total = current_ffa + current_storage;
@@ -206,7 +208,7 @@ proptest! {
     ) {
         let mut app = MetabolisticApp::new_headless();
         
-        app.world_mut().resource_mut::<CurrencyPools>().set(Currency::OrganicWaste, initial_waste);
+        player_pools_mut(app.world_mut()).set(Currency::OrganicWaste, initial_waste);
         app.world_mut().spawn(VesicleExportBlock);
         
         let initial_snapshot = get_currency_snapshot(&app);
@@ -226,7 +228,7 @@ proptest! {
             prop_assert!(current_total <= initial_total + 0.1); // Allow small epsilon for floating-point
             
             // Waste specifically should only decrease
-            let current_waste = app.world().resource::<CurrencyPools>().get(Currency::OrganicWaste);
+            let current_waste = player_pools(app.world()).get(Currency::OrganicWaste);
             prop_assert!(current_waste <= initial_waste + 0.1);
             
             // No currency should go negative
@@ -250,7 +252,7 @@ proptest! {
         let mut app = MetabolisticApp::new_headless();
         
         // Initialize all currencies
-        let mut currency_pools = app.world_mut().resource_mut::<CurrencyPools>();
+        let mut currency_pools = player_pools_mut(app.world_mut());
         currency_pools.set(Currency::Pyruvate, initial_pyruvate);
         currency_pools.set(Currency::ReducingPower, initial_rp);
         currency_pools.set(Currency::ATP, 50.0);
@@ -4,12 +4,14 @@
 //! consumption logic works as expected within a minimal Bevy app environment.
 
 use bevy::prelude::*;
-use metabolistic3d::metabolism::CurrencyPools;
+use metabolistic3d::metabolism::cells::{player_pools, player_pools_mut, PlayerCell};
+use metabolistic3d::metabolism::{run_ticks, CurrencyPools};
 use metabolistic3d::molecules::{Currency, CellMass, CurrencyPlugin, LipidToxicityThreshold, PolyMer};
 use metabolistic3d::MetabolisticApp;
 
-/// A system that simulates a block consuming a fixed amount of ATP on every update.
-fn atp_consuming_system(mut currency_pools: ResMut<CurrencyPools>) {
+/// A system that simulates the player's cell consuming a fixed amount of ATP on every update.
+fn atp_consuming_system(mut pools: Query<&mut CurrencyPools, With<PlayerCell>>) {
+    let mut currency_pools = pools.single_mut();
     // This system represents a metabolic block that requires 10 ATP per cycle.
     if currency_pools.can_consume(Currency::ATP, 10.0) {
         currency_pools.modify(Currency::ATP, -10.0);
@@ -25,17 +27,17 @@ fn test_atp_consumption_decreases_resource() {
     app.add_systems(Update, atp_consuming_system);
 
     // Initialize ATP with a starting amount.
-    app.world_mut().resource_mut::<CurrencyPools>().set(Currency::ATP, 100.0);
+    player_pools_mut(app.world_mut()).set(Currency::ATP, 100.0);
 
     // --- Initial State Verification ---
-    let initial_atp = app.world().resource::<CurrencyPools>().get(Currency::ATP);
+    let initial_atp = player_pools(app.world()).get(Currency::ATP);
     assert_eq!(initial_atp, 100.0, "Initial ATP should be 100.0");
 
     // --- Run Simulation (1st update) ---
     app.update();
 
     // --- Verification after 1st update ---
-    let atp_after_1_update = app.world().resource::<CurrencyPools>().get(Currency::ATP);
+    let atp_after_1_update = player_pools(app.world()).get(Currency::ATP);
     assert_eq!(
         atp_after_1_update, 90.0,
         "ATP should decrease by 10 after one update"
@@ -48,7 +50,7 @@ fn test_atp_consumption_decreases_resource() {
     }
 
     // --- Verification after 6 total updates ---
-    let atp_after_6_updates = app.world().resource::<CurrencyPools>().get(Currency::ATP);
+    let atp_after_6_updates = player_pools(app.world()).get(Currency::ATP);
     assert_eq!(
         atp_after_6_updates, 40.0,
         "ATP should be 40.0 after 6 total updates (100 - 6*10)"
@@ -61,7 +63,7 @@ fn test_atp_consumption_decreases_resource() {
     }
 
     // --- Verification of depletion ---
-    let atp_after_10_updates = app.world().resource::<CurrencyPools>().get(Currency::ATP);
+    let atp_after_10_updates = player_pools(app.world()).get(Currency::ATP);
     assert_eq!(
         atp_after_10_updates, 0.0,
         "ATP should be fully depleted to 0.0"
@@ -70,7 +72,7 @@ fn test_atp_consumption_decreases_resource() {
     // --- Verification of non-negative currency ---
     // Run the app one more time to ensure ATP doesn't go below zero.
     app.update();
-    let atp_after_depletion = app.world().resource::<CurrencyPools>().get(Currency::ATP);
+    let atp_after_depletion = player_pools(app.world()).get(Currency::ATP);
     assert_eq!(
         atp_after_depletion, 0.0,
         "ATP should not become negative after depletion"
@@ -99,7 +101,7 @@ fn test_consumption_fails_on_insufficient_funds() {
 
     // --- Verification ---
     // Verify that the resource amount remains unchanged.
-    let power_after_failed_attempt = app.world().resource::<CurrencyPools>().get(Currency::ReducingPower);
+    let power_after_failed_attempt = player_pools(app.world()).get(Currency::ReducingPower);
     assert_eq!(
         power_after_failed_attempt, 5.0,
         "ReducingPower amount should not change after a failed consumption"
@@ -113,32 +115,33 @@ fn test_polymerization_on_toxicity_threshold() {
     let mut app = MetabolisticApp::new_headless();
 
     // Initialize resources and components
-    app.world_mut().resource_mut::<CurrencyPools>().set(Currency::FreeFattyAcids, 100.0);
-    app.world_mut().resource_mut::<CurrencyPools>().set(Currency::StorageBeads, 0.0);
-    app.world_mut().resource_mut::<CurrencyPools>().set(Currency::ATP, 10.0); // Enough ATP for polymerization
+    player_pools_mut(app.world_mut()).set(Currency::FreeFattyAcids, 100.0);
+    player_pools_mut(app.world_mut()).set(Currency::StorageBeads, 0.0);
+    player_pools_mut(app.world_mut()).set(Currency::ATP, 10.0); // Enough ATP for polymerization
     app.world_mut()
         .insert_resource(LipidToxicityThreshold(50.0));
 
     // --- Initial State Verification ---
-    assert_eq!(app.world().resource::<CurrencyPools>().get(Currency::FreeFattyAcids), 100.0);
-    assert_eq!(app.world().resource::<CurrencyPools>().get(Currency::StorageBeads), 0.0);
+    assert_eq!(player_pools(app.world()).get(Currency::FreeFattyAcids), 100.0);
+    assert_eq!(player_pools(app.world()).get(Currency::StorageBeads), 0.0);
 
     // --- Run Simulation ---
     println!(
         "FreeFattyAcids before update: {}",
-        app.world().resource::<CurrencyPools>().get(Currency::FreeFattyAcids)
+        player_pools(app.world()).get(Currency::FreeFattyAcids)
     );
     app.update();
+    run_ticks(app.world_mut(), 1);
     println!(
         "FreeFattyAcids after update: {}",
-        app.world().resource::<CurrencyPools>().get(Currency::FreeFattyAcids)
+        player_pools(app.world()).get(Currency::FreeFattyAcids)
     );
 
     // --- Verification ---
     // Expected FFA after polymerization: 100 (initial) - 20 (poly_rate) = 80
     // Expected StorageBeads: 0 (initial) + 20 (poly_rate) = 20
-    assert_eq!(app.world().resource::<CurrencyPools>().get(Currency::FreeFattyAcids), 80.0);
-    assert_eq!(app.world().resource::<CurrencyPools>().get(Currency::StorageBeads), 20.0);
+    assert_eq!(player_pools(app.world()).get(Currency::FreeFattyAcids), 80.0);
+    assert_eq!(player_pools(app.world()).get(Currency::StorageBeads), 20.0);
 }
 
 #[test]
@@ -147,9 +150,9 @@ fn test_polymerization_with_lipolysis_entity() {
     let mut app = MetabolisticApp::new_headless();
 
     // Initialize resources
-    app.world_mut().resource_mut::<CurrencyPools>().set(Currency::FreeFattyAcids, 100.0);
-    app.world_mut().resource_mut::<CurrencyPools>().set(Currency::StorageBeads, 10.0);
-    app.world_mut().resource_mut::<CurrencyPools>().set(Currency::ATP, 10.0);
+    player_pools_mut(app.world_mut()).set(Currency::FreeFattyAcids, 100.0);
+    player_pools_mut(app.world_mut()).set(Currency::StorageBeads, 10.0);
+    player_pools_mut(app.world_mut()).set(Currency::ATP, 10.0);
     app.world_mut()
         .insert_resource(LipidToxicityThreshold(50.0));
 
@@ -168,26 +171,27 @@ fn test_polymerization_with_lipolysis_entity() {
     ));
 
     // --- Initial State Verification ---
-    assert_eq!(app.world().resource::<CurrencyPools>().get(Currency::FreeFattyAcids), 100.0);
-    assert_eq!(app.world().resource::<CurrencyPools>().get(Currency::StorageBeads), 10.0);
+    assert_eq!(player_pools(app.world()).get(Currency::FreeFattyAcids), 100.0);
+    assert_eq!(player_pools(app.world()).get(Currency::StorageBeads), 10.0);
 
     // --- Run Simulation ---
     println!(
         "FreeFattyAcids before update: {}",
-        app.world().resource::<CurrencyPools>().get(Currency::FreeFattyAcids)
+        player_pools(app.world()).get(Currency::FreeFattyAcids)
     );
     println!(
         "StorageBeads before update: {}",
-        app.world().resource::<CurrencyPools>().get(Currency::StorageBeads)
+        player_pools(app.world()).get(Currency::StorageBeads)
     );
     app.update();
+    run_ticks(app.world_mut(), 1);
     println!(
         "FreeFattyAcids after update: {}",
-        app.world().resource::<CurrencyPools>().get(Currency::FreeFattyAcids)
+        player_pools(app.world()).get(Currency::FreeFattyAcids)
     );
     println!(
         "StorageBeads after update: {}",
-        app.world().resource::<CurrencyPools>().get(Currency::StorageBeads)
+        player_pools(app.world()).get(Currency::StorageBeads)
     );
 
     // --- Expected behavior after fix: Only polymerization runs ---
@@ -196,8 +200,8 @@ fn test_polymerization_with_lipolysis_entity() {
     // Net result: 80 FFA, 30 StorageBeads
 
     // This test verifies the fix prevents conflicting system execution
-    assert_eq!(app.world().resource::<CurrencyPools>().get(Currency::FreeFattyAcids), 80.0);
-    assert_eq!(app.world().resource::<CurrencyPools>().get(Currency::StorageBeads), 30.0);
+    assert_eq!(player_pools(app.world()).get(Currency::FreeFattyAcids), 80.0);
+    assert_eq!(player_pools(app.world()).get(Currency::StorageBeads), 30.0);
 }
 
 #[test]
@@ -206,9 +210,9 @@ fn test_lipolysis_when_safe() {
     let mut app = MetabolisticApp::new_headless();
 
     // Initialize resources - FFA below threshold
-    app.world_mut().resource_mut::<CurrencyPools>().set(Currency::FreeFattyAcids, 30.0);
-    app.world_mut().resource_mut::<CurrencyPools>().set(Currency::StorageBeads, 20.0);
-    app.world_mut().resource_mut::<CurrencyPools>().set(Currency::ATP, 10.0);
+    player_pools_mut(app.world_mut()).set(Currency::FreeFattyAcids, 30.0);
+    player_pools_mut(app.world_mut()).set(Currency::StorageBeads, 20.0);
+    player_pools_mut(app.world_mut()).set(Currency::ATP, 10.0);
     app.world_mut()
         .insert_resource(LipidToxicityThreshold(50.0));
 
@@ -227,16 +231,17 @@ fn test_lipolysis_when_safe() {
     ));
 
     // --- Initial State Verification ---
-    assert_eq!(app.world().resource::<CurrencyPools>().get(Currency::FreeFattyAcids), 30.0);
-    assert_eq!(app.world().resource::<CurrencyPools>().get(Currency::StorageBeads), 20.0);
+    assert_eq!(player_pools(app.world()).get(Currency::FreeFattyAcids), 30.0);
+    assert_eq!(player_pools(app.world()).get(Currency::StorageBeads), 20.0);
 
     // --- Run Simulation ---
     app.update();
+    run_ticks(app.world_mut(), 1);
 
     // --- Verification ---
     // Polymerization: DOES NOT RUN because FFA (30) < threshold (50)
     // Lipolysis: 30 + 5 = 35 FFA, 20 - 5 = 15 StorageBeads
-    assert_eq!(app.world().resource::<CurrencyPools>().get(Currency::FreeFattyAcids), 35.0);
-    assert_eq!(app.world().resource::<CurrencyPools>().get(Currency::StorageBeads), 15.0);
+    assert_eq!(player_pools(app.world()).get(Currency::FreeFattyAcids), 35.0);
+    assert_eq!(player_pools(app.world()).get(Currency::StorageBeads), 15.0);
 }
 
@@ -0,0 +1,37 @@
+#![cfg(feature = "full")]
+
+use metabolistic3d::dashboard::{pool_status, DashboardSettings, PoolStatus};
+use metabolistic3d::metrics::{Metric, MetricsHistory, MetricsSample};
+use metabolistic3d::molecules::Currency;
+use std::collections::BTreeMap;
+
+#[test]
+fn test_pool_status_warnings() {
+    let settings = DashboardSettings::default();
+
+    assert_eq!(pool_status(Currency::ATP, 50.0, &settings, 120.0), PoolStatus::Normal);
+    assert_eq!(pool_status(Currency::ATP, 2.0, &settings, 120.0), PoolStatus::Low);
+    assert_eq!(pool_status(Currency::FreeFattyAcids, 150.0, &settings, 120.0), PoolStatus::Toxic);
+    // Toxicity only applies to lipids, and an unset threshold never warns
+    assert_eq!(pool_status(Currency::Pyruvate, 150.0, &settings, 120.0), PoolStatus::Normal);
+    assert_eq!(pool_status(Currency::FreeFattyAcids, 150.0, &settings, 0.0), PoolStatus::Normal);
+}
+
+#[test]
+fn test_delta_is_change_over_last_tick() {
+    let mut history = MetricsHistory::default();
+    let atp = Metric::Currency(Currency::ATP);
+    for (tick, amount) in [(1, 10.0), (2, 14.0), (3, 11.5)] {
+        history.push(MetricsSample {
+            tick,
+            currencies: BTreeMap::from([(Currency::ATP, amount)]),
+            flux: BTreeMap::new(),
+            genes: BTreeMap::new(),
+            cell_mass: 0.0,
+        });
+        if tick == 1 {
+            assert_eq!(history.delta(atp), None);
+        }
+    }
+    assert_eq!(history.delta(atp), Some(-2.5));
+}
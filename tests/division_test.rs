@@ -0,0 +1,120 @@
+//! # Division Tests
+//!
+//! Cells that grow heavy and rich enough split in two: pools and bead mass are shared
+//! between mother and daughter, and the daughter inherits a possibly mutated genome.
+
+use bevy::prelude::*;
+use metabolistic3d::blocks::genome::{BlockKind, GeneState, Genome, MutationConfig, MutationStrategy};
+use metabolistic3d::metabolism::cells::{player_pools, player_pools_mut, spawn_cell, Cell, CellMember};
+use metabolistic3d::metabolism::division::CellDivided;
+use metabolistic3d::metabolism::{run_ticks, CurrencyPools};
+use metabolistic3d::molecules::{CellMass, Currency};
+use metabolistic3d::MetabolisticApp;
+
+/// Pools that meet the default division thresholds, with nothing to metabolise them.
+fn rich_pools() -> CurrencyPools {
+    let mut pools = CurrencyPools::default();
+    pools.set(Currency::ATP, 200.0);
+    pools.set(Currency::CarbonSkeletons, 50.0);
+    pools.set(Currency::StorageBeads, 10.0);
+    pools
+}
+
+fn daughters_of(app: &mut App, parent: Option<Entity>) -> Vec<Entity> {
+    let events = app.world().resource::<Events<CellDivided>>();
+    let mut reader = events.get_cursor();
+    reader
+        .read(events)
+        .filter(|divided| parent.is_none_or(|parent| divided.parent == parent))
+        .map(|divided| divided.daughter)
+        .collect()
+}
+
+/// Mutates every gene, but only when exposed for at least five time units.
+struct MutateWhenExposed;
+
+impl MutationStrategy for MutateWhenExposed {
+    fn should_mutate(&mut self, _block_kind: BlockKind, delta_time: f32) -> bool {
+        delta_time >= 5.0
+    }
+
+    fn get_mutation_target(&mut self, _block_kind: BlockKind) -> GeneState {
+        GeneState::Mutated
+    }
+}
+
+#[test]
+fn test_cell_divides_into_two_halves() {
+    let mut app = MetabolisticApp::new_headless();
+    app.update();
+    let mut genome = Genome::default();
+    genome.add_gene(BlockKind::Fermentation);
+    let table = genome.table.clone();
+    let cell = spawn_cell(app.world_mut(), genome, rich_pools());
+    app.world_mut().get_mut::<CellMass>(cell).unwrap().extra = 4.0;
+
+    run_ticks(app.world_mut(), 1);
+    let daughters = daughters_of(&mut app, Some(cell));
+    assert_eq!(daughters.len(), 1);
+    let daughter = daughters[0];
+    assert!(app.world().get::<Cell>(daughter).is_some());
+    assert_eq!(app.world().get::<CellMember>(daughter), Some(&CellMember(daughter)));
+
+    for entity in [cell, daughter] {
+        let pools = app.world().get::<CurrencyPools>(entity).unwrap();
+        assert_eq!(pools.get(Currency::ATP), 100.0);
+        assert_eq!(pools.get(Currency::CarbonSkeletons), 25.0);
+        assert_eq!(pools.get(Currency::StorageBeads), 5.0);
+        let mass = app.world().get::<CellMass>(entity).unwrap();
+        assert_eq!((mass.base, mass.extra), (1.0, 2.0));
+    }
+    // The deterministic headless strategy never mutates
+    assert_eq!(app.world().get::<Genome>(daughter).unwrap().table, table);
+
+    // Neither half is rich enough to divide again
+    app.world_mut().resource_mut::<Events<CellDivided>>().clear();
+    run_ticks(app.world_mut(), 1);
+    assert!(daughters_of(&mut app, None).is_empty());
+}
+
+#[test]
+fn test_player_cell_divides_into_a_cell_of_its_own() {
+    let mut app = MetabolisticApp::new_headless();
+    app.update();
+    app.world_mut().spawn(CellMass { base: 1.0, extra: 4.0 });
+    *player_pools_mut(app.world_mut()) = rich_pools();
+
+    run_ticks(app.world_mut(), 1);
+    let daughters = daughters_of(&mut app, None);
+    assert_eq!(daughters.len(), 1);
+    // The player keeps its half in the resources
+    let player_atp = player_pools(app.world()).get(Currency::ATP);
+    assert!(player_atp < 150.0);
+    let daughter = app.world().get::<CurrencyPools>(daughters[0]).unwrap();
+    assert_eq!(daughter.get(Currency::ATP), player_atp);
+}
+
+#[test]
+fn test_replicated_genome_mutates_with_exposure() {
+    let mut genome = Genome::default();
+    genome.add_gene(BlockKind::Fermentation);
+    genome.express_gene(BlockKind::Fermentation);
+
+    assert_eq!(genome.replicate(None, 10.0).table, genome.table);
+
+    let mut config = MutationConfig {
+        strategy: Box::new(MutateWhenExposed),
+        rate_multiplier: 1.0,
+    };
+    assert_eq!(genome.replicate(Some(&mut config), 1.0).table, genome.table);
+    let mutated = genome.replicate(Some(&mut config), 10.0);
+    assert_eq!(
+        mutated.get_gene_state(&BlockKind::Fermentation),
+        Some(&GeneState::Mutated)
+    );
+    // The original is left alone
+    assert_eq!(
+        genome.get_gene_state(&BlockKind::Fermentation),
+        Some(&GeneState::Expressed)
+    );
+}
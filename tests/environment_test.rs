@@ -0,0 +1,118 @@
+//! # Environment Tests
+//!
+//! The nutrient grid diffuses and replenishes on its own, cells absorb from the patch
+//! under them and secrete their exported waste back into it.
+
+use bevy::prelude::*;
+use metabolistic3d::blocks::genome::Genome;
+use metabolistic3d::blocks::vesicle_export::VesicleExportRate;
+use metabolistic3d::environment::{EnvironmentRules, Nutrient, NutrientGrid};
+use metabolistic3d::metabolism::cells::{player_pools, player_pools_mut, spawn_cell, PlayerCell};
+use metabolistic3d::metabolism::{run_ticks, CurrencyPools};
+use metabolistic3d::molecules::Currency;
+use metabolistic3d::MetabolisticApp;
+
+fn empty_grid() -> NutrientGrid {
+    NutrientGrid::new(Vec2::ZERO, 1.0, 3, 3, [0.0; Nutrient::COUNT])
+}
+
+/// A headless app whose grid has every patch at the default baseline, whatever the terrain.
+fn app_with_even_grid() -> App {
+    let mut app = MetabolisticApp::new_headless();
+    app.update();
+    let baseline = EnvironmentRules::default().baseline_levels();
+    app.insert_resource(NutrientGrid::new(Vec2::splat(-250.0), 10.0, 50, 50, baseline));
+    app
+}
+
+/// A headless app whose player cell sits in the middle of patch `(2, 3)`.
+fn app_with_player_at_patch() -> (App, usize) {
+    let mut app = app_with_even_grid();
+    let position = Vec3::new(-250.0 + 25.0, 1.0, -250.0 + 35.0);
+    let mut player = app.world_mut().query_filtered::<Entity, With<PlayerCell>>();
+    let player = player.single(app.world());
+    app.world_mut().entity_mut(player).insert(Transform::from_translation(position));
+    let patch = app.world().resource::<NutrientGrid>().patch_at(position).unwrap();
+    (app, patch)
+}
+
+#[test]
+fn test_patches_are_found_by_position() {
+    let grid = NutrientGrid::new(Vec2::new(-10.0, -10.0), 5.0, 4, 4, [0.0; Nutrient::COUNT]);
+    assert_eq!(grid.patch_at(Vec3::new(-10.0, 3.0, -10.0)), Some(0));
+    assert_eq!(grid.patch_at(Vec3::new(-4.0, 0.0, 1.0)), Some(2 * 4 + 1));
+    assert_eq!(grid.patch_at(Vec3::new(10.0, 0.0, 0.0)), None);
+    assert_eq!(grid.patch_at(Vec3::new(-11.0, 0.0, 0.0)), None);
+    assert_eq!(grid.patch_center(2 * 4 + 1), Vec3::new(-2.5, 0.0, 2.5));
+}
+
+#[test]
+fn test_diffusion_spreads_and_keeps_the_total() {
+    let mut grid = empty_grid();
+    grid.set(4, Nutrient::Sugar, 9.0);
+    grid.diffuse(0.1);
+    assert!((grid.get(4, Nutrient::Sugar) - 5.4).abs() < 1e-5);
+    for neighbour in [1, 3, 5, 7] {
+        assert!((grid.get(neighbour, Nutrient::Sugar) - 0.9).abs() < 1e-5);
+    }
+    assert_eq!(grid.get(0, Nutrient::Sugar), 0.0);
+    assert!((grid.total(Nutrient::Sugar) - 9.0).abs() < 1e-4);
+
+    for _ in 0..500 {
+        grid.diffuse(0.25);
+    }
+    assert!((grid.get(0, Nutrient::Sugar) - 1.0).abs() < 1e-3);
+}
+
+#[test]
+fn test_patches_replenish_towards_their_baseline() {
+    let mut grid = empty_grid();
+    grid.set_baseline(0, Nutrient::Light, 10.0);
+    grid.replenish(0.5);
+    assert_eq!(grid.get(0, Nutrient::Light), 5.0);
+    grid.replenish(0.5);
+    assert_eq!(grid.get(0, Nutrient::Light), 7.5);
+    assert_eq!(grid.get(1, Nutrient::Light), 0.0);
+}
+
+#[test]
+fn test_player_absorbs_from_the_patch_under_it() {
+    let (mut app, patch) = app_with_player_at_patch();
+    let rules = app.world().resource::<EnvironmentRules>().clone();
+    let sugar = rules.uptake[&Nutrient::Sugar];
+    let before = app.world().resource::<NutrientGrid>().get(patch, Nutrient::Sugar);
+    let neighbour = app.world().resource::<NutrientGrid>().get(patch + 1, Nutrient::Sugar);
+    assert_eq!(before, rules.baseline[&Nutrient::Sugar]);
+
+    player_pools_mut(app.world_mut()).set(Currency::Pyruvate, 0.0);
+    run_ticks(app.world_mut(), 1);
+    let grid = app.world().resource::<NutrientGrid>();
+    assert_eq!(grid.get(patch, Nutrient::Sugar), before - sugar.rate);
+    assert_eq!(grid.get(patch + 1, Nutrient::Sugar), neighbour);
+    assert_eq!(
+        player_pools(app.world()).get(sugar.currency),
+        sugar.rate
+    );
+}
+
+#[test]
+fn test_cells_off_the_scene_absorb_nothing() {
+    let mut app = app_with_even_grid();
+    let cell = spawn_cell(app.world_mut(), Genome::default(), CurrencyPools::default());
+    let total = app.world().resource::<NutrientGrid>().total(Nutrient::Sugar);
+    run_ticks(app.world_mut(), 1);
+    assert_eq!(app.world().get::<CurrencyPools>(cell).unwrap().get(Currency::Pyruvate), 0.0);
+    assert_eq!(app.world().resource::<NutrientGrid>().total(Nutrient::Sugar), total);
+}
+
+#[test]
+fn test_exported_waste_is_secreted_into_the_grid() {
+    let (mut app, patch) = app_with_player_at_patch();
+    player_pools_mut(app.world_mut()).set(Currency::OrganicWaste, 5.0);
+    let rate = app.world().resource::<VesicleExportRate>().0;
+
+    run_ticks(app.world_mut(), 1);
+    let grid = app.world().resource::<NutrientGrid>();
+    assert_eq!(grid.get(patch, Nutrient::OrganicWaste), rate);
+    assert_eq!(grid.total(Nutrient::OrganicWaste), rate);
+}
@@ -1,3 +1,4 @@
+use metabolistic3d::metabolism::cells::{player_genome_mut, player_pools, player_pools_mut};
 use metabolistic3d::molecules::Currency;
 use metabolistic3d::blocks::fermentation::FermentationPlugin;
 use metabolistic3d::blocks::vesicle_export::{VesicleExportPlugin, VesicleExportBlock};
@@ -22,7 +23,7 @@ fn test_fermentation_produces_and_consumes() {
 
     // Set up CurrencyPools with the same starting amounts
     {
-        let mut currency_pools = app.world_mut().resource_mut::<CurrencyPools>();
+        let mut currency_pools = player_pools_mut(app.world_mut());
         currency_pools.set(Currency::Pyruvate, 100.0);
         currency_pools.set(Currency::ReducingPower, 100.0);
         currency_pools.set(Currency::ATP, 0.0);
@@ -31,7 +32,7 @@ fn test_fermentation_produces_and_consumes() {
     
     // Activate the fermentation gene so the block will be active
     {
-        let mut genome = app.world_mut().resource_mut::<Genome>();
+        let mut genome = player_genome_mut(app.world_mut());
         genome.add_gene(BlockKind::Fermentation);
         genome.express_gene(BlockKind::Fermentation);
     }
@@ -44,7 +45,7 @@ fn test_fermentation_produces_and_consumes() {
 
     // --- Verification ---
     // Check CurrencyPools for the actual results since that's where changes are applied
-    let currency_pools = app.world().resource::<CurrencyPools>();
+    let currency_pools = player_pools(app.world());
     assert!((currency_pools.get(Currency::OrganicWaste) - 1.0).abs() < 1e-6, 
             "Expected OrganicWaste to be 1.0, got {}", currency_pools.get(Currency::OrganicWaste));
     assert!((currency_pools.get(Currency::ATP) - 1.0).abs() < 1e-6,
@@ -57,7 +58,7 @@ fn test_fermentation_produces_and_consumes() {
     app.update();
     app.world_mut().run_schedule(FixedUpdate);
     
-    let currency_pools = app.world().resource::<CurrencyPools>();
+    let currency_pools = player_pools(app.world());
     assert!((currency_pools.get(Currency::OrganicWaste) - 2.0).abs() < 1e-6,
             "Expected OrganicWaste to be 2.0, got {}", currency_pools.get(Currency::OrganicWaste));
 }
@@ -76,7 +77,7 @@ fn test_vesicle_export_decreases_organic_waste() {
     app.world_mut().spawn(VesicleExportBlock);
 
     // Initialize OrganicWaste with a starting amount.
-    app.world_mut().resource_mut::<CurrencyPools>().set(Currency::OrganicWaste, 100.0);
+    player_pools_mut(app.world_mut()).set(Currency::OrganicWaste, 100.0);
 
     // --- Run Simulation ---
     let fixed_time_step = app.world().resource::<Time<Fixed>>().delta();
@@ -86,7 +87,7 @@ fn test_vesicle_export_decreases_organic_waste() {
 
     // --- Verification ---
     // Assuming default VesicleExportRate(0.1)
-    assert!((app.world().resource::<CurrencyPools>().get(Currency::OrganicWaste) - 99.9).abs() < 1e-6);
+    assert!((player_pools(app.world()).get(Currency::OrganicWaste) - 99.9).abs() < 1e-6);
 
     // Run until depletion
     for _ in 0..999 {
@@ -96,5 +97,5 @@ fn test_vesicle_export_decreases_organic_waste() {
     }
 
     // Verify that it doesn't go below zero
-    assert!((app.world().resource::<CurrencyPools>().get(Currency::OrganicWaste) - 0.0).abs() < 1e-6);
+    assert!((player_pools(app.world()).get(Currency::OrganicWaste) - 0.0).abs() < 1e-6);
 }
@@ -0,0 +1,129 @@
+//! # Fixed Timestep Tests
+//!
+//! Metabolism must advance per tick, not per frame: the same number of ticks
+//! has to produce the same currency trajectory at any frame rate.
+
+use bevy::prelude::*;
+use bevy::time::TimeUpdateStrategy;
+use metabolistic3d::blocks::genome::{
+    BlockKind, GeneState, MutationConfig, RandomMutationStrategy,
+};
+use metabolistic3d::metabolism::cells::{player_genome, player_genome_mut, player_pools};
+use metabolistic3d::metabolism::{
+    run_ticks, MetabolicTick, MetabolicTickRate, DEFAULT_METABOLIC_TICK_SECONDS,
+};
+use metabolistic3d::molecules::{CellMass, Currency, LipidToxicityThreshold, PolyMer};
+use metabolistic3d::MetabolisticApp;
+use std::time::Duration;
+
+const TICKS: u64 = 12;
+
+fn sample(app: &App) -> Vec<(Currency, f32)> {
+    let mut pools: Vec<(Currency, f32)> = player_pools(app.world())
+        .pools
+        .iter()
+        .map(|(&c, &v)| (c, v))
+        .collect();
+    pools.sort_by_key(|&(c, _)| c);
+    pools
+}
+
+fn configured_app() -> App {
+    let mut app = MetabolisticApp::new_headless();
+    app.update();
+    app.world_mut().insert_resource(LipidToxicityThreshold(5.0));
+    app.world_mut().spawn((
+        CellMass { base: 1.0, extra: 0.0 },
+        PolyMer { capacity: 100.0, target_fill: 50.0, poly_rate: 5.0, lipo_rate: 1.0 },
+    ));
+    player_genome_mut(app.world_mut()).express_gene(BlockKind::Fermentation);
+    app.update();
+    app
+}
+
+/// Run a windowed-style app at `frame_seconds` per frame and record pools after each tick.
+fn trajectory_at_frame_rate(frame_seconds: f64) -> Vec<Vec<(Currency, f32)>> {
+    let mut app = configured_app();
+    app.insert_resource(TimeUpdateStrategy::ManualDuration(Duration::from_secs_f64(
+        frame_seconds,
+    )));
+
+    let start = app.world().resource::<MetabolicTick>().0;
+    let mut last_tick = start;
+    let mut trajectory = Vec::new();
+    while last_tick - start < TICKS {
+        app.update();
+        let tick = app.world().resource::<MetabolicTick>().0;
+        assert!(tick - last_tick <= 1, "frame spanned more than one tick");
+        if tick != last_tick {
+            trajectory.push(sample(&app));
+            last_tick = tick;
+        }
+    }
+    trajectory
+}
+
+#[test]
+fn test_trajectory_is_independent_of_frame_rate() {
+    let fast = trajectory_at_frame_rate(1.0 / 144.0);
+    let slow = trajectory_at_frame_rate(1.0 / 20.0);
+    assert_eq!(fast, slow);
+
+    // Explicit headless ticks follow the same trajectory
+    let mut app = configured_app();
+    let headless: Vec<_> = (0..TICKS)
+        .map(|_| {
+            run_ticks(app.world_mut(), 1);
+            sample(&app)
+        })
+        .collect();
+    assert_eq!(fast, headless);
+}
+
+#[test]
+fn test_headless_updates_do_not_advance_metabolism() {
+    let mut app = configured_app();
+    let before = sample(&app);
+    for _ in 0..10 {
+        app.update();
+    }
+    assert_eq!(app.world().resource::<MetabolicTick>().0, 0);
+    assert_eq!(sample(&app), before);
+
+    run_ticks(app.world_mut(), 3);
+    assert_eq!(app.world().resource::<MetabolicTick>().0, 3);
+}
+
+#[test]
+fn test_tick_rate_is_configurable() {
+    let mut app = configured_app();
+    assert_eq!(
+        app.world().resource::<Time<Fixed>>().timestep(),
+        Duration::from_secs_f64(DEFAULT_METABOLIC_TICK_SECONDS)
+    );
+
+    app.insert_resource(MetabolicTickRate(0.1));
+    app.update();
+    assert_eq!(
+        app.world().resource::<Time<Fixed>>().timestep(),
+        Duration::from_secs_f64(0.1)
+    );
+}
+
+#[test]
+fn test_time_based_systems_see_the_tick_length_under_run_ticks() {
+    let mut app = configured_app();
+    // Certain to fire once a tick's worth of time has passed, never on a zero delta
+    app.insert_resource(MutationConfig {
+        strategy: Box::new(RandomMutationStrategy { mutation_rate: 1.0e6 }),
+        rate_multiplier: 1.0,
+    });
+
+    run_ticks(app.world_mut(), 1);
+    assert_eq!(
+        player_genome(app.world()).get_gene_state(&BlockKind::Fermentation),
+        Some(&GeneState::Mutated)
+    );
+    // Frames outside the tick keep their own clock
+    assert_eq!(app.world().resource::<Time>().delta(), Duration::ZERO);
+}
@@ -0,0 +1,193 @@
+//! # Flow Editor Tests
+//!
+//! Wiring edits are validated and staged in a draft, then committed as edge entities that
+//! `rebuild_graph` routes dependencies through.
+
+use bevy::ecs::system::RunSystemOnce;
+use bevy::prelude::*;
+use metabolistic3d::blocks::genome::BlockKind;
+use metabolistic3d::metabolism::{
+    rebuild_graph, BlockStatus, FlowDirty, FlowEdge, FluxProfile, MetabolicEdge, MetabolicGraph,
+    MetabolicNode,
+};
+use metabolistic3d::molecules::Currency;
+use metabolistic3d::scenes::flow_editor::{
+    commit_flow_draft, FlowCommit, FlowDraft, FlowEditError, NodePorts,
+};
+use metabolistic3d::snapshot::SimulationSnapshot;
+use std::collections::HashMap;
+
+fn spawn_node(world: &mut World, kind: BlockKind, flux: &[(Currency, f32)]) -> Entity {
+    world
+        .spawn((
+            MetabolicNode {
+                kind,
+                status: BlockStatus::Active,
+            },
+            FluxProfile(flux.iter().copied().collect::<HashMap<_, _>>()),
+        ))
+        .id()
+}
+
+/// Two pyruvate producers and one consumer.
+fn pyruvate_world() -> (World, [Entity; 3]) {
+    let mut world = World::new();
+    world.init_resource::<FlowDirty>();
+    world.init_resource::<MetabolicGraph>();
+    let glycolysis = spawn_node(
+        &mut world,
+        BlockKind::SugarCatabolism,
+        &[(Currency::Pyruvate, 2.0), (Currency::ATP, 2.0)],
+    );
+    let lightcapture = spawn_node(&mut world, BlockKind::LightCapture, &[(Currency::Pyruvate, 1.0)]);
+    let fermentation = spawn_node(
+        &mut world,
+        BlockKind::Fermentation,
+        &[(Currency::Pyruvate, -1.0), (Currency::OrganicWaste, 1.0)],
+    );
+    (world, [glycolysis, lightcapture, fermentation])
+}
+
+fn ports(world: &World, entity: Entity) -> NodePorts {
+    NodePorts::from_profile(world.get::<FluxProfile>(entity).unwrap())
+}
+
+#[test]
+fn test_draft_validates_and_copies_on_write() {
+    let (world, [glycolysis, _, fermentation]) = pyruvate_world();
+    let (from, to) = (ports(&world, glycolysis), ports(&world, fermentation));
+    assert_eq!(from.outputs, vec![Currency::ATP, Currency::Pyruvate]);
+    assert_eq!(to.inputs, vec![Currency::Pyruvate]);
+
+    let mut draft = FlowDraft::default();
+    let wire = |from, to, currency| FlowEdge { from, to, currency };
+
+    assert_eq!(
+        draft.connect(wire(glycolysis, glycolysis, Currency::Pyruvate), &from, &from),
+        Err(FlowEditError::SelfLoop)
+    );
+    assert_eq!(
+        draft.connect(wire(fermentation, glycolysis, Currency::OrganicWaste), &to, &from),
+        Err(FlowEditError::NotConsumed(Currency::OrganicWaste))
+    );
+    assert_eq!(
+        draft.connect(wire(glycolysis, fermentation, Currency::ATP), &from, &to),
+        Err(FlowEditError::NotConsumed(Currency::ATP))
+    );
+    assert!(!draft.is_modified());
+
+    let pyruvate = wire(glycolysis, fermentation, Currency::Pyruvate);
+    draft.connect(pyruvate, &from, &to).unwrap();
+    assert_eq!(draft.connect(pyruvate, &from, &to), Err(FlowEditError::Duplicate));
+    assert_eq!(draft.added(), vec![pyruvate]);
+
+    draft.revert();
+    assert!(draft.edges().is_empty());
+    assert!(!draft.is_modified());
+
+    // Removing and re-adding a live wire leaves nothing to commit
+    let mut draft = FlowDraft::new(vec![pyruvate]);
+    assert!(draft.disconnect(&pyruvate));
+    assert_eq!(draft.removed(), vec![pyruvate]);
+    draft.connect(pyruvate, &from, &to).unwrap();
+    assert!(!draft.is_modified());
+}
+
+#[test]
+fn test_commit_syncs_edge_entities_and_marks_dirty() {
+    let (mut world, [glycolysis, lightcapture, fermentation]) = pyruvate_world();
+    let first = FlowEdge {
+        from: glycolysis,
+        to: fermentation,
+        currency: Currency::Pyruvate,
+    };
+    let second = FlowEdge {
+        from: lightcapture,
+        ..first
+    };
+
+    let mut draft = FlowDraft::from_world(&mut world);
+    draft
+        .connect(first, &ports(&world, glycolysis), &ports(&world, fermentation))
+        .unwrap();
+    world.insert_resource(draft);
+    assert_eq!(commit_flow_draft(&mut world), FlowCommit { added: 1, removed: 0 });
+    assert!(world.resource::<FlowDirty>().0);
+    assert!(!world.resource::<FlowDraft>().is_modified());
+
+    world.resource_mut::<FlowDirty>().0 = false;
+    let mut draft = world.resource::<FlowDraft>().clone();
+    draft.disconnect(&first);
+    draft
+        .connect(second, &ports(&world, lightcapture), &ports(&world, fermentation))
+        .unwrap();
+    world.insert_resource(draft);
+    assert_eq!(commit_flow_draft(&mut world), FlowCommit { added: 1, removed: 1 });
+    assert!(world.resource::<FlowDirty>().0);
+
+    let mut query = world.query_filtered::<&FlowEdge, With<MetabolicEdge>>();
+    let live: Vec<FlowEdge> = query.iter(&world).copied().collect();
+    assert_eq!(live, vec![second]);
+
+    // Committing an unchanged draft leaves the graph alone
+    world.resource_mut::<FlowDirty>().0 = false;
+    assert_eq!(commit_flow_draft(&mut world), FlowCommit::default());
+    assert!(!world.resource::<FlowDirty>().0);
+}
+
+#[test]
+fn test_rebuild_graph_follows_wires() {
+    let (mut world, [glycolysis, lightcapture, fermentation]) = pyruvate_world();
+    world.run_system_once(rebuild_graph).unwrap();
+    let mut deps: Vec<_> = world.resource::<MetabolicGraph>().dependencies_of(fermentation).collect();
+    deps.sort();
+    let mut both = vec![glycolysis, lightcapture];
+    both.sort();
+    assert_eq!(deps, both);
+
+    world.spawn((
+        MetabolicEdge,
+        FlowEdge {
+            from: lightcapture,
+            to: fermentation,
+            currency: Currency::Pyruvate,
+        },
+    ));
+    world.run_system_once(rebuild_graph).unwrap();
+    assert_eq!(
+        world.resource::<MetabolicGraph>().dependencies_of(fermentation).collect::<Vec<_>>(),
+        vec![lightcapture]
+    );
+}
+
+#[test]
+fn test_snapshot_round_trip_keeps_wires() {
+    let (mut world, [glycolysis, _, fermentation]) = pyruvate_world();
+    world.spawn((
+        MetabolicEdge,
+        FlowEdge {
+            from: glycolysis,
+            to: fermentation,
+            currency: Currency::Pyruvate,
+        },
+    ));
+
+    let snapshot = SimulationSnapshot::capture(&mut world);
+    assert_eq!(snapshot.flow_edges.len(), 1);
+    let json = snapshot.to_json().unwrap();
+    let mapping = SimulationSnapshot::from_json(&json)
+        .unwrap()
+        .apply(&mut world)
+        .unwrap();
+
+    let mut query = world.query::<&FlowEdge>();
+    let live: Vec<FlowEdge> = query.iter(&world).copied().collect();
+    assert_eq!(live.len(), 1, "old wires are replaced, not duplicated");
+    let restored = live[0];
+    assert!(mapping.values().any(|&e| e == restored.from));
+    assert!(mapping.values().any(|&e| e == restored.to));
+    assert_eq!(
+        world.get::<MetabolicNode>(restored.to).unwrap().kind,
+        BlockKind::Fermentation
+    );
+}
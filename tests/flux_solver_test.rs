@@ -0,0 +1,141 @@
+//! # Flux Solver Tests
+//!
+//! The solver works on a plain-data snapshot, and large graphs are solved on a background
+//! task whose result lands on a later tick.
+
+use std::collections::HashMap;
+use std::thread;
+use std::time::Duration;
+
+use bevy::prelude::Entity;
+use metabolistic3d::blocks::genome::BlockKind;
+use metabolistic3d::metabolism::cells::player_genome_mut;
+use metabolistic3d::metabolism::solver::{
+    fit_to_pools, solve, FluxProblem, FluxSolveMode, PendingFluxSolve, ProblemNode,
+};
+use metabolistic3d::metabolism::{
+    run_ticks, BlockStatus, CurrencyVector, FlowDirty, FluxProfile, FluxResult, MetabolicNode,
+};
+use metabolistic3d::molecules::Currency;
+use metabolistic3d::MetabolisticApp;
+
+fn flux(entries: &[(Currency, f32)]) -> CurrencyVector {
+    let mut vector = CurrencyVector::default();
+    for &(currency, amount) in entries {
+        vector.set(currency, amount);
+    }
+    vector
+}
+
+#[test]
+fn test_solve_plain_problem() {
+    let producer = Entity::from_raw(1);
+    let consumer = Entity::from_raw(2);
+    let starved = Entity::from_raw(3);
+    let problem = FluxProblem {
+        nodes: vec![
+            ProblemNode {
+                entity: producer,
+                status: BlockStatus::Active,
+                compartment: None,
+                flux: flux(&[(Currency::Pyruvate, 2.0)]),
+            },
+            // Only runs on what the producer made this tick
+            ProblemNode {
+                entity: consumer,
+                status: BlockStatus::Mutated,
+                compartment: None,
+                flux: flux(&[(Currency::Pyruvate, -2.0), (Currency::ATP, 4.0)]),
+            },
+            ProblemNode {
+                entity: starved,
+                status: BlockStatus::Active,
+                compartment: None,
+                flux: flux(&[(Currency::Pyruvate, -5.0)]),
+            },
+        ],
+        ..Default::default()
+    };
+
+    let result = solve(&problem);
+    assert_eq!(result.currency_changes[&Currency::Pyruvate], 1.0);
+    assert_eq!(result.currency_changes[&Currency::ATP], 2.0);
+    assert_eq!(result.entity_flux[&producer], 2.0);
+    assert_eq!(result.entity_flux[&consumer], 1.0);
+    assert_eq!(result.entity_flux[&starved], 0.0);
+}
+
+#[test]
+fn test_late_results_are_scaled_to_what_the_pools_still_hold() {
+    let consumer = Entity::from_raw(1);
+    let organelle = Entity::from_raw(2);
+    let mut result = FluxResult {
+        entity_flux: HashMap::from([(consumer, 2.0)]),
+        currency_changes: HashMap::from([(Currency::Pyruvate, -4.0), (Currency::ATP, 8.0)]),
+        compartment_changes: HashMap::from([(organelle, HashMap::from([(Currency::ReducingPower, -1.0)]))]),
+    };
+
+    // Solved against 4 pyruvate, but only 1 is left by the time it lands
+    let pools = HashMap::from([(Currency::Pyruvate, 1.0)]);
+    let compartments = HashMap::from([(organelle, HashMap::from([(Currency::ReducingPower, 10.0)]))]);
+    fit_to_pools(&mut result, &pools, &compartments);
+    assert_eq!(result.currency_changes[&Currency::Pyruvate], -1.0);
+    assert_eq!(result.currency_changes[&Currency::ATP], 2.0);
+    assert_eq!(result.compartment_changes[&organelle][&Currency::ReducingPower], -0.25);
+    assert_eq!(result.entity_flux[&consumer], 0.5);
+
+    // Pools that still cover everything leave the result alone
+    fit_to_pools(&mut result, &HashMap::from([(Currency::Pyruvate, 5.0)]), &compartments);
+    assert_eq!(result.currency_changes[&Currency::Pyruvate], -1.0);
+    assert_eq!(result.entity_flux[&consumer], 0.5);
+}
+
+#[test]
+fn test_background_solve_applies_on_a_later_tick() {
+    let mut app = MetabolisticApp::new_headless();
+    app.update();
+    app.world_mut()
+        .insert_resource(FluxSolveMode::Background { min_nodes: 1 });
+    let mut genome = player_genome_mut(app.world_mut());
+    genome.add_gene(BlockKind::AminoAcidBiosynthesis);
+    genome.express_gene(BlockKind::AminoAcidBiosynthesis);
+    app.world_mut().spawn((
+        MetabolicNode {
+            kind: BlockKind::AminoAcidBiosynthesis,
+            status: BlockStatus::Active,
+        },
+        FluxProfile(HashMap::from([
+            (Currency::Pyruvate, -0.1),
+            (Currency::OrganicWaste, 0.1),
+        ])),
+    ));
+    app.world_mut().resource_mut::<FlowDirty>().0 = true;
+
+    // The first tick only starts the solve
+    run_ticks(app.world_mut(), 1);
+    assert!(app.world().resource::<FluxResult>().currency_changes.is_empty());
+    assert!(app.world().resource::<PendingFluxSolve>().0.is_some());
+
+    let mut solved = None;
+    for _ in 0..200 {
+        thread::sleep(Duration::from_millis(5));
+        run_ticks(app.world_mut(), 1);
+        let changes = &app.world().resource::<FluxResult>().currency_changes;
+        if !changes.is_empty() {
+            solved = changes.get(&Currency::Pyruvate).copied();
+            break;
+        }
+    }
+    assert_eq!(solved, Some(-0.1));
+
+    // Back below the threshold, the same tick solves and applies again
+    app.world_mut()
+        .insert_resource(FluxSolveMode::Background { min_nodes: usize::MAX });
+    thread::sleep(Duration::from_millis(50));
+    run_ticks(app.world_mut(), 2);
+    assert!(app.world().resource::<PendingFluxSolve>().0.is_none());
+    assert_eq!(
+        app.world().resource::<FluxResult>().currency_changes.get(&Currency::Pyruvate),
+        Some(&-0.1)
+    );
+}
@@ -0,0 +1,82 @@
+#![cfg(feature = "full")]
+
+//! # Flux Visuals Tests
+//!
+//! Node spheres follow their node's status and flux without touching the solver.
+
+use bevy::ecs::system::RunSystemOnce;
+use bevy::prelude::*;
+use metabolistic3d::blocks::genome::BlockKind;
+use metabolistic3d::flux_visuals::{
+    glow_strength, spawn_node_visuals, status_color, update_node_visuals, FluxVisualSettings,
+    HasNodeVisual, NodeVisual,
+};
+use metabolistic3d::metabolism::{BlockStatus, FluxResult, MetabolicNode};
+
+#[test]
+fn test_status_colours_and_glow() {
+    assert_ne!(
+        status_color(BlockStatus::Active, 0.0, 2.0),
+        status_color(BlockStatus::Silent, 0.0, 2.0)
+    );
+    // Mutated nodes alternate between two ambers
+    assert_ne!(
+        status_color(BlockStatus::Mutated, 0.0, 2.0),
+        status_color(BlockStatus::Mutated, 0.3, 2.0)
+    );
+    assert_eq!(
+        status_color(BlockStatus::Mutated, 0.0, 2.0),
+        status_color(BlockStatus::Mutated, 0.5, 2.0)
+    );
+
+    assert_eq!(glow_strength(2.0, 4.0), 0.5);
+    assert_eq!(glow_strength(-4.0, 4.0), 1.0);
+    assert_eq!(glow_strength(1.0, 0.0), 0.0);
+}
+
+#[test]
+fn test_visuals_track_nodes_and_flux() {
+    let mut world = World::new();
+    world.init_resource::<Assets<Mesh>>();
+    world.init_resource::<Assets<StandardMaterial>>();
+    world.init_resource::<FluxVisualSettings>();
+    world.init_resource::<FluxResult>();
+    world.init_resource::<Time>();
+
+    let busy = world
+        .spawn(MetabolicNode {
+            kind: BlockKind::Fermentation,
+            status: BlockStatus::Active,
+        })
+        .id();
+    let idle = world
+        .spawn(MetabolicNode {
+            kind: BlockKind::Respiration,
+            status: BlockStatus::Silent,
+        })
+        .id();
+    world.resource_mut::<FluxResult>().entity_flux.insert(busy, 3.0);
+
+    world.run_system_once(spawn_node_visuals).unwrap();
+    world.run_system_once(update_node_visuals).unwrap();
+
+    let visual_of = |world: &World, node: Entity| world.get::<HasNodeVisual>(node).unwrap().0;
+    let (busy_visual, idle_visual) = (visual_of(&world, busy), visual_of(&world, idle));
+    assert_eq!(world.get::<NodeVisual>(busy_visual).unwrap().node, busy);
+
+    let emissive = |world: &World, visual: Entity| {
+        let handle = &world.get::<MeshMaterial3d<StandardMaterial>>(visual).unwrap().0;
+        world.resource::<Assets<StandardMaterial>>().get(handle).unwrap().emissive
+    };
+    assert!(emissive(&world, busy_visual).green > 0.0);
+    let idle_glow = emissive(&world, idle_visual);
+    assert_eq!((idle_glow.red, idle_glow.green, idle_glow.blue), (0.0, 0.0, 0.0));
+    assert!(world.get::<Transform>(busy_visual).unwrap().scale.x > 1.0);
+
+    // A second pass spawns nothing new; a despawned node loses its sphere
+    world.despawn(idle);
+    world.run_system_once(spawn_node_visuals).unwrap();
+    let mut visuals = world.query::<&NodeVisual>();
+    let remaining: Vec<Entity> = visuals.iter(&world).map(|v| v.node).collect();
+    assert_eq!(remaining, vec![busy]);
+}
@@ -0,0 +1,195 @@
+//! # Genome Command Tests
+//!
+//! Genome commands are validated, paid for and recorded so they can be undone and redone.
+
+use metabolistic3d::blocks::genome::{
+    execute_genome_command, BlockKind, GeneState, Genome, GenomeCommand, GenomeCommandError,
+    GenomeHistory, GenomeHistoryCommand, GenomeOperationCosts,
+};
+use metabolistic3d::metabolism::CurrencyPools;
+use metabolistic3d::metabolism::cells::player_genome;
+use metabolistic3d::molecules::Currency;
+use metabolistic3d::MetabolisticApp;
+
+fn starter() -> (Genome, CurrencyPools, GenomeOperationCosts, GenomeHistory) {
+    let mut genome = Genome::default();
+    genome.add_gene(BlockKind::Fermentation);
+    genome.add_gene(BlockKind::SugarCatabolism);
+    (
+        genome,
+        CurrencyPools::with_defaults(),
+        GenomeOperationCosts::default(),
+        GenomeHistory::default(),
+    )
+}
+
+#[test]
+fn test_command_charges_and_undo_refunds() {
+    let (mut genome, mut pools, costs, mut history) = starter();
+
+    let edit = execute_genome_command(
+        &mut genome,
+        &mut pools,
+        &costs,
+        GenomeCommand::Express(BlockKind::Fermentation),
+    )
+    .unwrap();
+    history.record(edit);
+    assert_eq!(genome.get_gene_state(&BlockKind::Fermentation), Some(&GeneState::Expressed));
+    assert_eq!(pools.get(Currency::ATP), 100.0 - costs.expression_atp_cost);
+
+    assert_eq!(
+        history.undo(&mut genome, &mut pools),
+        Ok(Some(GenomeCommand::Express(BlockKind::Fermentation)))
+    );
+    assert_eq!(genome.get_gene_state(&BlockKind::Fermentation), Some(&GeneState::Silent));
+    assert_eq!(pools.get(Currency::ATP), 100.0);
+    assert_eq!(history.undo(&mut genome, &mut pools), Ok(None));
+
+    assert!(history.redo(&mut genome, &mut pools).unwrap().is_some());
+    assert_eq!(genome.get_gene_state(&BlockKind::Fermentation), Some(&GeneState::Expressed));
+    assert_eq!(pools.get(Currency::ATP), 100.0 - costs.expression_atp_cost);
+    assert_eq!(history.undo_stack().len(), 1);
+    assert!(history.redo_stack().is_empty());
+}
+
+#[test]
+fn test_invalid_commands_change_nothing() {
+    let (mut genome, mut pools, costs, _) = starter();
+    genome.mutate_gene(BlockKind::SugarCatabolism);
+    let atp = pools.get(Currency::ATP);
+
+    let cases = [
+        (
+            GenomeCommand::Silence(BlockKind::Fermentation),
+            GenomeCommandError::WrongState {
+                block_kind: BlockKind::Fermentation,
+                state: GeneState::Silent,
+            },
+        ),
+        (
+            GenomeCommand::Express(BlockKind::SugarCatabolism),
+            GenomeCommandError::WrongState {
+                block_kind: BlockKind::SugarCatabolism,
+                state: GeneState::Mutated,
+            },
+        ),
+        (
+            GenomeCommand::Add(BlockKind::Fermentation),
+            GenomeCommandError::GeneAlreadyPresent(BlockKind::Fermentation),
+        ),
+        (
+            GenomeCommand::Remove(BlockKind::Respiration),
+            GenomeCommandError::MissingGene(BlockKind::Respiration),
+        ),
+        (
+            GenomeCommand::Swap(BlockKind::Fermentation, BlockKind::SugarCatabolism),
+            GenomeCommandError::GeneAlreadyPresent(BlockKind::SugarCatabolism),
+        ),
+    ];
+    for (command, expected) in cases {
+        assert_eq!(
+            execute_genome_command(&mut genome, &mut pools, &costs, command),
+            Err(expected)
+        );
+    }
+
+    pools.set(Currency::ReducingPower, 1.0);
+    assert!(matches!(
+        execute_genome_command(
+            &mut genome,
+            &mut pools,
+            &costs,
+            GenomeCommand::Repair(BlockKind::SugarCatabolism)
+        ),
+        Err(GenomeCommandError::InsufficientCurrency {
+            currency: Currency::ReducingPower,
+            ..
+        })
+    ));
+
+    assert_eq!(pools.get(Currency::ATP), atp);
+    assert_eq!(genome.get_gene_state(&BlockKind::SugarCatabolism), Some(&GeneState::Mutated));
+    assert_eq!(genome.table.len(), 2);
+}
+
+#[test]
+fn test_swap_and_remove_are_undone_exactly() {
+    let (mut genome, mut pools, costs, mut history) = starter();
+    genome.express_gene(BlockKind::Fermentation);
+    genome.mutate_gene(BlockKind::SugarCatabolism);
+
+    for command in [
+        GenomeCommand::Swap(BlockKind::Fermentation, BlockKind::Respiration),
+        GenomeCommand::Remove(BlockKind::SugarCatabolism),
+    ] {
+        let edit = execute_genome_command(&mut genome, &mut pools, &costs, command).unwrap();
+        history.record(edit);
+    }
+    assert_eq!(genome.get_gene_state(&BlockKind::Respiration), Some(&GeneState::Expressed));
+    assert_eq!(genome.get_gene_state(&BlockKind::Fermentation), None);
+    assert_eq!(genome.get_gene_state(&BlockKind::SugarCatabolism), None);
+
+    history.undo(&mut genome, &mut pools).unwrap();
+    history.undo(&mut genome, &mut pools).unwrap();
+    assert_eq!(genome.get_gene_state(&BlockKind::Fermentation), Some(&GeneState::Expressed));
+    assert_eq!(genome.get_gene_state(&BlockKind::SugarCatabolism), Some(&GeneState::Mutated));
+    assert_eq!(genome.get_gene_state(&BlockKind::Respiration), None);
+    assert_eq!(pools.get(Currency::ATP), 100.0);
+    assert_eq!(pools.get(Currency::ReducingPower), 50.0);
+}
+
+#[test]
+fn test_new_command_clears_redo_and_conflicts_are_dropped() {
+    let (mut genome, mut pools, costs, mut history) = starter();
+    let express = GenomeCommand::Express(BlockKind::Fermentation);
+
+    history.record(execute_genome_command(&mut genome, &mut pools, &costs, express).unwrap());
+    history.undo(&mut genome, &mut pools).unwrap();
+    let add = GenomeCommand::Add(BlockKind::Respiration);
+    history.record(execute_genome_command(&mut genome, &mut pools, &costs, add).unwrap());
+    assert!(history.redo_stack().is_empty());
+
+    // A mutation after the edit means undoing it would clobber the mutation
+    history.record(execute_genome_command(&mut genome, &mut pools, &costs, express).unwrap());
+    genome.mutate_gene(BlockKind::Fermentation);
+    assert_eq!(
+        history.undo(&mut genome, &mut pools),
+        Err(GenomeCommandError::Conflict(BlockKind::Fermentation))
+    );
+    assert_eq!(genome.get_gene_state(&BlockKind::Fermentation), Some(&GeneState::Mutated));
+
+    // The conflicting edit is gone; the one before it can still be undone
+    assert_eq!(history.undo(&mut genome, &mut pools), Ok(Some(add)));
+}
+
+#[test]
+fn test_commands_and_undo_events_in_app() {
+    let mut app = MetabolisticApp::new_headless();
+    app.update();
+
+    app.world_mut().send_event(GenomeCommand::Express(BlockKind::Fermentation));
+    app.world_mut().send_event(GenomeCommand::Add(BlockKind::Respiration));
+    app.update();
+    assert_eq!(
+        player_genome(app.world()).get_gene_state(&BlockKind::Fermentation),
+        Some(&GeneState::Expressed)
+    );
+    assert_eq!(app.world().resource::<GenomeHistory>().undo_stack().len(), 2);
+
+    app.world_mut().send_event(GenomeHistoryCommand::Undo);
+    app.world_mut().send_event(GenomeHistoryCommand::Undo);
+    app.update();
+    let genome = player_genome(app.world());
+    assert_eq!(genome.get_gene_state(&BlockKind::Fermentation), Some(&GeneState::Silent));
+    assert_eq!(genome.get_gene_state(&BlockKind::Respiration), None);
+    assert_eq!(app.world().resource::<GenomeHistory>().redo_stack().len(), 2);
+}
+
+#[test]
+fn test_genome_command_json_ids() {
+    let swap = GenomeCommand::Swap(BlockKind::Fermentation, BlockKind::Respiration);
+    let json = serde_json::to_string(&swap).unwrap();
+    assert_eq!(json, r#"{"op":"swap","gene":["fermentation","respiration"]}"#);
+    assert_eq!(serde_json::from_str::<GenomeCommand>(&json).unwrap(), swap);
+}
@@ -0,0 +1,94 @@
+use metabolistic3d::blocks::genome::{
+    create_starter_genome, BlockKind, GeneState, Genome, GenomeLoadError, GenomeSaveData,
+    GENOME_SAVE_VERSION,
+};
+
+#[test]
+fn test_genome_round_trip_is_versioned() {
+    let mut genome = create_starter_genome();
+    genome.express_gene(BlockKind::Fermentation);
+
+    let json = genome.to_json().unwrap();
+    let value: serde_json::Value = serde_json::from_str(&json).unwrap();
+    assert_eq!(value["version"], GENOME_SAVE_VERSION);
+
+    let loaded = Genome::from_json(&json).unwrap();
+    assert_eq!(loaded.table, genome.table);
+}
+
+#[test]
+fn test_genome_save_uses_stable_ids_in_sorted_order() {
+    let mut genome = Genome::default();
+    genome.add_gene(BlockKind::Polymerization);
+    genome.add_gene(BlockKind::LightCapture);
+
+    let data = GenomeSaveData::from(&genome);
+    let kinds: Vec<BlockKind> = data.genes.iter().map(|g| g.kind).collect();
+    assert_eq!(kinds, vec![BlockKind::LightCapture, BlockKind::Polymerization]);
+
+    let json = data.to_json().unwrap();
+    assert!(json.contains("\"light_capture\""), "unexpected json: {}", json);
+    assert!(json.contains("\"silent\""), "unexpected json: {}", json);
+}
+
+#[test]
+fn test_unversioned_genome_is_migrated() {
+    // Format written before versioning: bare gene list with Rust variant names
+    let legacy = r#"{
+        "genes": [
+            { "kind": "SugarCatabolism", "state": "Expressed", "description": "Break down sugars into pyruvate" },
+            { "kind": "NucleotideCofactorSynthesis", "state": "Mutated", "description": "" }
+        ]
+    }"#;
+
+    let genome = Genome::from_json(legacy).unwrap();
+    assert_eq!(
+        genome.get_gene_state(&BlockKind::SugarCatabolism),
+        Some(&GeneState::Expressed)
+    );
+    assert_eq!(
+        genome.get_gene_state(&BlockKind::NucleotideCofactorSynthesis),
+        Some(&GeneState::Mutated)
+    );
+}
+
+#[test]
+fn test_description_is_optional() {
+    let json = r#"{ "version": 1, "genes": [ { "kind": "respiration", "state": "silent" } ] }"#;
+    let genome = Genome::from_json(json).unwrap();
+    assert_eq!(genome.get_gene_state(&BlockKind::Respiration), Some(&GeneState::Silent));
+}
+
+#[test]
+fn test_load_errors_are_distinguished() {
+    assert!(matches!(
+        Genome::from_json("{ not json"),
+        Err(GenomeLoadError::MalformedJson(_))
+    ));
+
+    let unknown = r#"{ "version": 1, "genes": [ { "kind": "photon_torpedo", "state": "silent" } ] }"#;
+    match Genome::from_json(unknown) {
+        Err(GenomeLoadError::UnknownBlockKind(kind)) => assert_eq!(kind, "photon_torpedo"),
+        other => panic!("expected UnknownBlockKind, got {:?}", other.map(|g| g.table)),
+    }
+
+    let future = format!(r#"{{ "version": {}, "genes": [] }}"#, GENOME_SAVE_VERSION + 1);
+    assert!(matches!(
+        Genome::from_json(&future),
+        Err(GenomeLoadError::UnsupportedVersion(v)) if v == GENOME_SAVE_VERSION + 1
+    ));
+}
+
+#[test]
+fn test_genomes_saved_before_motility_gain_a_flagellum() {
+    let v1 = r#"{ "version": 1, "genes": [ { "kind": "fermentation", "state": "expressed" } ] }"#;
+    let genome = Genome::from_json(v1).unwrap();
+    assert_eq!(genome.get_gene_state(&BlockKind::Motility), Some(&GeneState::Expressed));
+    assert_eq!(genome.get_gene_state(&BlockKind::Fermentation), Some(&GeneState::Expressed));
+
+    // A flagellum already in the save is left as it was
+    let silenced = r#"{ "version": 1, "genes": [ { "kind": "motility", "state": "silent" } ] }"#;
+    let genome = Genome::from_json(silenced).unwrap();
+    assert_eq!(genome.get_gene_state(&BlockKind::Motility), Some(&GeneState::Silent));
+    assert_eq!(genome.table.len(), 1);
+}
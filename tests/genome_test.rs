@@ -1,10 +1,11 @@
 use bevy::prelude::*;
 use metabolistic3d::blocks::genome::{BlockKind, Genome, GenomeDiffEvent, MetabolicUpdateEvent, GeneState, GenomeOperationCosts, poll_genome_diff, apply_genome_diff};
+use metabolistic3d::metabolism::cells::{player_genome_mut, PlayerCell};
 
 fn setup_app() -> App {
     let mut app = App::new();
     app.add_plugins(MinimalPlugins);
-    app.insert_resource(Genome::default());
+    app.world_mut().spawn((PlayerCell, Genome::default()));
     app.insert_resource(GenomeOperationCosts::default());
     app.add_event::<GenomeDiffEvent>();
     app.add_event::<MetabolicUpdateEvent>();
@@ -16,7 +17,7 @@ fn setup_app() -> App {
 #[test]
 fn test_genome_diff_events_expression_and_silencing() {
     let mut app = setup_app();
-    let mut genome = app.world_mut().resource_mut::<Genome>();
+    let mut genome = player_genome_mut(app.world_mut());
 
     // Add a gene
     genome.add_gene(BlockKind::SugarCatabolism);
@@ -26,12 +27,12 @@ fn test_genome_diff_events_expression_and_silencing() {
     app.update();
 
     // Express a gene
-    let mut genome = app.world_mut().resource_mut::<Genome>();
+    let mut genome = player_genome_mut(app.world_mut());
     assert!(genome.express_gene(BlockKind::SugarCatabolism));
     assert_eq!(*genome.get_gene_state(&BlockKind::SugarCatabolism).unwrap(), GeneState::Expressed);
 
     // Express another gene
-    let mut genome = app.world_mut().resource_mut::<Genome>();
+    let mut genome = player_genome_mut(app.world_mut());
     assert!(genome.express_gene(BlockKind::LightCapture));
     assert_eq!(*genome.get_gene_state(&BlockKind::LightCapture).unwrap(), GeneState::Expressed);
 
@@ -54,7 +55,7 @@ fn test_genome_diff_events_expression_and_silencing() {
     app.world_mut().resource_mut::<Events<GenomeDiffEvent>>().clear();
 
     // Silence a gene
-    let mut genome = app.world_mut().resource_mut::<Genome>();
+    let mut genome = player_genome_mut(app.world_mut());
     assert!(genome.silence_gene(BlockKind::SugarCatabolism));
     assert_eq!(*genome.get_gene_state(&BlockKind::SugarCatabolism).unwrap(), GeneState::Silent);
 
@@ -76,7 +77,7 @@ fn test_genome_diff_events_expression_and_silencing() {
 #[test]
 fn test_genome_diff_events_mutation_and_repair() {
     let mut app = setup_app();
-    let mut genome = app.world_mut().resource_mut::<Genome>();
+    let mut genome = player_genome_mut(app.world_mut());
 
     // Add and express a gene
     genome.add_gene(BlockKind::Fermentation);
@@ -89,7 +90,7 @@ fn test_genome_diff_events_mutation_and_repair() {
     app.world_mut().resource_mut::<Events<GenomeDiffEvent>>().clear();
 
     // Mutate the expressed gene
-    let mut genome = app.world_mut().resource_mut::<Genome>();
+    let mut genome = player_genome_mut(app.world_mut());
     assert!(genome.mutate_gene(BlockKind::Fermentation));
     assert_eq!(*genome.get_gene_state(&BlockKind::Fermentation).unwrap(), GeneState::Mutated);
 
@@ -112,7 +113,7 @@ fn test_genome_diff_events_mutation_and_repair() {
     app.world_mut().resource_mut::<Events<GenomeDiffEvent>>().clear();
 
     // Repair the mutated gene (it should go back to Silent)
-    let mut genome = app.world_mut().resource_mut::<Genome>();
+    let mut genome = player_genome_mut(app.world_mut());
     assert!(genome.repair_gene(BlockKind::Fermentation));
     assert_eq!(*genome.get_gene_state(&BlockKind::Fermentation).unwrap(), GeneState::Silent);
 
@@ -129,7 +130,7 @@ fn test_genome_diff_events_mutation_and_repair() {
     app.world_mut().resource_mut::<Events<GenomeDiffEvent>>().clear();
 
     // Now express the repaired gene
-    let mut genome = app.world_mut().resource_mut::<Genome>();
+    let mut genome = player_genome_mut(app.world_mut());
     assert!(genome.express_gene(BlockKind::Fermentation));
     assert_eq!(*genome.get_gene_state(&BlockKind::Fermentation).unwrap(), GeneState::Expressed);
 
@@ -150,7 +151,7 @@ fn test_genome_diff_events_mutation_and_repair() {
 #[test]
 fn test_genome_diff_mutated_to_silent_no_event() {
     let mut app = setup_app();
-    let mut genome = app.world_mut().resource_mut::<Genome>();
+    let mut genome = player_genome_mut(app.world_mut());
 
     // Add and express a gene
     genome.add_gene(BlockKind::Fermentation);
@@ -163,7 +164,7 @@ fn test_genome_diff_mutated_to_silent_no_event() {
     app.world_mut().resource_mut::<Events<GenomeDiffEvent>>().clear();
 
     // Mutate the expressed gene
-    let mut genome = app.world_mut().resource_mut::<Genome>();
+    let mut genome = player_genome_mut(app.world_mut());
     assert!(genome.mutate_gene(BlockKind::Fermentation));
     assert_eq!(*genome.get_gene_state(&BlockKind::Fermentation).unwrap(), GeneState::Mutated);
 
@@ -174,7 +175,7 @@ fn test_genome_diff_mutated_to_silent_no_event() {
     app.world_mut().resource_mut::<Events<GenomeDiffEvent>>().clear();
 
     // Repair the mutated gene (it should go back to Silent)
-    let mut genome = app.world_mut().resource_mut::<Genome>();
+    let mut genome = player_genome_mut(app.world_mut());
     assert!(genome.repair_gene(BlockKind::Fermentation));
     assert_eq!(*genome.get_gene_state(&BlockKind::Fermentation).unwrap(), GeneState::Silent);
 
@@ -0,0 +1,119 @@
+//! # Input Binding Tests
+//!
+//! Every player-facing action can be played on a gamepad, bindings survive a trip through
+//! the settings file, and scene switching and genome commands follow actions, not keys.
+
+use bevy::prelude::*;
+use bevy::state::app::StatesPlugin;
+use leafwing_input_manager::clashing_inputs::BasicInputs;
+use leafwing_input_manager::prelude::*;
+use metabolistic3d::blocks::genome::GenomeHistoryCommand;
+use metabolistic3d::input::{GameAction, InputBindings};
+use metabolistic3d::player::controller::Action;
+use metabolistic3d::{shared, GameState};
+
+/// Whether pressing `input` only takes gamepad buttons.
+fn on_gamepad(input: &dyn Buttonlike) -> bool {
+    match input.decompose() {
+        BasicInputs::Simple(button) => button.as_ref().as_reflect().is::<GamepadButton>(),
+        BasicInputs::Composite(buttons) | BasicInputs::Chord(buttons) => {
+            buttons.iter().all(|button| button.as_ref().as_reflect().is::<GamepadButton>())
+        }
+        BasicInputs::None => false,
+    }
+}
+
+/// Leafwing deserializes inputs through a registry its plugin fills in.
+fn register_inputs() {
+    App::new().add_plugins(InputManagerPlugin::<GameAction>::server());
+}
+
+fn app_with_actions() -> App {
+    let mut app = App::new();
+    app.add_plugins((MinimalPlugins, StatesPlugin))
+        .init_state::<GameState>()
+        .add_event::<GenomeHistoryCommand>()
+        .init_resource::<ActionState<GameAction>>()
+        .add_systems(Update, (shared::state_transition_input, shared::genome_history_input));
+    app.update();
+    app
+}
+
+#[test]
+fn test_every_action_has_a_gamepad_default() {
+    let bindings = InputBindings::default();
+    for action in GameAction::ALL {
+        let inputs = bindings.game.get_buttonlike(&action).unwrap();
+        assert!(inputs.iter().any(|input| on_gamepad(input.as_ref())), "{:?}", action);
+        assert!(inputs.iter().any(|input| !on_gamepad(input.as_ref())), "{:?}", action);
+    }
+
+    let move_2d = bindings.game.get_dual_axislike(&GameAction::Move2D).unwrap();
+    assert!(move_2d.iter().any(|input| input.as_ref().as_reflect().is::<GamepadStick>()));
+
+    let jump = bindings.player.get_buttonlike(&Action::Jump).unwrap();
+    assert!(jump.iter().any(|input| on_gamepad(input.as_ref())));
+    for action in [Action::Move, Action::Pan] {
+        let sticks = bindings.player.get_dual_axislike(&action).unwrap();
+        assert!(sticks.iter().any(|input| input.as_ref().as_reflect().is::<GamepadStick>()), "{:?}", action);
+    }
+}
+
+#[test]
+fn test_rebinding_survives_the_settings_file() {
+    register_inputs();
+    let mut bindings = InputBindings::default();
+    bindings.game.clear_action(&GameAction::MainMenu);
+    bindings.game.insert(GameAction::MainMenu, KeyCode::KeyQ);
+    bindings.game.insert(GameAction::MainMenu, GamepadButton::Mode);
+
+    let path = std::env::temp_dir().join(format!(
+        "metabolistic_input_test_{}/input.json",
+        std::process::id()
+    ));
+    bindings.save_to_file(&path).unwrap();
+    let loaded = InputBindings::load_from_file(&path).unwrap();
+    std::fs::remove_dir_all(path.parent().unwrap()).ok();
+    assert_eq!(loaded, bindings);
+}
+
+#[test]
+fn test_actions_missing_from_the_file_keep_their_defaults() {
+    register_inputs();
+    let mut partial = InputBindings {
+        player: InputMap::default(),
+        game: InputMap::default(),
+    };
+    partial.game.insert(GameAction::Scene2D, KeyCode::F5);
+    let loaded = InputBindings::from_json(&partial.to_json().unwrap()).unwrap();
+
+    let defaults = InputBindings::default();
+    assert_eq!(loaded.player, defaults.player);
+    assert_eq!(loaded.game.get_buttonlike(&GameAction::Scene2D).unwrap().len(), 1);
+    assert_eq!(
+        loaded.game.get_buttonlike(&GameAction::Scene3D),
+        defaults.game.get_buttonlike(&GameAction::Scene3D)
+    );
+}
+
+#[test]
+fn test_scene_and_history_actions_drive_the_game() {
+    let mut app = app_with_actions();
+    app.world_mut()
+        .resource_mut::<ActionState<GameAction>>()
+        .press(&GameAction::GenomeEditor);
+    app.update();
+    app.update();
+    assert_eq!(
+        *app.world().resource::<State<GameState>>().get(),
+        GameState::GenomeEditing
+    );
+
+    let mut actions = app.world_mut().resource_mut::<ActionState<GameAction>>();
+    actions.release(&GameAction::GenomeEditor);
+    actions.press(&GameAction::Undo);
+    app.update();
+    let events = app.world().resource::<Events<GenomeHistoryCommand>>();
+    let sent: Vec<_> = events.get_cursor().read(events).cloned().collect();
+    assert_eq!(sent, vec![GenomeHistoryCommand::Undo]);
+}
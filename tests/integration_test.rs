@@ -1,6 +1,7 @@
 use metabolistic3d::MetabolisticApp;
 use metabolistic3d::metabolism::*;
-use metabolistic3d::blocks::genome::{BlockKind, Genome, GeneState};
+use metabolistic3d::blocks::genome::{BlockKind, GeneState};
+use metabolistic3d::metabolism::cells::{player_genome, player_genome_mut};
 use metabolistic3d::molecules::Currency;
 use std::collections::HashMap;
 
@@ -25,6 +26,7 @@ fn test_app_multiple_updates() {
     // Run several update cycles to simulate normal operation
     for _ in 0..5 {
         app.update();
+        run_ticks(app.world_mut(), 1);
     }
 
     // App should still be in valid state
@@ -49,15 +51,16 @@ fn test_headless_startup() {
 fn test_genome_add_blocks_and_graph_update() {
     let mut app = MetabolisticApp::new_headless();
     
-    // Initialize and run startup
+    // Initialize and run startup, then one tick so the graph reflects startup blocks
     app.update();
+    run_ticks(app.world_mut(), 1);
     
     // Get initial metabolic graph state
     let initial_node_count = app.world().resource::<MetabolicGraph>().nodes.len();
     
     // Add genes to the genome
     {
-        let mut genome = app.world_mut().resource_mut::<Genome>();
+        let mut genome = player_genome_mut(app.world_mut());
         genome.add_gene(BlockKind::SugarCatabolism);
         genome.add_gene(BlockKind::Fermentation);
         genome.add_gene(BlockKind::LightCapture);
@@ -90,6 +93,7 @@ fn test_genome_add_blocks_and_graph_update() {
     // Run several update cycles to process all systems
     for _ in 0..3 {
         app.update();
+        run_ticks(app.world_mut(), 1);
     }
     
     // Verify the metabolic graph has been updated with new nodes
@@ -97,7 +101,7 @@ fn test_genome_add_blocks_and_graph_update() {
     assert_eq!(final_node_count, initial_node_count + 3, "Expected 3 new nodes in metabolic graph");
     
     // Verify genome contains the added genes
-    let genome = app.world().resource::<Genome>();
+    let genome = player_genome(app.world());
     assert_eq!(genome.get_gene_state(&BlockKind::SugarCatabolism), Some(&GeneState::Silent));
     assert_eq!(genome.get_gene_state(&BlockKind::Fermentation), Some(&GeneState::Silent));
     assert_eq!(genome.get_gene_state(&BlockKind::LightCapture), Some(&GeneState::Silent));
@@ -110,7 +114,7 @@ fn test_genome_gene_expression_metabolic_activation() {
     
     // Add and spawn a metabolic block
     let block_entity = {
-        let mut genome = app.world_mut().resource_mut::<Genome>();
+        let mut genome = player_genome_mut(app.world_mut());
         genome.add_gene(BlockKind::SugarCatabolism);
         
         app.world_mut().spawn((
@@ -130,13 +134,14 @@ fn test_genome_gene_expression_metabolic_activation() {
     
     // Express the gene
     {
-        let mut genome = app.world_mut().resource_mut::<Genome>();
+        let mut genome = player_genome_mut(app.world_mut());
         assert!(genome.express_gene(BlockKind::SugarCatabolism));
     }
     
     // Run update cycles to process genome diff events
     for _ in 0..3 {
         app.update();
+        run_ticks(app.world_mut(), 1);
     }
     
     // Verify the metabolic block is now active
@@ -146,7 +151,7 @@ fn test_genome_gene_expression_metabolic_activation() {
     }
     
     // Verify genome state
-    let genome = app.world().resource::<Genome>();
+    let genome = player_genome(app.world());
     assert_eq!(genome.get_gene_state(&BlockKind::SugarCatabolism), Some(&GeneState::Expressed));
 }
 
@@ -157,7 +162,7 @@ fn test_genome_gene_mutation_and_repair() {
     
     // Set up a metabolic block with expressed gene
     let block_entity = {
-        let mut genome = app.world_mut().resource_mut::<Genome>();
+        let mut genome = player_genome_mut(app.world_mut());
         genome.add_gene(BlockKind::Fermentation);
         genome.express_gene(BlockKind::Fermentation);
         
@@ -172,38 +177,40 @@ fn test_genome_gene_mutation_and_repair() {
     
     // Mutate the gene
     {
-        let mut genome = app.world_mut().resource_mut::<Genome>();
+        let mut genome = player_genome_mut(app.world_mut());
         assert!(genome.mutate_gene(BlockKind::Fermentation));
     }
     
     // Process the mutation
     for _ in 0..3 {
         app.update();
+        run_ticks(app.world_mut(), 1);
     }
     
     // Verify the block is now mutated
     {
         let node = app.world().entity(block_entity).get::<MetabolicNode>().unwrap();
         assert_eq!(node.status, BlockStatus::Mutated);
-        let genome = app.world().resource::<Genome>();
+        let genome = player_genome(app.world());
         assert_eq!(genome.get_gene_state(&BlockKind::Fermentation), Some(&GeneState::Mutated));
     }
     
     // Repair the gene
     {
-        let mut genome = app.world_mut().resource_mut::<Genome>();
+        let mut genome = player_genome_mut(app.world_mut());
         assert!(genome.repair_gene(BlockKind::Fermentation));
     }
     
     // Process the repair
     for _ in 0..3 {
         app.update();
+        run_ticks(app.world_mut(), 1);
     }
     
     // Verify the gene is repaired in the genome (Silent) but node status may remain Mutated
     // since both Silent and Mutated are non-expressed states
     {
-        let genome = app.world().resource::<Genome>();
+        let genome = player_genome(app.world());
         assert_eq!(genome.get_gene_state(&BlockKind::Fermentation), Some(&GeneState::Silent));
         
         // The node status may still be Mutated since no diff event is generated
@@ -221,7 +228,7 @@ fn test_genome_gene_silencing() {
     
     // Set up an active metabolic block
     let block_entity = {
-        let mut genome = app.world_mut().resource_mut::<Genome>();
+        let mut genome = player_genome_mut(app.world_mut());
         genome.add_gene(BlockKind::LightCapture);
         genome.express_gene(BlockKind::LightCapture);
         
@@ -242,20 +249,21 @@ fn test_genome_gene_silencing() {
     
     // Silence the gene
     {
-        let mut genome = app.world_mut().resource_mut::<Genome>();
+        let mut genome = player_genome_mut(app.world_mut());
         assert!(genome.silence_gene(BlockKind::LightCapture));
     }
     
     // Process the silencing
     for _ in 0..3 {
         app.update();
+        run_ticks(app.world_mut(), 1);
     }
     
     // Verify the block is now silent
     {
         let node = app.world().entity(block_entity).get::<MetabolicNode>().unwrap();
         assert_eq!(node.status, BlockStatus::Silent);
-        let genome = app.world().resource::<Genome>();
+        let genome = player_genome(app.world());
         assert_eq!(genome.get_gene_state(&BlockKind::LightCapture), Some(&GeneState::Silent));
     }
 }
@@ -267,7 +275,7 @@ fn test_genome_remove_blocks_and_graph_update() {
     
     // Set up multiple metabolic blocks
     let (block1, block2, block3) = {
-        let mut genome = app.world_mut().resource_mut::<Genome>();
+        let mut genome = player_genome_mut(app.world_mut());
         genome.add_gene(BlockKind::SugarCatabolism);
         genome.add_gene(BlockKind::Fermentation);
         genome.add_gene(BlockKind::Respiration);
@@ -297,6 +305,7 @@ fn test_genome_remove_blocks_and_graph_update() {
     
     for _ in 0..3 {
         app.update();
+        run_ticks(app.world_mut(), 1);
     }
     
     // Verify all blocks are in the graph
@@ -311,6 +320,7 @@ fn test_genome_remove_blocks_and_graph_update() {
     
     for _ in 0..3 {
         app.update();
+        run_ticks(app.world_mut(), 1);
     }
     
     // Verify the graph has been updated
@@ -330,7 +340,7 @@ fn test_flux_calculation_with_different_gene_states() {
     
     // Create blocks with different gene states
     let (active_block, mutated_block, silent_block) = {
-        let mut genome = app.world_mut().resource_mut::<Genome>();
+        let mut genome = player_genome_mut(app.world_mut());
         genome.add_gene(BlockKind::SugarCatabolism);
         genome.add_gene(BlockKind::Fermentation);
         genome.add_gene(BlockKind::LightCapture);
@@ -366,6 +376,7 @@ fn test_flux_calculation_with_different_gene_states() {
     
     for _ in 0..5 {
         app.update();
+        run_ticks(app.world_mut(), 1);
     }
     
     // Verify flux results based on block status
@@ -388,7 +399,7 @@ fn test_complex_genome_manipulation_sequence() {
     
     // Create a complex scenario with multiple blocks
     let blocks = {
-        let mut genome = app.world_mut().resource_mut::<Genome>();
+        let mut genome = player_genome_mut(app.world_mut());
         
         // Add multiple genes
         let block_kinds = vec![
@@ -422,13 +433,14 @@ fn test_complex_genome_manipulation_sequence() {
     
     // Step 1: Express some genes
     {
-        let mut genome = app.world_mut().resource_mut::<Genome>();
+        let mut genome = player_genome_mut(app.world_mut());
         genome.express_gene(BlockKind::SugarCatabolism);
         genome.express_gene(BlockKind::LightCapture);
     }
     
     for _ in 0..3 {
         app.update();
+        run_ticks(app.world_mut(), 1);
     }
     
     // Verify expressed genes are active
@@ -440,12 +452,13 @@ fn test_complex_genome_manipulation_sequence() {
     
     // Step 2: Mutate an active gene
     {
-        let mut genome = app.world_mut().resource_mut::<Genome>();
+        let mut genome = player_genome_mut(app.world_mut());
         genome.mutate_gene(BlockKind::SugarCatabolism);
     }
     
     for _ in 0..3 {
         app.update();
+        run_ticks(app.world_mut(), 1);
     }
     
     // Verify mutation affects flux
@@ -454,12 +467,13 @@ fn test_complex_genome_manipulation_sequence() {
     
     // Step 3: Express another gene
     {
-        let mut genome = app.world_mut().resource_mut::<Genome>();
+        let mut genome = player_genome_mut(app.world_mut());
         genome.express_gene(BlockKind::Fermentation);
     }
     
     for _ in 0..3 {
         app.update();
+        run_ticks(app.world_mut(), 1);
     }
     
     // Verify new gene is active
@@ -468,12 +482,13 @@ fn test_complex_genome_manipulation_sequence() {
     
     // Step 4: Silence an active gene
     {
-        let mut genome = app.world_mut().resource_mut::<Genome>();
+        let mut genome = player_genome_mut(app.world_mut());
         genome.silence_gene(BlockKind::LightCapture);
     }
     
     for _ in 0..3 {
         app.update();
+        run_ticks(app.world_mut(), 1);
     }
     
     // Verify silenced gene has no flux
@@ -481,7 +496,7 @@ fn test_complex_genome_manipulation_sequence() {
     assert_eq!(flux_result.entity_flux.get(&blocks[2]), Some(&0.0)); // LightCapture now silent
     
     // Final verification: Check genome state
-    let genome = app.world().resource::<Genome>();
+    let genome = player_genome(app.world());
     assert_eq!(genome.get_gene_state(&BlockKind::SugarCatabolism), Some(&GeneState::Mutated));
     assert_eq!(genome.get_gene_state(&BlockKind::Fermentation), Some(&GeneState::Expressed));
     assert_eq!(genome.get_gene_state(&BlockKind::LightCapture), Some(&GeneState::Silent));
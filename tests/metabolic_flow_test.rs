@@ -1,6 +1,8 @@
 use bevy::prelude::*;
 use bevy::ecs::system::SystemState;
 use metabolistic3d::metabolism::*;
+use metabolistic3d::metabolism::cells::player_genome_mut;
+use metabolistic3d::metabolism::compartments::{CompartmentPools, InCompartment};
 use metabolistic3d::blocks::genome::{BlockKind, Genome, GenomeDiffEvent, MetabolicUpdateEvent};
 use metabolistic3d::molecules::Currency;
 
@@ -77,14 +79,14 @@ fn test_on_genome_diff_system() {
     // Initialize Genome resource and express the gene
     app.world_mut().insert_resource(Genome::default());
     let node_entity = app.world_mut().spawn((MetabolicNode { kind: BlockKind::Fermentation, status: BlockStatus::Silent }, MetabolicBlock, FluxProfile::default())).id();
-    app.world_mut().resource_mut::<Genome>().add_gene(BlockKind::Fermentation);
-    app.world_mut().resource_mut::<Genome>().express_gene(BlockKind::Fermentation);
+    player_genome_mut(app.world_mut()).add_gene(BlockKind::Fermentation);
+    player_genome_mut(app.world_mut()).express_gene(BlockKind::Fermentation);
 
     // Run the app to allow GenomePlugin to update and emit events
     app.update();
 
-    // Run the MetabolicSchedule directly to process the event
-    app.world_mut().run_schedule(MetabolicSchedule);
+    // Run a metabolic tick of the player's cell to process the event
+    run_ticks(app.world_mut(), 1);
 
     // Verify node status is updated
     let node = app.world().entity(node_entity).get::<MetabolicNode>().unwrap();
@@ -95,13 +97,13 @@ fn test_on_genome_diff_system() {
     assert!(flow_dirty.0);
 
     // Silence the gene
-    app.world_mut().resource_mut::<Genome>().silence_gene(BlockKind::Fermentation);
+    player_genome_mut(app.world_mut()).silence_gene(BlockKind::Fermentation);
 
     // Run the app again to allow GenomePlugin to update and emit events
     app.update();
 
-    // Run the MetabolicSchedule directly to process the silencing event
-    app.world_mut().run_schedule(MetabolicSchedule);
+    // Run a metabolic tick of the player's cell to process the silencing event
+    run_ticks(app.world_mut(), 1);
 
     // Verify node status is updated again
     let node = app.world().entity(node_entity).get::<MetabolicNode>().unwrap();
@@ -133,9 +135,9 @@ fn test_solve_flux_system() {
     // Run the solve_flux_system directly
     {
         let mut world = app.world_mut();
-        let mut system_state: SystemState<(Res<MetabolicGraph>, ResMut<FluxResult>, Res<CurrencyPools>, Query<(&MetabolicNode, &FluxProfile)>)> = SystemState::new(&mut world);
-        let (metabolic_graph, flux_result, currency_pools, query_blocks) = system_state.get_mut(&mut world);
-        solve_flux_system(metabolic_graph, flux_result, currency_pools, query_blocks);
+        let mut system_state: SystemState<(Res<MetabolicGraph>, ResMut<FluxResult>, Res<CurrencyPools>, Query<(&MetabolicNode, &FluxProfile)>, Query<&InCompartment>, Query<&CompartmentPools>)> = SystemState::new(&mut world);
+        let (metabolic_graph, flux_result, currency_pools, query_blocks, query_compartments, query_organelles) = system_state.get_mut(&mut world);
+        solve_flux_system(metabolic_graph, flux_result, currency_pools, query_blocks, query_compartments, query_organelles);
         system_state.apply(&mut world);
     }
 
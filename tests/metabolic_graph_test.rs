@@ -0,0 +1,183 @@
+//! # Metabolic Graph Tests
+//!
+//! The dense graph links consumers to producers through a CSR adjacency and patches
+//! only what changed when a profile is updated or nodes come and go.
+
+use std::collections::HashMap;
+
+use bevy::prelude::*;
+use metabolistic3d::blocks::fermentation::FermentationBlock;
+use metabolistic3d::blocks::genome::BlockKind;
+use metabolistic3d::metabolism::cells::player_pools_mut;
+use metabolistic3d::metabolism::{
+    run_ticks, BlockStatus, CsrAdjacency, CurrencyVector, FlowEdge, FluxProfile,
+    MetabolicGraph, MetabolicNode,
+};
+use metabolistic3d::molecules::Currency;
+use metabolistic3d::MetabolisticApp;
+
+fn flux(entries: &[(Currency, f32)]) -> CurrencyVector {
+    let mut vector = CurrencyVector::default();
+    for &(currency, amount) in entries {
+        vector.set(currency, amount);
+    }
+    vector
+}
+
+/// Glycolysis and light capture make pyruvate, fermentation burns it into ATP, and a
+/// consumer of ATP depends on fermentation.
+fn nodes() -> Vec<(Entity, CurrencyVector)> {
+    vec![
+        (Entity::from_raw(10), flux(&[(Currency::Pyruvate, 2.0)])),
+        (Entity::from_raw(11), flux(&[(Currency::Pyruvate, 1.0)])),
+        (
+            Entity::from_raw(12),
+            flux(&[(Currency::Pyruvate, -1.0), (Currency::ATP, 1.0)]),
+        ),
+        (Entity::from_raw(13), flux(&[(Currency::ATP, -2.0)])),
+    ]
+}
+
+#[test]
+fn test_currency_vector_and_csr() {
+    let vector = flux(&[(Currency::ATP, 1.0), (Currency::Pyruvate, -1.0)]);
+    assert_eq!(vector.get(Currency::ATP), 1.0);
+    assert_eq!(vector.produced(), 1 << Currency::ATP.index());
+    assert_eq!(vector.consumed(), 1 << Currency::Pyruvate.index());
+    assert_eq!(
+        vector.iter().collect::<Vec<_>>(),
+        vec![(Currency::ATP, 1.0), (Currency::Pyruvate, -1.0)]
+    );
+
+    let mut csr = CsrAdjacency::from_rows([vec![], vec![0], vec![0, 1]]);
+    assert_eq!(csr.len(), 3);
+    assert_eq!(csr.row(2), &[0, 1]);
+    assert_eq!(csr.row(7), &[] as &[u32]);
+    csr.replace_rows([(1, vec![2])].into_iter().collect());
+    assert_eq!(csr.row(1), &[2]);
+    assert_eq!(csr.row(2), &[0, 1]);
+    assert_eq!(csr.target_count(), 3);
+}
+
+#[test]
+fn test_build_links_producers_and_wires() {
+    let [glycolysis, lightcapture, fermentation, consumer] =
+        [10, 11, 12, 13].map(Entity::from_raw);
+    let graph = MetabolicGraph::build(nodes(), Vec::new(), []);
+    assert_eq!(
+        graph.dependencies_of(fermentation).collect::<Vec<_>>(),
+        vec![glycolysis, lightcapture]
+    );
+    assert_eq!(graph.dependencies_of(consumer).collect::<Vec<_>>(), vec![fermentation]);
+    assert_eq!(graph.dependencies_of(glycolysis).count(), 0);
+    assert_eq!(graph.links().count(), 3);
+
+    let wire = FlowEdge {
+        from: lightcapture,
+        to: fermentation,
+        currency: Currency::Pyruvate,
+    };
+    let wired = MetabolicGraph::build(nodes(), Vec::new(), [wire]);
+    assert_eq!(
+        wired.dependencies_of(fermentation).collect::<Vec<_>>(),
+        vec![lightcapture]
+    );
+}
+
+#[test]
+fn test_update_profile_matches_full_rebuild() {
+    let [glycolysis, _, fermentation, consumer] = [10, 11, 12, 13].map(Entity::from_raw);
+    let mut graph = MetabolicGraph::build(nodes(), Vec::new(), []);
+
+    // New amounts with the same signs leave the adjacency alone
+    assert!(!graph.update_profile(glycolysis, flux(&[(Currency::Pyruvate, 5.0)])));
+    assert_eq!(graph.profiles[0].get(Currency::Pyruvate), 5.0);
+
+    // Fermentation running dry stops feeding the consumer and waiting on pyruvate
+    assert!(graph.update_profile(fermentation, CurrencyVector::default()));
+    assert_eq!(graph.dependencies_of(consumer).count(), 0);
+    assert_eq!(graph.dependencies_of(fermentation).count(), 0);
+
+    let mut expected = nodes();
+    expected[0].1 = flux(&[(Currency::Pyruvate, 5.0)]);
+    expected[2].1 = CurrencyVector::default();
+    let rebuilt = MetabolicGraph::build(expected, Vec::new(), []);
+    assert_eq!(graph.dependencies, rebuilt.dependencies);
+
+    // And picking back up restores both links
+    assert!(graph.update_profile(fermentation, nodes()[2].1));
+    let mut restored = nodes();
+    restored[0].1 = flux(&[(Currency::Pyruvate, 5.0)]);
+    let rebuilt = MetabolicGraph::build(restored, Vec::new(), []);
+    assert_eq!(graph.dependencies, rebuilt.dependencies);
+}
+
+#[test]
+fn test_add_and_remove_nodes_match_full_rebuild() {
+    let [glycolysis, lightcapture, fermentation, consumer] =
+        [10, 11, 12, 13].map(Entity::from_raw);
+    let wire = FlowEdge {
+        from: lightcapture,
+        to: fermentation,
+        currency: Currency::Pyruvate,
+    };
+    let all = nodes();
+    let mut graph = MetabolicGraph::build(all[..2].to_vec(), Vec::new(), [wire]);
+    // Nothing makes ATP yet, so the consumer has no producers to link to
+    assert!(!graph.add_node(consumer, all[3].1));
+    assert!(graph.add_node(fermentation, all[2].1));
+    let rebuilt = MetabolicGraph::build(all.clone(), Vec::new(), [wire]);
+    assert_eq!(graph.mismatch(&rebuilt), None);
+    assert_eq!(graph.dependencies_of(consumer).collect::<Vec<_>>(), vec![fermentation]);
+
+    // Removing the wired producer reopens the port to every pyruvate producer
+    assert!(graph.remove_node(lightcapture));
+    assert!(!graph.remove_node(lightcapture));
+    assert_eq!(graph.nodes, vec![glycolysis, consumer, fermentation]);
+    assert_eq!(graph.dependencies_of(fermentation).collect::<Vec<_>>(), vec![glycolysis]);
+    let remaining = vec![all[0], all[2], all[3]];
+    assert_eq!(graph.mismatch(&MetabolicGraph::build(remaining, Vec::new(), [wire])), None);
+
+    assert!(graph.mismatch(&rebuilt).is_some());
+}
+
+#[test]
+fn test_graph_follows_profile_changes_and_despawns() {
+    let mut app = MetabolisticApp::new_headless();
+    app.update();
+    run_ticks(app.world_mut(), 1);
+    let fermentation = app
+        .world_mut()
+        .query_filtered::<Entity, With<FermentationBlock>>()
+        .single(app.world());
+    let consumer = app
+        .world_mut()
+        .spawn((
+            MetabolicNode {
+                kind: BlockKind::AminoAcidBiosynthesis,
+                status: BlockStatus::Silent,
+            },
+            FluxProfile(HashMap::from([(Currency::ATP, -1.0)])),
+        ))
+        .id();
+
+    // No `FlowDirty`: the graph picks the node up on its own
+    run_ticks(app.world_mut(), 1);
+    let depends_on_fermentation = |app: &App| {
+        app.world()
+            .resource::<MetabolicGraph>()
+            .dependencies_of(consumer)
+            .any(|producer| producer == fermentation)
+    };
+    assert!(depends_on_fermentation(&app));
+
+    // Without pyruvate fermentation clears its profile and stops producing ATP
+    player_pools_mut(app.world_mut())
+        .set(Currency::Pyruvate, 0.0);
+    run_ticks(app.world_mut(), 1);
+    assert!(!depends_on_fermentation(&app));
+
+    app.world_mut().despawn(consumer);
+    run_ticks(app.world_mut(), 1);
+    assert!(!app.world().resource::<MetabolicGraph>().index.contains_key(&consumer));
+}
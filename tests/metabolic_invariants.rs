@@ -5,12 +5,13 @@
 //! consistency, and cellular viability constraints.
 
 use proptest::prelude::*;
+use metabolistic3d::metabolism::cells::{player_genome, player_genome_mut, player_pools, player_pools_mut};
 use metabolistic3d::molecules::*;
 use metabolistic3d::blocks::genome::{GeneState, Genome};
 use metabolistic3d::blocks::fermentation::FermentationBlock;
 use metabolistic3d::molecules::{PolyMer, CellMass};
 use metabolistic3d::blocks::vesicle_export::VesicleExportBlock;
-use metabolistic3d::metabolism::{MetabolicNode, BlockStatus, FluxProfile, MetabolicBlock, CurrencyPools};
+use metabolistic3d::metabolism::{MetabolicNode, BlockStatus, FluxProfile, MetabolicBlock, run_ticks};
 use metabolistic3d::MetabolisticApp;
 use bevy::prelude::*;
 use bevy::time::{Time, Fixed};
@@ -39,7 +40,7 @@ proptest! {
         let mut app = MetabolisticApp::new_headless();
         
         // Set up economically stressed but viable cell
-        let mut currency_pools = app.world_mut().resource_mut::<CurrencyPools>();
+        let mut currency_pools = player_pools_mut(app.world_mut());
         currency_pools.set(Currency::Pyruvate, pyruvate);
         currency_pools.set(Currency::ReducingPower, rp);
         currency_pools.set(Currency::ATP, atp);
@@ -66,14 +67,14 @@ proptest! {
         
         // Monitor economic health over stress period
         for step in 0..economic_stress_duration {
-            let atp_before = app.world().resource::<CurrencyPools>().get(Currency::ATP);
+            let atp_before = player_pools(app.world()).get(Currency::ATP);
             
             let fixed_time_step = app.world().resource::<Time<Fixed>>().delta();
             app.world_mut().resource_mut::<Time>().advance_by(fixed_time_step);
             app.update();
             app.world_mut().run_schedule(FixedUpdate);
             
-            let atp_after = app.world().resource::<CurrencyPools>().get(Currency::ATP);
+            let atp_after = player_pools(app.world()).get(Currency::ATP);
             let atp_produced_this_step = (atp_after - atp_before).max(0.0);
             total_energy_production += atp_produced_this_step;
             
@@ -119,7 +120,7 @@ proptest! {
         let mut app = MetabolisticApp::new_headless();
         
         // Set up scarcity conditions
-        let mut currency_pools = app.world_mut().resource_mut::<CurrencyPools>();
+        let mut currency_pools = player_pools_mut(app.world_mut());
         match scarcity_scenario {
             "energy_scarce" => {
                 currency_pools.set(Currency::Pyruvate, 10.0);   // Very low
@@ -147,7 +148,7 @@ proptest! {
         
         app.world_mut().insert_resource(LipidToxicityThreshold(150.0));
 {
-    let mut currency_pools = app.world_mut().resource_mut::<CurrencyPools>();
+    let mut currency_pools = player_pools_mut(app.world_mut());
     currency_pools.set(Currency::StorageBeads, 20.0);
 }
         
@@ -205,7 +206,7 @@ proptest! {
         let mut app = MetabolisticApp::new_headless();
         
         // Initialize full system
-        let mut currency_pools = app.world_mut().resource_mut::<CurrencyPools>();
+        let mut currency_pools = player_pools_mut(app.world_mut());
         currency_pools.set(Currency::Pyruvate, 200.0);
         currency_pools.set(Currency::ReducingPower, 200.0);
         currency_pools.set(Currency::ATP, 100.0);
@@ -241,22 +242,23 @@ proptest! {
             // Modify genome state
             match target_state {
                 GeneState::Expressed => {
-                    app.world_mut().resource_mut::<Genome>().express_gene(block_kind);
+                    player_genome_mut(app.world_mut()).express_gene(block_kind);
                 },
                 GeneState::Mutated => {
-                    app.world_mut().resource_mut::<Genome>().mutate_gene(block_kind);
+                    player_genome_mut(app.world_mut()).mutate_gene(block_kind);
                 },
                 GeneState::Silent => {
-                    app.world_mut().resource_mut::<Genome>().silence_gene(block_kind);
+                    player_genome_mut(app.world_mut()).silence_gene(block_kind);
                 },
             }
             
             // Run simulation to propagate changes
             for step in 0..consistency_check_steps {
                 app.update();
+                run_ticks(app.world_mut(), 1);
                 
                 // Verify genome-metabolic consistency
-                let genome = app.world().resource::<Genome>();
+                let genome = player_genome(app.world());
                 let actual_gene_state = genome.get_gene_state(&block_kind);
                 
                 if let Some(entity) = gene_entities.get(&block_kind) {
@@ -304,7 +306,7 @@ proptest! {
         let mut app = MetabolisticApp::new_headless();
         
         // Set up high toxicity scenario
-        let mut currency_pools = app.world_mut().resource_mut::<CurrencyPools>();
+        let mut currency_pools = player_pools_mut(app.world_mut());
         currency_pools.set(Currency::OrganicWaste, initial_waste);
         currency_pools.set(Currency::Pyruvate, 50.0);
         currency_pools.set(Currency::ReducingPower, 50.0);
@@ -322,14 +324,14 @@ proptest! {
         
         // Monitor toxicity response
         for step in 0..toxicity_resolution_time {
-            let waste_before = app.world().resource::<CurrencyPools>().get(Currency::OrganicWaste);
+            let waste_before = player_pools(app.world()).get(Currency::OrganicWaste);
             
             let fixed_time_step = app.world().resource::<Time<Fixed>>().delta();
             app.world_mut().resource_mut::<Time>().advance_by(fixed_time_step);
             app.update();
             app.world_mut().run_schedule(FixedUpdate);
             
-            let waste_after = app.world().resource::<CurrencyPools>().get(Currency::OrganicWaste);
+            let waste_after = player_pools(app.world()).get(Currency::OrganicWaste);
             waste_levels.push(waste_after);
             
             // Check for toxicity response (waste removal)
@@ -377,7 +379,7 @@ proptest! {
         // Set up lipid toxicity scenario
         app.world_mut().insert_resource(LipidToxicityThreshold(threshold));
 {
-    let mut currency_pools = app.world_mut().resource_mut::<CurrencyPools>();
+    let mut currency_pools = player_pools_mut(app.world_mut());
     currency_pools.set(Currency::FreeFattyAcids, initial_ffa);
     currency_pools.set(Currency::StorageBeads, 10.0);
     currency_pools.set(Currency::ATP, 200.0); // Sufficient for polymerization
@@ -402,12 +404,13 @@ proptest! {
         
         // Monitor lipid toxicity protection
         for step in 0..protection_duration {
-            let ffa_before = app.world().resource::<CurrencyPools>().get(Currency::FreeFattyAcids);
+            let ffa_before = player_pools(app.world()).get(Currency::FreeFattyAcids);
             
             app.update();
+            run_ticks(app.world_mut(), 1);
             
-            let ffa_after = app.world().resource::<CurrencyPools>().get(Currency::FreeFattyAcids);
-            let storage_after = app.world().resource::<CurrencyPools>().get(Currency::StorageBeads);
+            let ffa_after = player_pools(app.world()).get(Currency::FreeFattyAcids);
+            let storage_after = player_pools(app.world()).get(Currency::StorageBeads);
             
             ffa_levels.push(ffa_after);
             
@@ -458,7 +461,7 @@ proptest! {
         let mut app = MetabolisticApp::new_headless();
         
         // Set up full cellular system
-        let mut currency_pools = app.world_mut().resource_mut::<CurrencyPools>();
+        let mut currency_pools = player_pools_mut(app.world_mut());
         currency_pools.set(Currency::Pyruvate, pyruvate);
         currency_pools.set(Currency::ReducingPower, rp);
         currency_pools.set(Currency::ATP, atp);
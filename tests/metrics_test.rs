@@ -0,0 +1,126 @@
+//! # Metrics Tests
+//!
+//! The metrics history samples every tick, stays bounded and exports what it recorded.
+
+use bevy::prelude::{Entity, Vec3, With};
+use metabolistic3d::blocks::genome::{BlockKind, GeneState, Genome};
+use metabolistic3d::metabolism::cells::PlayerCell;
+use metabolistic3d::metabolism::run_ticks;
+use metabolistic3d::metrics::{Metric, MetricsHistory, MetricsSample};
+use metabolistic3d::molecules::{CellMass, Currency};
+use metabolistic3d::npc::behaviour::Temperament;
+use metabolistic3d::npc::spawn_npc;
+use metabolistic3d::player::physics::CellPhysics;
+use metabolistic3d::scenario::Scenario;
+use metabolistic3d::MetabolisticApp;
+use std::collections::BTreeMap;
+
+const FERMENTATION_SCENARIO: &str = r#"{
+    "ticks": 5,
+    "currencies": { "pyruvate": 10.0, "reducing_power": 10.0, "organic_waste": 0.0 },
+    "genome": { "version": 1, "genes": [{ "kind": "fermentation", "state": "expressed" }] },
+    "rates": { "fermentation": 2.0, "vesicle_export": 0.0 },
+    "environment": { "cells": [{ "cell_mass": { "base": 1.0, "extra": 0.5 } }] }
+}"#;
+
+fn sample(tick: u64, atp: f32) -> MetricsSample {
+    MetricsSample {
+        tick,
+        currencies: BTreeMap::from([(Currency::ATP, atp)]),
+        flux: BTreeMap::new(),
+        genes: BTreeMap::new(),
+        cell_mass: 1.0,
+    }
+}
+
+#[test]
+fn test_scenario_records_one_sample_per_tick() {
+    let scenario = Scenario::from_json(FERMENTATION_SCENARIO).unwrap();
+    let (series, metrics) = scenario.run_with_metrics().unwrap();
+
+    let ticks: Vec<u64> = metrics.samples().map(|s| s.tick).collect();
+    assert_eq!(ticks, vec![1, 2, 3, 4, 5]);
+
+    // Each sample matches the currency series at the end of the same tick
+    for sample in metrics.samples() {
+        let recorded = &series.samples[sample.tick as usize];
+        assert_eq!(sample.currencies, recorded.currencies);
+    }
+
+    let last = metrics.latest().unwrap();
+    assert_eq!(last.genes[&BlockKind::Fermentation], GeneState::Expressed);
+    // Fatty acids stored in beads add to the cell's mass
+    let beads = last.currencies[&Currency::StorageBeads];
+    assert!((last.cell_mass - (1.5 + beads * CellPhysics::default().mass_per_bead)).abs() < 1e-5);
+    // The fermentation node is solved every tick
+    assert_eq!(metrics.series(Metric::Flux(BlockKind::Fermentation), ..).len(), 5);
+}
+
+#[test]
+fn test_window_queries() {
+    let mut history = MetricsHistory::default();
+    for (tick, atp) in [(1, 10.0), (2, 20.0), (3, 30.0), (4, 60.0)] {
+        history.push(sample(tick, atp));
+    }
+    let atp = Metric::Currency(Currency::ATP);
+
+    let all = history.stats(atp, ..).unwrap();
+    assert_eq!((all.min, all.max, all.mean, all.samples), (10.0, 60.0, 30.0, 4));
+    assert_eq!(history.mean(atp, 2..=3), Some(25.0));
+    assert_eq!(history.max(atp, 3..), Some(60.0));
+    assert_eq!(history.min(atp, 10..), None);
+    assert_eq!(history.stats(Metric::Currency(Currency::Pyruvate), ..), None);
+}
+
+#[test]
+fn test_history_is_bounded() {
+    let mut history = MetricsHistory::with_capacity(3);
+    for tick in 1..=5 {
+        history.push(sample(tick, tick as f32));
+    }
+    assert_eq!(history.len(), 3);
+    assert_eq!(history.samples().next().unwrap().tick, 3);
+    assert_eq!(history.latest().unwrap().tick, 5);
+}
+
+#[test]
+fn test_exports() {
+    let (_, metrics) = Scenario::from_json(FERMENTATION_SCENARIO)
+        .unwrap()
+        .run_with_metrics()
+        .unwrap();
+
+    let csv = metrics.to_csv();
+    let mut lines = csv.lines();
+    let header = lines.next().unwrap();
+    assert!(header.starts_with("tick,atp,"), "unexpected header: {}", header);
+    assert!(header.contains(",gene_fermentation"));
+    assert!(header.ends_with(",cell_mass"));
+    let columns = header.split(',').count();
+    assert!(lines.all(|row| row.split(',').count() == columns));
+
+    let jsonl = metrics.to_json_lines().unwrap();
+    let decoded: Vec<MetricsSample> = jsonl
+        .lines()
+        .map(|line| serde_json::from_str(line).unwrap())
+        .collect();
+    assert_eq!(decoded, metrics.samples().cloned().collect::<Vec<_>>());
+    assert!(jsonl.contains(r#""organic_waste":"#));
+}
+
+#[test]
+fn test_cell_mass_is_the_player_cells_alone() {
+    let mut app = MetabolisticApp::new_headless();
+    app.update();
+    let world = app.world_mut();
+    let player = world.query_filtered::<Entity, With<PlayerCell>>().single(world);
+    world.entity_mut(player).insert(CellMass { base: 1.0, extra: 0.5 });
+    spawn_npc(world, Temperament::Predator, Genome::default(), Vec3::ZERO);
+
+    run_ticks(app.world_mut(), 1);
+
+    let last = app.world().resource::<MetricsHistory>().latest().unwrap();
+    let beads = last.currencies[&Currency::StorageBeads];
+    let expected = 1.5 + beads * CellPhysics::default().mass_per_bead;
+    assert!((last.cell_mass - expected).abs() < 1e-5, "NPC mass leaked into the player's: {}", last.cell_mass);
+}
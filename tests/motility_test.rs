@@ -0,0 +1,55 @@
+//! # Motility Tests
+//!
+//! The flagellum only works while its gene is expressed, weakens as ATP runs low and
+//! charges ATP for every jump.
+
+use metabolistic3d::blocks::genome::{create_starter_genome, BlockKind, Genome};
+use metabolistic3d::blocks::motility::MotilityRules;
+use metabolistic3d::metabolism::CurrencyPools;
+use metabolistic3d::molecules::Currency;
+
+fn pools_with_atp(atp: f32) -> CurrencyPools {
+    let mut pools = CurrencyPools::default();
+    pools.set(Currency::ATP, atp);
+    pools
+}
+
+#[test]
+fn test_power_follows_expression_and_atp() {
+    let rules = MotilityRules::default();
+    let mut genome = Genome::default();
+    let fed = pools_with_atp(rules.full_power_atp * 2.0);
+    assert_eq!(rules.power(&genome, &fed), 0.0);
+
+    genome.add_gene(BlockKind::Motility);
+    assert_eq!(rules.power(&genome, &fed), 0.0);
+    genome.express_gene(BlockKind::Motility);
+    assert_eq!(rules.power(&genome, &fed), 1.0);
+    assert_eq!(rules.power(&genome, &pools_with_atp(rules.full_power_atp / 2.0)), 0.5);
+    assert_eq!(rules.power(&genome, &pools_with_atp(0.0)), 0.0);
+
+    // A mutated flagellum still beats, at half strength
+    genome.mutate_gene(BlockKind::Motility);
+    assert_eq!(rules.power(&genome, &fed), 0.5);
+}
+
+#[test]
+fn test_cells_start_out_motile() {
+    let genome = create_starter_genome();
+    assert_eq!(genome.expression_level(BlockKind::Motility), 1.0);
+}
+
+#[test]
+fn test_jumps_cost_atp() {
+    let rules = MotilityRules::default();
+    let mut pools = pools_with_atp(rules.jump_atp + 1.0);
+    assert_eq!(rules.pay_for_jump(&mut pools, 0.8), 0.8);
+    assert_eq!(pools.get(Currency::ATP), 1.0);
+
+    // Too little ATP, or no flagellum, buys no jump and costs nothing
+    assert_eq!(rules.pay_for_jump(&mut pools, 1.0), 0.0);
+    assert_eq!(pools.get(Currency::ATP), 1.0);
+    let mut pools = pools_with_atp(10.0);
+    assert_eq!(rules.pay_for_jump(&mut pools, 0.0), 0.0);
+    assert_eq!(pools.get(Currency::ATP), 10.0);
+}
@@ -6,9 +6,10 @@
 
 use proptest::prelude::*;
 use approx::assert_relative_eq;
+use metabolistic3d::metabolism::cells::{player_pools, player_pools_mut};
+use metabolistic3d::metabolism::run_ticks;
 use metabolistic3d::molecules::*;
 use metabolistic3d::blocks::fermentation::FermentationBlock;
-use metabolistic3d::metabolism::CurrencyPools;
 use metabolistic3d::molecules::{PolyMer, CellMass};
 use metabolistic3d::blocks::vesicle_export::VesicleExportBlock;
 use metabolistic3d::MetabolisticApp;
@@ -32,7 +33,7 @@ proptest! {
         let mut app = MetabolisticApp::new_headless();
         
         // Initialize fermentation inputs, disable fat storage by removing its resources
-        let mut currency_pools = app.world_mut().resource_mut::<CurrencyPools>();
+        let mut currency_pools = player_pools_mut(app.world_mut());
         currency_pools.set(Currency::Pyruvate, initial_pyruvate);
         currency_pools.set(Currency::ReducingPower, initial_rp);
         currency_pools.set(Currency::ATP, 0.0);
@@ -64,14 +65,14 @@ proptest! {
         
         // Run simulation and observe interactions
         for _ in 0..simulation_steps {
-            let waste_before = app.world().resource::<CurrencyPools>().get(Currency::OrganicWaste);
+            let waste_before = player_pools(app.world()).get(Currency::OrganicWaste);
             
             let fixed_time_step = app.world().resource::<Time<Fixed>>().delta();
             app.world_mut().resource_mut::<Time>().advance_by(fixed_time_step);
             app.update();
             app.world_mut().run_schedule(FixedUpdate);
             
-            let waste_after = app.world().resource::<CurrencyPools>().get(Currency::OrganicWaste);
+            let waste_after = player_pools(app.world()).get(Currency::OrganicWaste);
             
             // Track waste dynamics
             if waste_after > waste_before {
@@ -111,7 +112,7 @@ proptest! {
         let mut app = MetabolisticApp::new_headless();
         
         // Set up conditions where both systems compete for ATP
-        let mut currency_pools = app.world_mut().resource_mut::<CurrencyPools>();
+        let mut currency_pools = player_pools_mut(app.world_mut());
         currency_pools.set(Currency::Pyruvate, initial_pyruvate);
         currency_pools.set(Currency::ReducingPower, initial_rp);
         currency_pools.set(Currency::ATP, initial_atp);
@@ -145,9 +146,10 @@ proptest! {
         // Run simulation with ATP competition
         for step in 0..simulation_steps {
             app.update();
+            run_ticks(app.world_mut(), 1);
             
             // Verify ATP never goes negative despite competition
-            let current_atp = app.world().resource::<CurrencyPools>().get(Currency::ATP);
+            let current_atp = player_pools(app.world()).get(Currency::ATP);
             prop_assert!(current_atp >= 0.0, 
                 "ATP went negative due to system competition at step {}", step);
             
@@ -156,8 +158,8 @@ proptest! {
                 "Currency went negative during ATP competition at step {}", step);
             
             // Verify systems operate consistently despite resource constraints
-            let pyruvate = app.world().resource::<CurrencyPools>().get(Currency::Pyruvate);
-            let ffa = app.world().resource::<CurrencyPools>().get(Currency::FreeFattyAcids);
+            let pyruvate = player_pools(app.world()).get(Currency::Pyruvate);
+            let ffa = player_pools(app.world()).get(Currency::FreeFattyAcids);
             
             prop_assert!(pyruvate <= initial_pyruvate + 0.1);
             prop_assert!(ffa >= 0.0);
@@ -180,7 +182,7 @@ proptest! {
         let mut app = MetabolisticApp::new_headless();
         
         // Create scarcity conditions
-        let mut currency_pools = app.world_mut().resource_mut::<CurrencyPools>();
+        let mut currency_pools = player_pools_mut(app.world_mut());
         currency_pools.set(Currency::ATP, scarce_atp);
         currency_pools.set(Currency::Pyruvate, scarce_pyruvate);
         currency_pools.set(Currency::ReducingPower, scarce_rp);
@@ -241,7 +243,7 @@ proptest! {
             
             // Verify high-priority operations still function
             // (vesicle export should continue removing waste)
-            let waste = app.world().resource::<CurrencyPools>().get(Currency::OrganicWaste);
+            let waste = player_pools(app.world()).get(Currency::OrganicWaste);
             prop_assert!(waste >= 0.0);
             
             // Verify no system causes overflow or underflow
@@ -269,7 +271,7 @@ proptest! {
         let mut app = MetabolisticApp::new_headless();
         
         // Set up cascade: fermentation produces waste, export removes it
-        let mut currency_pools = app.world_mut().resource_mut::<CurrencyPools>();
+        let mut currency_pools = player_pools_mut(app.world_mut());
         currency_pools.set(Currency::Pyruvate, pyruvate);
         currency_pools.set(Currency::ReducingPower, rp);
         currency_pools.set(Currency::ATP, 0.0);
@@ -313,7 +315,7 @@ proptest! {
             app.update();
             app.world_mut().run_schedule(FixedUpdate);
             
-            let current_waste = app.world().resource::<CurrencyPools>().get(Currency::OrganicWaste);
+            let current_waste = player_pools(app.world()).get(Currency::OrganicWaste);
             waste_levels.push(current_waste);
             
             // Verify cascade doesn't break down
@@ -360,7 +362,7 @@ proptest! {
         let mut app = MetabolisticApp::new_headless();
         
         // Initialize full system state
-        let mut currency_pools = app.world_mut().resource_mut::<CurrencyPools>();
+        let mut currency_pools = player_pools_mut(app.world_mut());
         currency_pools.set(Currency::Pyruvate, pyruvate);
         currency_pools.set(Currency::ReducingPower, rp);
         currency_pools.set(Currency::ATP, atp);
@@ -431,7 +433,7 @@ proptest! {
                 initial_total, current_total);
             
             // Verify individual system constraints
-            let currency_pools = app.world().resource::<CurrencyPools>();
+            let currency_pools = player_pools(app.world());
             let current_ffa = currency_pools.get(Currency::FreeFattyAcids);
             let current_storage = currency_pools.get(Currency::StorageBeads);
             let current_waste = currency_pools.get(Currency::OrganicWaste);
@@ -465,7 +467,7 @@ proptest! {
         let mut app = MetabolisticApp::new_headless();
         
         // Set up depletion scenario
-        let mut currency_pools = app.world_mut().resource_mut::<CurrencyPools>();
+        let mut currency_pools = player_pools_mut(app.world_mut());
         match depletion_scenario {
             "no_fermentation_inputs" => {
                 currency_pools.set(Currency::Pyruvate, 0.0);
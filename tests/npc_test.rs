@@ -0,0 +1,247 @@
+//! # NPC Tests
+//!
+//! NPC cells flee, hunt, seek nutrients or wander depending on who they see, and
+//! predators engulf a share of the lighter cells they touch.
+
+use bevy::prelude::*;
+use avian3d::prelude::ExternalTorque;
+use metabolistic3d::blocks::genome::{create_starter_genome, BlockKind, Genome};
+use metabolistic3d::environment::{Nutrient, NutrientGrid};
+use metabolistic3d::metabolism::cells::{player_pools, PlayerCell};
+use metabolistic3d::metabolism::toxicity::CellHealth;
+use metabolistic3d::metabolism::{run_ticks, CurrencyPools};
+use metabolistic3d::molecules::{CellMass, Currency};
+use metabolistic3d::npc::behaviour::{
+    nutrient_direction, Behaviour, Neighbour, Observer, SteeringRules, Temperament, Wander,
+};
+use metabolistic3d::npc::{spawn_npc, CellEngulfed};
+use metabolistic3d::{GameState, MetabolisticApp};
+
+fn neighbour(index: u32, x: f32, mass: f32, temperament: Option<Temperament>) -> Neighbour {
+    Neighbour {
+        entity: Entity::from_raw(index),
+        position: Vec3::new(x, 0.0, 0.0),
+        mass,
+        toxic: false,
+        temperament,
+    }
+}
+
+fn observer(temperament: Temperament, mass: f32, atp: f32) -> Observer {
+    Observer {
+        entity: Entity::from_raw(0),
+        position: Vec3::ZERO,
+        mass,
+        atp,
+        temperament,
+    }
+}
+
+#[test]
+fn test_prey_flee_from_cells_that_can_engulf_them() {
+    let rules = SteeringRules::default();
+    let me = observer(Temperament::Prey, 1.0, 100.0);
+    let others = [
+        neighbour(1, 3.0, 1.0, Some(Temperament::Prey)),
+        neighbour(2, 8.0, 2.0, Some(Temperament::Predator)),
+        neighbour(3, 5.0, 2.0, None),
+    ];
+    // The player is a threat as much as a predator, and the nearest threat counts
+    assert_eq!(rules.decide(&me, &others), Behaviour::Flee(Entity::from_raw(3)));
+
+    // A predator no heavier than the prey cannot engulf it
+    let others = [neighbour(2, 3.0, 1.0, Some(Temperament::Predator))];
+    assert_eq!(rules.decide(&me, &others), Behaviour::Wander);
+}
+
+#[test]
+fn test_predators_hunt_the_nearest_lighter_cell_that_is_not_toxic() {
+    let rules = SteeringRules::default();
+    let me = observer(Temperament::Predator, 2.0, 100.0);
+    let mut toxic = neighbour(1, 2.0, 1.0, Some(Temperament::Prey));
+    toxic.toxic = true;
+    let others = [
+        toxic,
+        neighbour(2, 3.0, 3.0, Some(Temperament::Prey)),
+        neighbour(3, 4.0, 1.0, Some(Temperament::Predator)),
+        neighbour(4, 6.0, 1.0, Some(Temperament::Prey)),
+        neighbour(5, 5.0, 1.0, None),
+    ];
+    assert_eq!(rules.decide(&me, &others), Behaviour::Hunt(Entity::from_raw(5)));
+
+    // Cells out of sight are ignored
+    let far = [neighbour(4, rules.sight_range + 1.0, 1.0, Some(Temperament::Prey))];
+    assert_eq!(rules.decide(&me, &far), Behaviour::Wander);
+}
+
+#[test]
+fn test_hungry_cells_seek_nutrients() {
+    let rules = SteeringRules::default();
+    let hungry = observer(Temperament::Predator, 2.0, rules.hungry_below_atp - 1.0);
+    assert_eq!(rules.decide(&hungry, &[]), Behaviour::SeekNutrients);
+
+    // Running away still beats eating
+    let hungry = observer(Temperament::Prey, 1.0, 0.0);
+    let others = [neighbour(1, 2.0, 2.0, Some(Temperament::Predator))];
+    assert_eq!(rules.decide(&hungry, &others), Behaviour::Flee(Entity::from_raw(1)));
+}
+
+#[test]
+fn test_nutrient_direction_points_at_the_sweetest_neighbouring_patch() {
+    let mut grid = NutrientGrid::new(Vec2::ZERO, 10.0, 5, 5, [0.0; Nutrient::COUNT]);
+    let here = Vec3::new(25.0, 0.0, 25.0);
+    assert_eq!(nutrient_direction(&grid, here), Vec2::ZERO);
+
+    let east = grid.patch_at(Vec3::new(35.0, 0.0, 25.0)).unwrap();
+    grid.set(east, Nutrient::Sugar, 10.0);
+    assert_eq!(nutrient_direction(&grid, here), Vec2::X);
+    assert_eq!(nutrient_direction(&grid, Vec3::new(500.0, 0.0, 0.0)), Vec2::ZERO);
+}
+
+/// Off the nutrient grid, so that nothing is absorbed while cells are engulfed.
+const AWAY: Vec3 = Vec3::new(1000.0, 0.0, 1000.0);
+
+fn pools_with(beads: f32, acetyl_coa: f32) -> CurrencyPools {
+    let mut pools = CurrencyPools::default();
+    pools.set(Currency::StorageBeads, beads);
+    pools.set(Currency::AcetylCoA, acetyl_coa);
+    pools
+}
+
+fn spawn_with(app: &mut App, temperament: Temperament, genome: Genome, position: Vec3, pools: CurrencyPools) -> Entity {
+    let cell = spawn_npc(app.world_mut(), temperament, genome, position);
+    app.world_mut().entity_mut(cell).insert(pools);
+    cell
+}
+
+/// A headless app in the 3D scene, where cells engulf each other.
+fn app_in_scene() -> App {
+    let mut app = MetabolisticApp::new_headless();
+    app.world_mut().resource_mut::<NextState<GameState>>().set(GameState::Scene3D);
+    app.update();
+    app
+}
+
+fn engulfings(app: &App) -> Vec<CellEngulfed> {
+    let events = app.world().resource::<Events<CellEngulfed>>();
+    events.get_cursor().read(events).copied().collect()
+}
+
+#[test]
+fn test_predators_engulf_a_share_of_lighter_cells_they_touch() {
+    let mut app = app_in_scene();
+    let predator = spawn_with(&mut app, Temperament::Predator, Genome::default(), AWAY, pools_with(0.0, 0.0));
+    let prey = spawn_with(&mut app, Temperament::Prey, Genome::default(), AWAY + Vec3::X, pools_with(8.0, 4.0));
+    app.world_mut().get_mut::<CellMass>(prey).unwrap().extra = 0.4;
+    // Out of reach, and another heavier than the predator can engulf
+    let distant = spawn_with(&mut app, Temperament::Prey, Genome::default(), AWAY + Vec3::X * 5.0, pools_with(8.0, 4.0));
+    let heavy = spawn_with(&mut app, Temperament::Prey, Genome::default(), AWAY - Vec3::X, pools_with(8.0, 4.0));
+    app.world_mut().get_mut::<CellMass>(heavy).unwrap().base = 2.0;
+
+    run_ticks(app.world_mut(), 1);
+    let rules = SteeringRules::default();
+    assert_eq!(engulfings(&app), vec![CellEngulfed { predator, prey }]);
+
+    let taken = |cell: Entity| app.world().get::<CurrencyPools>(cell).unwrap().clone();
+    assert_eq!(taken(prey).get(Currency::StorageBeads), 8.0 * (1.0 - rules.engulf_fraction));
+    assert_eq!(taken(predator).get(Currency::StorageBeads), 8.0 * rules.engulf_fraction);
+    assert_eq!(taken(predator).get(Currency::AcetylCoA), 4.0 * rules.engulf_fraction);
+    assert_eq!(taken(distant).get(Currency::StorageBeads), 8.0);
+    assert_eq!(taken(heavy).get(Currency::StorageBeads), 8.0);
+
+    let mass = |cell: Entity| app.world().get::<CellMass>(cell).unwrap();
+    assert!((mass(predator).extra - 0.4 * rules.engulf_fraction).abs() < 1e-6);
+    assert!((mass(prey).extra - 0.4 * (1.0 - rules.engulf_fraction)).abs() < 1e-6);
+}
+
+#[test]
+fn test_nobody_is_engulfed_outside_the_3d_scene() {
+    let mut app = MetabolisticApp::new_headless();
+    app.update();
+    spawn_with(&mut app, Temperament::Predator, Genome::default(), AWAY, pools_with(0.0, 0.0));
+    let prey = spawn_with(&mut app, Temperament::Prey, Genome::default(), AWAY + Vec3::X, pools_with(8.0, 0.0));
+
+    run_ticks(app.world_mut(), 1);
+    assert!(engulfings(&app).is_empty());
+    assert_eq!(app.world().get::<CurrencyPools>(prey).unwrap().get(Currency::StorageBeads), 8.0);
+}
+
+#[test]
+fn test_predators_leave_a_cell_they_engulfed_alone_for_a_while() {
+    let mut app = app_in_scene();
+    let rules = SteeringRules::default();
+    let predator = spawn_with(&mut app, Temperament::Predator, Genome::default(), AWAY, pools_with(0.0, 0.0));
+    let prey = spawn_with(&mut app, Temperament::Prey, Genome::default(), AWAY + Vec3::X, pools_with(8.0, 0.0));
+
+    // Engulfed on the first tick, then left alone until the cooldown runs out
+    run_ticks(app.world_mut(), rules.engulf_cooldown_ticks as u64);
+    assert_eq!(engulfings(&app), vec![CellEngulfed { predator, prey }]);
+    let beads = app.world().get::<CurrencyPools>(prey).unwrap().get(Currency::StorageBeads);
+    assert_eq!(beads, 8.0 * (1.0 - rules.engulf_fraction));
+
+    run_ticks(app.world_mut(), 1);
+    assert_eq!(engulfings(&app).len(), 2);
+}
+
+#[test]
+fn test_engulfing_a_toxic_cell_hurts() {
+    let mut app = app_in_scene();
+    let mut toxic = Genome::default();
+    toxic.add_gene(BlockKind::SecondaryMetabolites);
+    toxic.express_gene(BlockKind::SecondaryMetabolites);
+    let predator = spawn_with(&mut app, Temperament::Predator, Genome::default(), AWAY, pools_with(0.0, 0.0));
+    spawn_with(&mut app, Temperament::Prey, toxic, AWAY + Vec3::X, pools_with(8.0, 0.0));
+
+    run_ticks(app.world_mut(), 1);
+    let rules = SteeringRules::default();
+    let health = app.world().get::<CellHealth>(predator).unwrap();
+    assert!(health.current <= health.max - rules.toxin_damage + 1.0);
+}
+
+#[test]
+fn test_the_player_engulfs_into_its_own_pools() {
+    let mut app = app_in_scene();
+    let player = app
+        .world_mut()
+        .query_filtered::<Entity, With<PlayerCell>>()
+        .single(app.world());
+    // Nothing the player's own metabolism makes gets in the way
+    app.world_mut().entity_mut(player).insert((
+        Transform::from_translation(AWAY),
+        CellMass { base: 2.0, extra: 0.0 },
+        Genome::default(),
+        CurrencyPools::default(),
+    ));
+    let prey = spawn_with(&mut app, Temperament::Prey, Genome::default(), AWAY + Vec3::Z, pools_with(8.0, 0.0));
+
+    run_ticks(app.world_mut(), 1);
+    assert_eq!(engulfings(&app), vec![CellEngulfed { predator: player, prey }]);
+    let rules = SteeringRules::default();
+    let pools = player_pools(app.world());
+    assert_eq!(pools.get(Currency::StorageBeads), 8.0 * rules.engulf_fraction);
+}
+
+/// A lone wandering cell after `ticks` metabolic ticks, in the 3D scene or not.
+fn wander_for(ticks: u64, in_scene: bool) -> (Wander, Vec3) {
+    let mut app = MetabolisticApp::new_headless();
+    if in_scene {
+        app.world_mut().resource_mut::<NextState<GameState>>().set(GameState::Scene3D);
+    }
+    app.update();
+    let cell = spawn_npc(app.world_mut(), Temperament::Prey, create_starter_genome(), AWAY);
+    run_ticks(app.world_mut(), ticks);
+    let world = app.world();
+    (*world.get::<Wander>(cell).unwrap(), world.get::<ExternalTorque>(cell).unwrap().torque())
+}
+
+#[test]
+fn test_npcs_steer_reproducibly_on_the_tick_in_the_3d_scene() {
+    let (start, _) = wander_for(0, true);
+    let (wander, torque) = wander_for(3, true);
+    assert_ne!(wander, start);
+    assert_ne!(torque, Vec3::ZERO);
+    assert_eq!(wander_for(3, true), (wander, torque));
+
+    // Outside the 3D scene nobody steers
+    assert_eq!(wander_for(3, false), (start, Vec3::ZERO));
+}
@@ -0,0 +1,71 @@
+//! # Cell Physics Tests
+//!
+//! Bodies take their mass and drag from `CellMass`, stored fat makes the cell heavier, and
+//! rolling costs ATP.
+
+use avian3d::prelude::*;
+use bevy::prelude::*;
+use metabolistic3d::metabolism::cells::{player_pools, player_pools_mut};
+use metabolistic3d::metabolism::{run_ticks, CurrencyPools};
+use metabolistic3d::molecules::{CellMass, Currency, LipidToxicityThreshold};
+use metabolistic3d::player::physics::{BaseDamping, CellPhysics, CellPhysicsPlugin};
+use metabolistic3d::MetabolisticApp;
+
+#[test]
+fn test_bodies_follow_their_cell_mass() {
+    let mut app = App::new();
+    app.add_plugins(MinimalPlugins).add_plugins(CellPhysicsPlugin);
+    let body = app
+        .world_mut()
+        .spawn((RigidBody::Dynamic, CellMass { base: 1.0, extra: 0.0 }, BaseDamping(5.0)))
+        .id();
+    app.update();
+    assert_eq!(app.world().get::<Mass>(body), Some(&Mass(1.0)));
+    assert_eq!(app.world().get::<AngularDamping>(body), Some(&AngularDamping(5.0)));
+
+    app.world_mut().get_mut::<CellMass>(body).unwrap().extra = 20.0;
+    app.update();
+    let physics = app.world().resource::<CellPhysics>().clone();
+    assert_eq!(app.world().get::<Mass>(body), Some(&Mass(21.0)));
+    let damping = app.world().get::<AngularDamping>(body).unwrap().0;
+    assert_eq!(damping, 5.0 + 20.0 * physics.damping_per_extra_mass);
+
+    // A drained cell keeps a body the solver can move
+    app.world_mut().get_mut::<CellMass>(body).unwrap().extra = -5.0;
+    app.update();
+    assert_eq!(app.world().get::<Mass>(body), Some(&Mass(physics.min_mass)));
+}
+
+#[test]
+fn test_storing_fat_adds_mass() {
+    let mut app = MetabolisticApp::new_headless();
+    app.update();
+    let cell = app.world_mut().spawn(CellMass { base: 1.0, extra: 0.0 }).id();
+    app.world_mut().resource_mut::<LipidToxicityThreshold>().0 = 10.0;
+    player_pools_mut(app.world_mut()).set(Currency::FreeFattyAcids, 30.0);
+
+    run_ticks(app.world_mut(), 1);
+    let beads = player_pools(app.world()).get(Currency::StorageBeads);
+    assert!(beads > 0.0);
+    let mass_per_bead = app.world().resource::<CellPhysics>().mass_per_bead;
+    assert_eq!(app.world().get::<CellMass>(cell).unwrap().extra, beads * mass_per_bead);
+}
+
+#[test]
+fn test_rolling_costs_atp() {
+    let physics = CellPhysics::default();
+    let torque = Vec3::new(2.0, 0.0, 0.0);
+    let mut pools = CurrencyPools::default();
+    pools.set(Currency::ATP, 10.0);
+
+    let applied = physics.pay_for_torque(&mut pools, torque);
+    assert_eq!(applied, torque);
+    assert_eq!(pools.get(Currency::ATP), 10.0 - 2.0 * physics.atp_per_torque);
+
+    // Only what the ATP left can pay for is applied
+    pools.set(Currency::ATP, physics.atp_per_torque);
+    let applied = physics.pay_for_torque(&mut pools, torque);
+    assert_eq!(applied, torque * 0.5);
+    assert_eq!(pools.get(Currency::ATP), 0.0);
+    assert_eq!(physics.pay_for_torque(&mut pools, torque), Vec3::ZERO);
+}
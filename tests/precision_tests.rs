@@ -5,9 +5,10 @@
 
 use proptest::prelude::*;
 use approx::{assert_relative_eq, assert_abs_diff_eq};
+use metabolistic3d::metabolism::cells::{player_pools, player_pools_mut};
+use metabolistic3d::metabolism::run_ticks;
 use metabolistic3d::molecules::*;
 use metabolistic3d::blocks::fermentation::FermentationBlock;
-use metabolistic3d::metabolism::CurrencyPools;
 use metabolistic3d::molecules::{PolyMer, CellMass};  
 use metabolistic3d::blocks::vesicle_export::VesicleExportBlock;
 use metabolistic3d::MetabolisticApp;
@@ -39,21 +40,21 @@ proptest! {
         iterations in 10..1000usize
     ) {
         let mut app = MetabolisticApp::new_headless();
-        app.world_mut().resource_mut::<CurrencyPools>().set(Currency::ATP, initial_amount);
+        player_pools_mut(app.world_mut()).set(Currency::ATP, initial_amount);
         
         // Perform many small operations
         let mut expected_total = initial_amount;
         for _ in 0..iterations {
             if expected_total >= operation_size {
-                let atp_before = app.world().resource::<CurrencyPools>().get(Currency::ATP);
+                let atp_before = player_pools(app.world()).get(Currency::ATP);
                 if atp_before >= operation_size {
-                    app.world_mut().resource_mut::<CurrencyPools>().modify(Currency::ATP, -operation_size);
+                    player_pools_mut(app.world_mut()).modify(Currency::ATP, -operation_size);
                     expected_total -= operation_size;
                 }
             }
         }
         
-        let actual_total = app.world().resource::<CurrencyPools>().get(Currency::ATP);
+        let actual_total = player_pools(app.world()).get(Currency::ATP);
         
         // Verify precision is maintained within acceptable bounds
         assert_relative_eq!(actual_total, expected_total, epsilon = CURRENCY_RELATIVE_EPSILON);
@@ -72,7 +73,7 @@ proptest! {
     ) {
         let mut app = MetabolisticApp::new_headless();
         
-        let mut currency_pools = app.world_mut().resource_mut::<CurrencyPools>();
+        let mut currency_pools = player_pools_mut(app.world_mut());
         currency_pools.set(Currency::FreeFattyAcids, initial_ffa);
         currency_pools.set(Currency::StorageBeads, 50.0);
         currency_pools.set(Currency::ATP, 1000.0); // Plenty of ATP
@@ -93,17 +94,20 @@ proptest! {
         // Run multiple cycles to test precision over time
         for _ in 0..cycles {
             app.update();
+            run_ticks(app.world_mut(), 1);
             
-            let currency_pools = app.world().resource::<CurrencyPools>();
+            let currency_pools = player_pools(app.world());
             let current_ffa = currency_pools.get(Currency::FreeFattyAcids);
             let current_storage = currency_pools.get(Currency::StorageBeads);
             let current_total = current_ffa + current_storage;
             
-            // Verify lipid conservation with tight precision bounds
+            // Verify lipid conservation with tight precision bounds: each tick may round
+            // both pools by at most an f32 ulp of the total
             assert_relative_eq!(
                 current_total, 
                 initial_total_lipids, 
-                epsilon = CURRENCY_RELATIVE_EPSILON
+                epsilon = CURRENCY_RELATIVE_EPSILON,
+                max_relative = f32::EPSILON * cycles as f32
             );
         }
     }
@@ -121,7 +125,7 @@ proptest! {
     ) {
         let mut app = MetabolisticApp::new_headless();
         
-        let mut currency_pools = app.world_mut().resource_mut::<CurrencyPools>();
+        let mut currency_pools = player_pools_mut(app.world_mut());
         currency_pools.set(Currency::Pyruvate, initial_pyruvate);
         currency_pools.set(Currency::ReducingPower, initial_rp);
         currency_pools.set(Currency::ATP, 0.0);
@@ -142,7 +146,7 @@ proptest! {
             
             // Check mass balance precision every 10 steps
             if step % 10 == 0 {
-                let currency_pools = app.world().resource::<CurrencyPools>();
+                let currency_pools = player_pools(app.world());
                 let current_pyruvate = currency_pools.get(Currency::Pyruvate);
                 let current_rp = currency_pools.get(Currency::ReducingPower);
                 let current_atp = currency_pools.get(Currency::ATP);
@@ -194,22 +198,22 @@ proptest! {
         operations in 1..100usize
     ) {
         let mut app = MetabolisticApp::new_headless();
-        app.world_mut().resource_mut::<CurrencyPools>().set(Currency::ATP, tiny_amount);
+        player_pools_mut(app.world_mut()).set(Currency::ATP, tiny_amount);
         
         let initial_amount = tiny_amount;
         
         // Attempt operations on very small amounts
         for _ in 0..operations {
-            let current_amount = app.world().resource::<CurrencyPools>().get(Currency::ATP);
+            let current_amount = player_pools(app.world()).get(Currency::ATP);
             
             // Try to consume half of remaining amount
             let consumption_amount = current_amount * 0.5;
             if consumption_amount > 1e-10 { // Avoid underflow
-                if app.world().resource::<CurrencyPools>().can_consume(Currency::ATP, consumption_amount) {
-                    app.world_mut().resource_mut::<CurrencyPools>().modify(Currency::ATP, -consumption_amount);
+                if player_pools(app.world()).can_consume(Currency::ATP, consumption_amount) {
+                    player_pools_mut(app.world_mut()).modify(Currency::ATP, -consumption_amount);
                 }
                 
-                let new_amount = app.world().resource::<CurrencyPools>().get(Currency::ATP);
+                let new_amount = player_pools(app.world()).get(Currency::ATP);
                 
                 // Verify precision maintained even for tiny operations
                 let expected = current_amount - consumption_amount;
@@ -222,7 +226,7 @@ proptest! {
         }
         
         // Final amount should still be non-negative and reasonable
-        let final_amount = app.world().resource::<CurrencyPools>().get(Currency::ATP);
+        let final_amount = player_pools(app.world()).get(Currency::ATP);
         prop_assert!(final_amount >= 0.0);
         prop_assert!(final_amount <= initial_amount + 1e-10);
     }
@@ -236,21 +240,21 @@ proptest! {
         small_operations in prop::collection::vec(1.0f32..100.0f32, 10..50)
     ) {
         let mut app = MetabolisticApp::new_headless();
-        app.world_mut().resource_mut::<CurrencyPools>().set(Currency::ATP, large_amount);
+        player_pools_mut(app.world_mut()).set(Currency::ATP, large_amount);
         
         let mut expected_amount = large_amount;
         
         // Perform many small operations on large amount
         for &op_amount in &small_operations {
             if expected_amount >= op_amount {
-                if app.world().resource::<CurrencyPools>().can_consume(Currency::ATP, op_amount) {
-                    app.world_mut().resource_mut::<CurrencyPools>().modify(Currency::ATP, -op_amount);
+                if player_pools(app.world()).can_consume(Currency::ATP, op_amount) {
+                    player_pools_mut(app.world_mut()).modify(Currency::ATP, -op_amount);
                     expected_amount -= op_amount;
                 }
             }
         }
         
-        let actual_amount = app.world().resource::<CurrencyPools>().get(Currency::ATP);
+        let actual_amount = player_pools(app.world()).get(Currency::ATP);
         
         // Verify precision maintained even with large base amounts
         assert_relative_eq!(
@@ -280,7 +284,7 @@ proptest! {
         let mut app = MetabolisticApp::new_headless();
         
         // Initialize all systems
-        let mut currency_pools = app.world_mut().resource_mut::<CurrencyPools>();
+        let mut currency_pools = player_pools_mut(app.world_mut());
         currency_pools.set(Currency::Pyruvate, pyruvate);
         currency_pools.set(Currency::ReducingPower, rp);
         currency_pools.set(Currency::FreeFattyAcids, ffa);
@@ -4,9 +4,10 @@
 //! It includes generators for currency values, metabolic states, and system configurations.
 
 use proptest::prelude::*;
+use metabolistic3d::metabolism::cells::{player_pools, player_pools_mut};
 use metabolistic3d::molecules::*;
 use metabolistic3d::blocks::genome::{BlockKind, GeneState};
-use metabolistic3d::metabolism::{BlockStatus, FluxProfile, CurrencyPools};
+use metabolistic3d::metabolism::{BlockStatus, FluxProfile};
 use metabolistic3d::MetabolisticApp;
 use bevy::prelude::*;
 use std::collections::HashMap;
@@ -125,7 +126,7 @@ pub fn app_with_currencies(
 ) -> App {
     let mut app = MetabolisticApp::new_headless();
     
-    let mut currency_pools = app.world_mut().resource_mut::<CurrencyPools>();
+    let mut currency_pools = player_pools_mut(app.world_mut());
     currency_pools.set(Currency::ATP, atp);
     currency_pools.set(Currency::ReducingPower, reducing_power);
     currency_pools.set(Currency::AcetylCoA, acetyl_coa);
@@ -189,19 +190,19 @@ pub fn spawn_complete_fermentation_block(app: &mut App) {
 
 /// Calculates the total currency pool across all currencies in an app
 pub fn total_currency_pool(app: &App) -> f32 {
-    let currency_pools = app.world().resource::<CurrencyPools>();
+    let currency_pools = player_pools(app.world());
     currency_pools.pools.values().sum()
 }
 
 /// Checks if all currencies in an app are non-negative
 pub fn all_currencies_non_negative(app: &App) -> bool {
-    let currency_pools = app.world().resource::<CurrencyPools>();
+    let currency_pools = player_pools(app.world());
     currency_pools.pools.values().all(|&v| v >= 0.0)
 }
 
 /// Gets all currency amounts as a vector for easy comparison
 pub fn get_currency_snapshot(app: &App) -> Vec<f32> {
-    let currency_pools = app.world().resource::<CurrencyPools>();
+    let currency_pools = player_pools(app.world());
     vec![
         currency_pools.get(Currency::ATP),
         currency_pools.get(Currency::ReducingPower),
@@ -226,6 +227,7 @@ macro_rules! proptest_over_time {
                 for _ in 0..$updates {
                     let initial_state = get_currency_snapshot(&app);
                     app.update();
+                    metabolistic3d::metabolism::run_ticks(app.world_mut(), 1);
                     prop_assert!($property(&app, &initial_state));
                 }
             }
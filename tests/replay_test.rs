@@ -0,0 +1,153 @@
+//! # Replay Tests
+//!
+//! A session recorded frame by frame must play back on a fresh headless app and
+//! end with the same currency pools.
+
+use bevy::prelude::*;
+use bevy::time::TimeUpdateStrategy;
+use metabolistic3d::blocks::genome::{
+    BlockKind, GeneState, GenomeCommand, MutationConfig, MutationStrategy,
+};
+use metabolistic3d::metabolism::{CurrencyPools, MetabolicTick, DEFAULT_METABOLIC_TICK_SECONDS};
+use metabolistic3d::molecules::Currency;
+use metabolistic3d::replay::{self, ReplayError, ReplayInput, ReplayLog, REPLAY_VERSION};
+use metabolistic3d::{GameState, MetabolisticApp};
+use std::time::Duration;
+
+/// Mutates fermentation once, on the given call.
+struct MutateOnce {
+    calls: u32,
+    at: u32,
+}
+
+impl MutationStrategy for MutateOnce {
+    fn should_mutate(&mut self, block_kind: BlockKind, _delta_time: f32) -> bool {
+        if block_kind != BlockKind::Fermentation {
+            return false;
+        }
+        self.calls += 1;
+        self.calls == self.at
+    }
+
+    fn get_mutation_target(&mut self, _block_kind: BlockKind) -> GeneState {
+        GeneState::Mutated
+    }
+}
+
+/// An app where every `update` is one frame running exactly one metabolic tick.
+fn frame_driven_app() -> App {
+    let mut app = MetabolisticApp::new_headless();
+    app.insert_resource(TimeUpdateStrategy::ManualDuration(Duration::from_secs_f64(
+        DEFAULT_METABOLIC_TICK_SECONDS,
+    )));
+    app.update();
+    app
+}
+
+fn record_session() -> ReplayLog {
+    let mut app = frame_driven_app();
+    app.insert_resource(MutationConfig {
+        strategy: Box::new(MutateOnce { calls: 0, at: 22 }),
+        rate_multiplier: 1.0,
+    });
+    replay::start_recording(app.world_mut());
+
+    for frame in 0..30 {
+        match frame {
+            3 => {
+                app.world_mut().send_event(GenomeCommand::Express(BlockKind::Fermentation));
+            }
+            7 => app.world_mut().resource_mut::<NextState<GameState>>().set(GameState::Scene2D),
+            12 => {
+                app.world_mut().send_event(GenomeCommand::Silence(BlockKind::Fermentation));
+            }
+            15 => {
+                app.world_mut().send_event(GenomeCommand::Express(BlockKind::Fermentation));
+            }
+            _ => {}
+        }
+        app.update();
+    }
+
+    replay::stop_recording(app.world_mut()).expect("recording was started")
+}
+
+#[test]
+fn test_recording_logs_inputs_with_ticks() {
+    let log = record_session();
+
+    assert_eq!(log.version, REPLAY_VERSION);
+    assert!(log.final_tick >= 25, "only {} ticks recorded", log.final_tick);
+    let inputs: Vec<&ReplayInput> = log.inputs.iter().map(|r| &r.input).collect();
+    assert!(inputs.contains(&&ReplayInput::Genome {
+        command: GenomeCommand::Express(BlockKind::Fermentation)
+    }));
+    assert!(inputs.contains(&&ReplayInput::StateTransition { state: GameState::Scene2D }));
+    assert!(inputs.contains(&&ReplayInput::Mutation {
+        gene: BlockKind::Fermentation,
+        target: GeneState::Mutated
+    }));
+    let ticks: Vec<u64> = log.inputs.iter().map(|r| r.tick).collect();
+    assert!(ticks.windows(2).all(|w| w[0] <= w[1]), "ticks out of order: {:?}", ticks);
+}
+
+#[test]
+fn test_headless_playback_reproduces_final_pools() {
+    let log = record_session();
+    let json = log.to_json().unwrap();
+
+    let outcome = replay::play_headless(&ReplayLog::from_json(&json).unwrap()).unwrap();
+
+    assert_eq!(outcome.final_tick, log.final_tick);
+    assert!(outcome.matches(), "replay diverged: {:?}", outcome);
+}
+
+#[test]
+fn test_playback_detects_divergence() {
+    let mut log = record_session();
+    log.inputs.retain(|r| !matches!(r.input, ReplayInput::Genome { .. }));
+
+    let outcome = replay::play_headless(&log).unwrap();
+    assert!(!outcome.matches());
+}
+
+#[test]
+fn test_playback_restores_live_configuration() {
+    let log = record_session();
+    let mut app = MetabolisticApp::new_headless();
+    app.update();
+    let ticks_before = app.world().resource::<MetabolicTick>().0;
+
+    replay::start_playback(app.world_mut(), log.clone()).unwrap();
+    while app.world().contains_resource::<replay::ReplayPlayback>() {
+        app.update();
+    }
+
+    assert!(app.world().contains_resource::<MutationConfig>());
+    assert_eq!(app.world().resource::<MetabolicTick>().0 - ticks_before, log.final_tick);
+    assert!(app.world().resource::<replay::ReplayOutcome>().matches());
+}
+
+#[test]
+fn test_currency_hash_is_order_independent_and_exact() {
+    let mut a = CurrencyPools::default();
+    a.set(Currency::ATP, 1.0);
+    a.set(Currency::Pyruvate, 2.0);
+    let mut b = CurrencyPools::default();
+    b.set(Currency::Pyruvate, 2.0);
+    b.set(Currency::ATP, 1.0);
+    assert_eq!(a.state_hash(), b.state_hash());
+
+    b.set(Currency::ATP, 1.0 + f32::EPSILON);
+    assert_ne!(a.state_hash(), b.state_hash());
+}
+
+#[test]
+fn test_newer_replay_version_is_rejected() {
+    let mut value: serde_json::Value = serde_json::from_str(&record_session().to_json().unwrap()).unwrap();
+    value["version"] = serde_json::json!(REPLAY_VERSION + 1);
+    assert!(matches!(
+        ReplayLog::from_json(&value.to_string()),
+        Err(ReplayError::UnsupportedVersion(_))
+    ));
+}
@@ -0,0 +1,48 @@
+//! # Scenario Regression Tests
+//!
+//! Runs every scenario in `tests/scenarios/` and checks its expectations, so
+//! regression cases can be added as JSON files without writing Rust.
+
+use std::path::PathBuf;
+
+use metabolistic3d::scenario::Scenario;
+
+fn scenario_files() -> Vec<PathBuf> {
+    let dir = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/scenarios");
+    let mut files: Vec<PathBuf> = std::fs::read_dir(dir)
+        .unwrap()
+        .map(|entry| entry.unwrap().path())
+        .filter(|path| path.extension().and_then(|e| e.to_str()) == Some("json"))
+        .collect();
+    files.sort();
+    files
+}
+
+#[test]
+fn test_scenario_expectations_hold() {
+    let files = scenario_files();
+    assert!(!files.is_empty(), "no scenarios found in tests/scenarios");
+
+    let mut report = Vec::new();
+    for path in &files {
+        let name = path.file_name().unwrap().to_string_lossy();
+        let scenario = match Scenario::load_from_file(path) {
+            Ok(scenario) => scenario,
+            Err(err) => {
+                report.push(format!("{}: {}", name, err));
+                continue;
+            }
+        };
+        assert!(
+            !scenario.expectations.is_empty(),
+            "{} has no expectations",
+            name
+        );
+        match scenario.check() {
+            Ok(failures) => report.extend(failures.iter().map(|f| format!("{}: {}", name, f))),
+            Err(err) => report.push(format!("{}: {}", name, err)),
+        }
+    }
+
+    assert!(report.is_empty(), "scenario failures:\n{}", report.join("\n"));
+}
@@ -0,0 +1,148 @@
+use metabolistic3d::blocks::fermentation::FermentationRate;
+use metabolistic3d::blocks::genome::{BlockKind, GeneState};
+use metabolistic3d::metabolism::CurrencyPools;
+use metabolistic3d::metabolism::cells::{player_genome, player_pools};
+use metabolistic3d::molecules::{CellMass, Currency};
+use metabolistic3d::scenario::{Scenario, ScenarioError};
+
+const FERMENTATION_SCENARIO: &str = r#"{
+    "name": "fermentation",
+    "ticks": 5,
+    "currencies": { "pyruvate": 10.0, "reducing_power": 10.0, "organic_waste": 0.0 },
+    "genome": { "version": 1, "genes": [{ "kind": "fermentation", "state": "expressed" }] },
+    "rates": { "fermentation": 2.0, "vesicle_export": 0.0 },
+    "environment": {
+        "lipid_toxicity_threshold": 5.0,
+        "cells": [{ "cell_mass": { "base": 1.0, "extra": 0.0 } }]
+    }
+}"#;
+
+#[test]
+fn test_scenario_applies_initial_state() {
+    let scenario = Scenario::from_json(FERMENTATION_SCENARIO).unwrap();
+    let mut app = scenario.build_app().unwrap();
+    let world = app.world_mut();
+
+    let pools = player_pools(world);
+    assert_eq!(pools.get(Currency::Pyruvate), 10.0);
+    // Unlisted currencies keep their defaults
+    assert_eq!(pools.get(Currency::ATP), CurrencyPools::with_defaults().get(Currency::ATP));
+    assert_eq!(world.resource::<FermentationRate>().0, 2.0);
+    assert_eq!(
+        player_genome(world).get_gene_state(&BlockKind::Fermentation),
+        Some(&GeneState::Expressed)
+    );
+    assert_eq!(world.query::<&CellMass>().iter(world).count(), 1);
+}
+
+#[test]
+fn test_scenario_run_records_every_tick() {
+    let scenario = Scenario::from_json(FERMENTATION_SCENARIO).unwrap();
+    let series = scenario.run().unwrap();
+
+    let ticks: Vec<u64> = series.samples.iter().map(|s| s.tick).collect();
+    assert_eq!(ticks, (0..=5).collect::<Vec<_>>());
+
+    // Fermentation at rate 2 turns pyruvate into waste every tick
+    let pyruvate: Vec<f32> = series.samples.iter().map(|s| s.currencies[&Currency::Pyruvate]).collect();
+    assert_eq!(pyruvate, vec![10.0, 8.0, 6.0, 4.0, 2.0, 0.0]);
+    assert_eq!(series.samples[5].currencies[&Currency::OrganicWaste], 10.0);
+}
+
+#[test]
+fn test_scenario_runs_are_reproducible() {
+    let scenario = Scenario::from_json(FERMENTATION_SCENARIO).unwrap();
+    assert_eq!(scenario.run().unwrap(), scenario.run().unwrap());
+}
+
+#[test]
+fn test_time_series_csv_has_one_column_per_currency() {
+    let series = Scenario::from_json(FERMENTATION_SCENARIO).unwrap().run().unwrap();
+    let csv = series.to_csv();
+    let mut lines = csv.lines();
+
+    let header = lines.next().unwrap();
+    assert!(header.starts_with("tick,atp,"), "unexpected header: {}", header);
+    assert!(header.contains("organic_waste"));
+    let columns = header.split(',').count();
+    assert_eq!(lines.clone().count(), series.samples.len());
+    assert!(lines.all(|row| row.split(',').count() == columns));
+}
+
+#[test]
+fn test_scenario_defaults_and_errors() {
+    let scenario = Scenario::from_json(r#"{ "ticks": 2 }"#).unwrap();
+    assert_eq!(scenario.run().unwrap().samples.len(), 3);
+
+    assert!(matches!(
+        Scenario::from_json("{ not json"),
+        Err(ScenarioError::MalformedJson(_))
+    ));
+
+    let bad_genome = r#"{ "ticks": 1, "genome": { "version": 1, "genes": [{ "kind": "warp_drive", "state": "expressed" }] } }"#;
+    let scenario = Scenario::from_json(bad_genome).unwrap();
+    assert!(matches!(scenario.run(), Err(ScenarioError::Genome(_))));
+}
+
+#[test]
+fn test_bundled_scenarios_load() {
+    let dir = concat!(env!("CARGO_MANIFEST_DIR"), "/scenarios");
+    for entry in std::fs::read_dir(dir).unwrap() {
+        let path = entry.unwrap().path();
+        if path.extension().and_then(|e| e.to_str()) == Some("json") {
+            let scenario = Scenario::load_from_file(&path)
+                .unwrap_or_else(|err| panic!("{}: {}", path.display(), err));
+            scenario
+                .build_app()
+                .unwrap_or_else(|err| panic!("{}: {}", path.display(), err));
+        }
+    }
+}
+
+#[test]
+fn test_actions_apply_after_their_tick_is_recorded() {
+    let json = r#"{
+        "ticks": 4,
+        "currencies": { "pyruvate": 10.0, "reducing_power": 10.0, "organic_waste": 0.0 },
+        "rates": { "vesicle_export": 0.0 },
+        "actions": [
+            { "tick": 2, "action": "express_gene", "gene": "fermentation" },
+            { "tick": 2, "action": "add_currency", "currency": "atp", "amount": -50.0 }
+        ]
+    }"#;
+    let series = Scenario::from_json(json).unwrap().run().unwrap();
+    let waste: Vec<f32> = series.samples.iter().map(|s| s.currencies[&Currency::OrganicWaste]).collect();
+    let atp: Vec<f32> = series.samples.iter().map(|s| s.currencies[&Currency::ATP]).collect();
+
+    assert_eq!(waste, vec![0.0, 0.0, 0.0, 1.0, 2.0]);
+    assert_eq!(atp, vec![100.0, 100.0, 100.0, 51.0, 52.0]);
+}
+
+#[test]
+fn test_expectation_failures_name_tick_and_currency() {
+    let json = r#"{
+        "ticks": 3,
+        "genome": { "version": 1, "genes": [{ "kind": "fermentation", "state": "expressed" }] },
+        "rates": { "vesicle_export": 0.0 },
+        "expectations": [
+            { "expect": "always", "currency": "organic_waste", "max": 1.5 },
+            { "expect": "at_tick", "tick": 3, "currency": "atp", "min": 103.0, "max": 103.0 },
+            { "expect": "at_tick", "tick": 10, "currency": "atp", "min": 0.0 }
+        ]
+    }"#;
+    let scenario = Scenario::from_json(json).unwrap();
+    let failures = scenario.check().unwrap();
+
+    assert_eq!(failures.len(), 2, "unexpected failures: {:?}", failures);
+    assert_eq!(failures[0].index, 0);
+    assert_eq!(failures[0].tick, Some(2));
+    assert!(failures[0].message.contains("organic_waste"), "{}", failures[0]);
+    assert_eq!(failures[1].index, 2);
+    assert_eq!(failures[1].tick, None);
+}
+
+#[test]
+fn test_unknown_action_is_rejected() {
+    let json = r#"{ "ticks": 1, "actions": [{ "tick": 0, "action": "warp", "gene": "fermentation" }] }"#;
+    assert!(matches!(Scenario::from_json(json), Err(ScenarioError::MalformedJson(_))));
+}
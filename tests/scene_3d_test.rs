@@ -0,0 +1,88 @@
+#![cfg(feature = "full")]
+
+//! # 3D Scene Tests
+//!
+//! Leaving the 3D scene puts the bodies of cells away, the player's and the NPCs', without
+//! touching the cells.
+
+use avian3d::prelude::{ColliderDisabled, RigidBodyDisabled};
+use bevy::gizmos::GizmoPlugin;
+use bevy::input::InputPlugin;
+use bevy::prelude::*;
+use metabolistic3d::blocks::genome::{BlockKind, GeneState};
+use metabolistic3d::metabolism::cells::{
+    player_cell, player_genome, player_genome_mut, player_pools, player_pools_mut,
+};
+use metabolistic3d::molecules::Currency;
+use metabolistic3d::npc::behaviour::Temperament;
+use metabolistic3d::player::Player;
+use metabolistic3d::scenes::scene_3d::Scene3DPlugin;
+use metabolistic3d::{GameState, MetabolisticApp};
+
+fn app_with_scene() -> App {
+    let mut app = MetabolisticApp::new_headless();
+    // The asset collections the render plugins would add
+    app.init_asset::<Shader>()
+        .init_asset::<Mesh>()
+        .init_asset::<StandardMaterial>()
+        .add_plugins((InputPlugin, GizmoPlugin, Scene3DPlugin));
+    app.update();
+    app
+}
+
+fn go_to(app: &mut App, state: GameState) {
+    app.world_mut().resource_mut::<NextState<GameState>>().set(state);
+    app.update();
+    app.update();
+}
+
+#[test]
+fn test_the_player_survives_leaving_the_3d_scene() {
+    let mut app = app_with_scene();
+    go_to(&mut app, GameState::Scene3D);
+    player_pools_mut(app.world_mut()).set(Currency::ATP, 42.0);
+    player_genome_mut(app.world_mut()).express_gene(BlockKind::Fermentation);
+    let player = player_cell(app.world()).unwrap();
+
+    go_to(&mut app, GameState::MainMenu);
+    let world = app.world();
+    assert_eq!(player_cell(world), Some(player));
+    assert_eq!(player_pools(world).get(Currency::ATP), 42.0);
+    assert_eq!(
+        player_genome(world).get_gene_state(&BlockKind::Fermentation),
+        Some(&GeneState::Expressed)
+    );
+    assert!(world.get::<Player>(player).is_some());
+    assert!(world.get::<RigidBodyDisabled>(player).is_some());
+    assert_eq!(world.get::<Visibility>(player), Some(&Visibility::Hidden));
+
+    go_to(&mut app, GameState::Scene3D);
+    let world = app.world();
+    assert_eq!(player_cell(world), Some(player));
+    assert!(world.get::<RigidBodyDisabled>(player).is_none());
+    assert!(world.get::<ColliderDisabled>(player).is_none());
+}
+
+#[test]
+fn test_npcs_are_put_away_with_the_scene_and_come_back_with_it() {
+    let mut app = app_with_scene();
+    go_to(&mut app, GameState::Scene3D);
+    let world = app.world_mut();
+    let npcs: Vec<Entity> = world.query_filtered::<Entity, With<Temperament>>().iter(world).collect();
+    assert!(!npcs.is_empty());
+
+    go_to(&mut app, GameState::MainMenu);
+    let world = app.world();
+    for &npc in &npcs {
+        assert!(world.get::<RigidBodyDisabled>(npc).is_some());
+        assert!(world.get::<ColliderDisabled>(npc).is_some());
+        assert_eq!(world.get::<Visibility>(npc), Some(&Visibility::Hidden));
+    }
+
+    // The same cells return rather than a fresh set
+    go_to(&mut app, GameState::Scene3D);
+    let world = app.world_mut();
+    let returned: Vec<Entity> = world.query_filtered::<Entity, With<Temperament>>().iter(world).collect();
+    assert_eq!(returned, npcs);
+    assert!(npcs.iter().all(|&npc| world.get::<RigidBodyDisabled>(npc).is_none()));
+}
@@ -0,0 +1,231 @@
+use bevy::prelude::{App, Entity, Transform, Vec3, With, Without};
+use metabolistic3d::blocks::fermentation::{FermentationBlock, FermentationRate};
+use metabolistic3d::blocks::genome::{BlockKind, GeneState, Genome};
+use metabolistic3d::environment::{Nutrient, NutrientGrid};
+use metabolistic3d::metabolism::cells::{
+    player_genome, player_genome_mut, player_pools, player_pools_mut, Cell, CellMember,
+    CellMetabolism, PlayerCell,
+};
+use metabolistic3d::metabolism::{run_ticks, CurrencyPools, FlowDirty, FluxProfile, MetabolicNode};
+use metabolistic3d::molecules::{CellMass, Currency, PolyMer};
+use metabolistic3d::npc::behaviour::Temperament;
+use metabolistic3d::npc::spawn_npc;
+use metabolistic3d::player::Player;
+use metabolistic3d::snapshot::{
+    self, SimulationSnapshot, SnapshotEntityMap, SnapshotError, SNAPSHOT_VERSION,
+};
+use metabolistic3d::{GameState, MetabolisticApp};
+
+fn started_app() -> bevy::app::App {
+    let mut app = MetabolisticApp::new_headless();
+    app.update();
+    app
+}
+
+/// Give the player's cell the body the 3D scene spawns it with.
+fn with_player_body(app: &mut App, extra: f32) -> Entity {
+    let world = app.world_mut();
+    let player = world.query_filtered::<Entity, With<PlayerCell>>().single(world);
+    world.entity_mut(player).insert((
+        Player,
+        CellMass { base: 1.0, extra },
+        PolyMer { capacity: 100.0, target_fill: 50.0, poly_rate: 20.0, lipo_rate: 5.0 },
+    ));
+    player
+}
+
+#[test]
+fn test_snapshot_captures_currencies_genome_and_blocks() {
+    let mut app = started_app();
+    player_pools_mut(app.world_mut()).set(Currency::ATP, 42.0);
+    player_genome_mut(app.world_mut()).express_gene(BlockKind::Fermentation);
+    app.world_mut().insert_resource(FermentationRate(2.5));
+    app.world_mut().spawn((
+        CellMass { base: 1.0, extra: 3.0 },
+        PolyMer { capacity: 100.0, target_fill: 50.0, poly_rate: 5.0, lipo_rate: 2.0 },
+    ));
+
+    let snapshot = SimulationSnapshot::capture(app.world_mut());
+
+    assert_eq!(snapshot.version, SNAPSHOT_VERSION);
+    assert_eq!(snapshot.currencies.get(&Currency::ATP), Some(&42.0));
+    assert_eq!(snapshot.fermentation_rate, Some(2.5));
+    assert!(snapshot.entities.iter().any(|e| e.fermentation_block && e.node.is_some()));
+    assert!(snapshot.entities.iter().any(|e| e.cell_mass.as_ref().map(|m| m.extra) == Some(3.0)));
+    let ids: Vec<u32> = snapshot.entities.iter().map(|e| e.id).collect();
+    assert_eq!(ids, (0..snapshot.entities.len() as u32).collect::<Vec<_>>());
+}
+
+#[test]
+fn test_snapshot_restores_state_into_fresh_app() {
+    let mut source = started_app();
+    player_pools_mut(source.world_mut()).set(Currency::Pyruvate, 7.0);
+    player_genome_mut(source.world_mut()).express_gene(BlockKind::Fermentation);
+    source.world_mut().spawn(CellMass { base: 2.0, extra: 0.5 });
+    let json = SimulationSnapshot::capture(source.world_mut()).to_json().unwrap();
+
+    let mut target = started_app();
+    player_pools_mut(target.world_mut()).set(Currency::Pyruvate, 999.0);
+    let mapping = SimulationSnapshot::from_json(&json)
+        .unwrap()
+        .apply(target.world_mut())
+        .unwrap();
+
+    let world = target.world_mut();
+    assert_eq!(player_pools(world).get(Currency::Pyruvate), 7.0);
+    assert_eq!(
+        player_genome(world).get_gene_state(&BlockKind::Fermentation),
+        Some(&GeneState::Expressed)
+    );
+    assert!(world.resource::<FlowDirty>().0);
+    assert_eq!(world.resource::<SnapshotEntityMap>().0, mapping);
+
+    // The original fermentation block was replaced rather than duplicated
+    let fermentation_blocks = world
+        .query_filtered::<&FluxProfile, (bevy::prelude::With<FermentationBlock>, bevy::prelude::With<MetabolicNode>)>()
+        .iter(world)
+        .count();
+    assert_eq!(fermentation_blocks, 1);
+    let masses: Vec<f32> = world.query::<&CellMass>().iter(world).map(|m| m.base).collect();
+    assert_eq!(masses, vec![2.0]);
+}
+
+#[test]
+fn test_snapshot_load_is_deterministic() {
+    let mut source = started_app();
+    source.world_mut().spawn(CellMass { base: 1.0, extra: 0.0 });
+    let snapshot = SimulationSnapshot::capture(source.world_mut());
+
+    let mut first = started_app();
+    let mut second = started_app();
+    snapshot.apply(first.world_mut()).unwrap();
+    snapshot.apply(second.world_mut()).unwrap();
+
+    let recaptured_first = SimulationSnapshot::capture(first.world_mut()).to_json().unwrap();
+    let recaptured_second = SimulationSnapshot::capture(second.world_mut()).to_json().unwrap();
+    assert_eq!(recaptured_first, recaptured_second);
+    assert_eq!(recaptured_first, snapshot.to_json().unwrap());
+}
+
+#[test]
+fn test_snapshot_file_round_trip_and_scene() {
+    let path = std::env::temp_dir().join(format!(
+        "metabolistic_snapshot_test_{}/save.json",
+        std::process::id()
+    ));
+
+    let mut source = started_app();
+    source
+        .world_mut()
+        .resource_mut::<bevy::prelude::NextState<GameState>>()
+        .set(GameState::Scene2D);
+    source.update();
+    snapshot::save_simulation(source.world_mut(), &path).unwrap();
+
+    let mut target = started_app();
+    snapshot::load_simulation(target.world_mut(), &path).unwrap();
+    target.update();
+    assert_eq!(
+        *target.world().resource::<bevy::prelude::State<GameState>>().get(),
+        GameState::Scene2D
+    );
+
+    let _ = std::fs::remove_dir_all(path.parent().unwrap());
+}
+
+#[test]
+fn test_snapshot_rejects_future_versions() {
+    let json = format!(r#"{{ "version": {} }}"#, SNAPSHOT_VERSION + 1);
+    assert!(matches!(
+        SimulationSnapshot::from_json(&json),
+        Err(SnapshotError::UnsupportedVersion(_))
+    ));
+    assert!(matches!(
+        SimulationSnapshot::from_json("not json"),
+        Err(SnapshotError::MalformedJson(_))
+    ));
+}
+
+#[test]
+fn test_snapshot_restores_every_cell() {
+    let mut source = started_app();
+    let mut genome = Genome::default();
+    genome.add_gene(BlockKind::Fermentation);
+    genome.express_gene(BlockKind::Fermentation);
+    let position = Vec3::new(3.0, 1.0, -2.0);
+    let cell = spawn_npc(source.world_mut(), Temperament::Prey, genome, position);
+    run_ticks(source.world_mut(), 1);
+    let pyruvate = source.world().get::<CurrencyPools>(cell).unwrap().get(Currency::Pyruvate);
+    let members_of = |app: &mut App, cell: Entity| {
+        let world = app.world_mut();
+        world.query::<&CellMember>().iter(world).filter(|member| member.0 == cell).count()
+    };
+    let members = members_of(&mut source, cell);
+    let snapshot = SimulationSnapshot::capture(source.world_mut());
+
+    let mut target = started_app();
+    snapshot.apply(target.world_mut()).unwrap();
+    let world = target.world_mut();
+    let restored = world
+        .query_filtered::<Entity, (With<Cell>, Without<PlayerCell>)>()
+        .single(world);
+    assert_eq!(world.get::<CurrencyPools>(restored).unwrap().get(Currency::Pyruvate), pyruvate);
+    assert_eq!(
+        world.get::<Genome>(restored).unwrap().get_gene_state(&BlockKind::Fermentation),
+        Some(&GeneState::Expressed)
+    );
+    assert_eq!(world.get::<Temperament>(restored), Some(&Temperament::Prey));
+    assert_eq!(world.get::<Transform>(restored).unwrap().translation, position);
+    assert_eq!(world.get::<CellMetabolism>(restored).unwrap().compartments.0.len(), 4);
+    assert_eq!(members_of(&mut target, restored), members);
+    let recaptured = SimulationSnapshot::capture(target.world_mut());
+    assert_eq!(recaptured.to_json().unwrap(), snapshot.to_json().unwrap());
+
+    // It goes on metabolising exactly as it would have
+    run_ticks(source.world_mut(), 1);
+    run_ticks(target.world_mut(), 1);
+    let pools = |app: &App, cell| app.world().get::<CurrencyPools>(cell).unwrap().state_hash();
+    assert_eq!(pools(&target, restored), pools(&source, cell));
+}
+
+#[test]
+fn test_player_survives_a_load() {
+    let mut source = started_app();
+    with_player_body(&mut source, 4.0);
+    let snapshot = SimulationSnapshot::capture(source.world_mut());
+    assert_eq!(snapshot.player_mass.as_ref().map(|mass| mass.extra), Some(4.0));
+    assert!(snapshot.entities.iter().all(|entity| entity.cell_mass.is_none()));
+
+    let mut target = started_app();
+    let player = with_player_body(&mut target, 0.0);
+    snapshot.apply(target.world_mut()).unwrap();
+    let world = target.world_mut();
+    assert!(world.get::<Player>(player).is_some());
+    assert_eq!(world.get::<CellMass>(player).unwrap().extra, 4.0);
+    assert!(world.get::<PolyMer>(player).is_some());
+    assert_eq!(world.query::<&PlayerCell>().iter(world).count(), 1);
+    assert_eq!(world.query::<&CellMass>().iter(world).count(), 1);
+}
+
+#[test]
+fn test_snapshot_restores_a_depleted_nutrient_patch() {
+    let mut source = started_app();
+    let player = with_player_body(&mut source, 0.0);
+    source.world_mut().entity_mut(player).insert(Transform::from_xyz(3.0, 1.0, -2.0));
+    let mut grid = source.world_mut().resource_mut::<NutrientGrid>();
+    grid.take(0, Nutrient::Sugar, f32::MAX);
+    grid.set_baseline(0, Nutrient::Oxygen, 0.25);
+    let json = SimulationSnapshot::capture(source.world_mut()).to_json().unwrap();
+
+    let mut target = started_app();
+    let player = with_player_body(&mut target, 0.0);
+    target.world_mut().entity_mut(player).insert(Transform::default());
+    SimulationSnapshot::from_json(&json).unwrap().apply(target.world_mut()).unwrap();
+
+    let world = target.world();
+    let grid = world.resource::<NutrientGrid>();
+    assert_eq!(grid.get(0, Nutrient::Sugar), 0.0);
+    assert_eq!(grid.baseline(0, Nutrient::Oxygen), 0.25);
+    assert_eq!(grid, source.world().resource::<NutrientGrid>());
+    assert_eq!(world.get::<Transform>(player).unwrap().translation, Vec3::new(3.0, 1.0, -2.0));
+}
@@ -6,9 +6,9 @@
 
 use proptest::prelude::*;
 use approx::{assert_relative_eq, assert_abs_diff_eq};
+use metabolistic3d::metabolism::cells::{player_pools, player_pools_mut};
 use metabolistic3d::molecules::*;
 use metabolistic3d::blocks::fermentation::FermentationBlock;
-use metabolistic3d::metabolism::CurrencyPools;
 use metabolistic3d::molecules::{PolyMer, CellMass};
 use metabolistic3d::blocks::vesicle_export::VesicleExportBlock;
 use metabolistic3d::MetabolisticApp;
@@ -37,7 +37,7 @@ proptest! {
         let mut app = MetabolisticApp::new_headless();
         
         // Set up stable system configuration
-        let mut currency_pools = app.world_mut().resource_mut::<CurrencyPools>();
+        let mut currency_pools = player_pools_mut(app.world_mut());
         currency_pools.set(Currency::Pyruvate, pyruvate);
         currency_pools.set(Currency::ReducingPower, rp);
         currency_pools.set(Currency::ATP, atp);
@@ -127,7 +127,7 @@ proptest! {
             let mut app = MetabolisticApp::new_headless();
             
             // Identical initial conditions
-            let mut currency_pools = app.world_mut().resource_mut::<CurrencyPools>();
+            let mut currency_pools = player_pools_mut(app.world_mut());
             currency_pools.set(Currency::Pyruvate, pyruvate);
             currency_pools.set(Currency::ReducingPower, rp);
             currency_pools.set(Currency::ATP, atp);
@@ -191,7 +191,7 @@ proptest! {
         let mut app = MetabolisticApp::new_headless();
         
         // Set up stable, well-resourced system
-        let mut currency_pools = app.world_mut().resource_mut::<CurrencyPools>();
+        let mut currency_pools = player_pools_mut(app.world_mut());
         currency_pools.set(Currency::Pyruvate, pyruvate);
         currency_pools.set(Currency::ReducingPower, rp);
         currency_pools.set(Currency::ATP, atp);
@@ -282,7 +282,7 @@ proptest! {
         let mut app = MetabolisticApp::new_headless();
         
         // Set up conditions that might produce cyclic behavior
-        let mut currency_pools = app.world_mut().resource_mut::<CurrencyPools>();
+        let mut currency_pools = player_pools_mut(app.world_mut());
         currency_pools.set(Currency::Pyruvate, pyruvate);
         currency_pools.set(Currency::ReducingPower, rp);
         currency_pools.set(Currency::ATP, atp);
@@ -313,7 +313,7 @@ proptest! {
             app.update();
             app.world_mut().run_schedule(FixedUpdate);
             
-            let currency_pools = app.world().resource::<CurrencyPools>();
+            let currency_pools = player_pools(app.world());
             let current_ffa = currency_pools.get(Currency::FreeFattyAcids);
             let current_waste = currency_pools.get(Currency::OrganicWaste);
             
@@ -390,7 +390,7 @@ proptest! {
         
         // Identical initial conditions
         for app in [&mut normal_app, &mut scaled_app] {
-            let mut currency_pools = app.world_mut().resource_mut::<CurrencyPools>();
+            let mut currency_pools = player_pools_mut(app.world_mut());
             currency_pools.set(Currency::Pyruvate, pyruvate);
             currency_pools.set(Currency::ReducingPower, rp);
             currency_pools.set(Currency::ATP, atp);
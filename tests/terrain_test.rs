@@ -0,0 +1,89 @@
+//! # Terrain Tests
+//!
+//! The terrain is reproducible from its seed, its chunks fit together without seams, and
+//! its biomes set the baseline of the nutrient grid.
+
+use std::collections::HashSet;
+
+use metabolistic3d::environment::{EnvironmentRules, Nutrient, NutrientGrid};
+use metabolistic3d::terrain::{Biome, Terrain, TerrainSettings};
+use metabolistic3d::MetabolisticApp;
+
+fn terrain(seed: u64) -> Terrain {
+    Terrain::new(TerrainSettings {
+        seed,
+        ..Default::default()
+    })
+}
+
+#[test]
+fn test_terrain_is_reproducible_from_its_seed() {
+    let (a, b, c) = (terrain(7), terrain(7), terrain(8));
+    let points = [(0.0, 0.0), (12.5, -80.0), (-240.0, 199.0)];
+    for (x, z) in points {
+        assert_eq!(a.height_at(x, z), b.height_at(x, z));
+        assert_eq!(a.biome_at(x, z), b.biome_at(x, z));
+    }
+    assert!(points.iter().any(|&(x, z)| a.height_at(x, z) != c.height_at(x, z)));
+}
+
+#[test]
+fn test_heights_stay_in_range_and_every_biome_appears() {
+    let terrain = terrain(42);
+    let scale = terrain.settings.height_scale;
+    let mut biomes = HashSet::new();
+    for z in (-250..250).step_by(5) {
+        for x in (-250..250).step_by(5) {
+            let height = terrain.height_at(x as f32, z as f32);
+            assert!((0.0..=scale).contains(&height), "{} at {}, {}", height, x, z);
+            biomes.insert(terrain.biome_at(x as f32, z as f32));
+        }
+    }
+    for biome in [Biome::Seabed, Biome::NutrientVent, Biome::SunnyShallows, Biome::AnoxicMud] {
+        assert!(biomes.contains(&biome), "{:?} never appears", biome);
+    }
+}
+
+#[test]
+fn test_chunks_share_their_edges() {
+    let terrain = terrain(42);
+    let resolution = terrain.settings.chunk_resolution as usize;
+    let row = resolution + 1;
+    assert_eq!(terrain.chunk_coords().count(), 100);
+
+    let left = terrain.chunk_geometry([0, 0].into());
+    let right = terrain.chunk_geometry([1, 0].into());
+    assert_eq!(left.positions.len(), row * row);
+    assert_eq!(left.triangles.len(), 2 * resolution * resolution);
+    for z in 0..row {
+        let edge = left.positions[z * row + resolution] + terrain.chunk_origin([0, 0].into());
+        let start = right.positions[z * row] + terrain.chunk_origin([1, 0].into());
+        assert!(edge.distance(start) < 1e-4);
+        assert!(left.normals[z * row + resolution].distance(right.normals[z * row]) < 1e-4);
+    }
+    // The collider is built from the same triangles as the mesh
+    left.collider();
+}
+
+#[test]
+fn test_biomes_set_the_nutrient_baseline() {
+    let mut app = MetabolisticApp::new_headless();
+    app.update();
+    let terrain = app.world().resource::<Terrain>();
+    let grid = app.world().resource::<NutrientGrid>();
+    let sugar = EnvironmentRules::default().baseline[&Nutrient::Sugar];
+
+    let mut vents = 0;
+    for patch in 0..grid.patch_count() {
+        let center = grid.patch_center(patch);
+        let biome = terrain.biome_at(center.x, center.z);
+        let expected = sugar * biome.nutrient_factors()[Nutrient::Sugar.index()];
+        assert_eq!(grid.baseline(patch, Nutrient::Sugar), expected);
+        assert_eq!(grid.get(patch, Nutrient::Sugar), expected);
+        if biome == Biome::NutrientVent {
+            vents += 1;
+            assert!(expected > sugar);
+        }
+    }
+    assert!(vents > 0);
+}
@@ -0,0 +1,78 @@
+//! # Toxicity Tests
+//!
+//! Toxic pools damage cells; badly damaged cells lose efficiency, mutate faster and die.
+
+use metabolistic3d::blocks::genome::MutationConfig;
+use metabolistic3d::metabolism::cells::player_pools_mut;
+use metabolistic3d::metabolism::toxicity::{
+    efficiency_at, mutation_multiplier_at, toxic_excess, CellHealth, MetabolicEfficiency,
+    ToxicityRules,
+};
+use metabolistic3d::metabolism::vitality::CellVitality;
+use metabolistic3d::metabolism::{run_ticks, CurrencyPools};
+use metabolistic3d::molecules::{CellMass, Currency, LipidToxicityThreshold};
+use metabolistic3d::MetabolisticApp;
+
+#[test]
+fn test_excess_and_impairment() {
+    let rules = ToxicityRules::default();
+    let mut pools = CurrencyPools::with_defaults();
+    pools.set(Currency::OrganicWaste, rules.waste_threshold + 10.0);
+    pools.set(Currency::FreeFattyAcids, 30.0);
+
+    assert_eq!(toxic_excess(&pools, &rules, 25.0), 15.0);
+    // An unset lipid threshold never makes fatty acids toxic
+    assert_eq!(toxic_excess(&pools, &rules, 0.0), 10.0);
+
+    assert_eq!(efficiency_at(1.0, &rules), 1.0);
+    assert_eq!(efficiency_at(rules.impaired_below, &rules), 1.0);
+    assert_eq!(efficiency_at(0.0, &rules), rules.min_efficiency);
+    assert_eq!(mutation_multiplier_at(0.9, &rules), 1.0);
+    assert_eq!(mutation_multiplier_at(0.0, &rules), rules.max_mutation_multiplier);
+}
+
+#[test]
+fn test_cells_get_health_and_take_toxic_damage() {
+    let mut app = MetabolisticApp::new_headless();
+    app.update();
+    let cell = app.world_mut().spawn(CellMass { base: 1.0, extra: 0.0 }).id();
+    assert_eq!(app.world().get::<CellHealth>(cell), Some(&CellHealth::default()));
+
+    let waste_threshold = app.world().resource::<ToxicityRules>().waste_threshold;
+    player_pools_mut(app.world_mut())
+        .set(Currency::OrganicWaste, waste_threshold + 500.0);
+    run_ticks(app.world_mut(), 1);
+    let damaged = app.world().get::<CellHealth>(cell).unwrap().current;
+    assert!(damaged < 100.0);
+
+    // Health recovers once the waste is gone
+    player_pools_mut(app.world_mut()).set(Currency::OrganicWaste, 0.0);
+    run_ticks(app.world_mut(), 1);
+    assert!(app.world().get::<CellHealth>(cell).unwrap().current > damaged);
+}
+
+#[test]
+fn test_damage_lowers_efficiency_and_raises_mutation() {
+    let mut app = MetabolisticApp::new_headless();
+    app.update();
+    app.world_mut().insert_resource(LipidToxicityThreshold(10.0));
+    app.world_mut().spawn((
+        CellMass { base: 1.0, extra: 0.0 },
+        CellHealth {
+            current: 20.0,
+            max: 100.0,
+        },
+    ));
+
+    run_ticks(app.world_mut(), 1);
+    let efficiency = app.world().resource::<MetabolicEfficiency>().0;
+    assert!(efficiency < 1.0 && efficiency > 0.25, "efficiency {}", efficiency);
+    assert!(app.world().resource::<MutationConfig>().rate_multiplier > 1.0);
+
+    // Flooding the cell with fatty acids finishes it off
+    player_pools_mut(app.world_mut())
+        .set(Currency::FreeFattyAcids, 100_000.0);
+    run_ticks(app.world_mut(), 1);
+    assert_eq!(*app.world().resource::<CellVitality>(), CellVitality::Dead);
+    assert_eq!(app.world().resource::<MetabolicEfficiency>().0, 0.25);
+}
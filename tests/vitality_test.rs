@@ -0,0 +1,111 @@
+//! # Vitality Tests
+//!
+//! A cell without ATP goes dormant, metabolises only every few ticks, wakes once ATP
+//! recovers and dies if it starves for too long.
+
+use bevy::prelude::App;
+use metabolistic3d::blocks::genome::BlockKind;
+use metabolistic3d::metabolism::cells::{player_genome_mut, player_pools, player_pools_mut};
+use metabolistic3d::metabolism::vitality::{
+    next_vitality, CellVitality, StarvationTicks, VitalityRules,
+};
+use metabolistic3d::metabolism::{
+    run_ticks, BlockStatus, FlowDirty, FluxProfile, MetabolicNode, MetabolicTick,
+};
+use metabolistic3d::molecules::Currency;
+use metabolistic3d::snapshot::SimulationSnapshot;
+use metabolistic3d::MetabolisticApp;
+use std::collections::HashMap;
+
+/// An expressed node that turns pyruvate into waste without needing ATP.
+fn spawn_waste_producer(app: &mut App) {
+    let mut genome = player_genome_mut(app.world_mut());
+    genome.add_gene(BlockKind::AminoAcidBiosynthesis);
+    genome.express_gene(BlockKind::AminoAcidBiosynthesis);
+    app.world_mut().spawn((
+        MetabolicNode {
+            kind: BlockKind::AminoAcidBiosynthesis,
+            status: BlockStatus::Active,
+        },
+        FluxProfile(HashMap::from([
+            (Currency::Pyruvate, -0.1),
+            (Currency::OrganicWaste, 0.1),
+        ])),
+    ));
+    app.world_mut().resource_mut::<FlowDirty>().0 = true;
+}
+
+#[test]
+fn test_vitality_transitions() {
+    let rules = VitalityRules::default();
+    let next = |current, atp, starving| next_vitality(current, atp, starving, &rules);
+
+    assert_eq!(next(CellVitality::Active, 80.0, 0), CellVitality::Active);
+    assert_eq!(next(CellVitality::Active, 10.0, 0), CellVitality::Stressed);
+    assert_eq!(next(CellVitality::Stressed, 0.0, 1), CellVitality::Stressed);
+    assert_eq!(
+        next(CellVitality::Stressed, 0.0, rules.dormancy_ticks),
+        CellVitality::Dormant
+    );
+    // Waking up needs more than the first trickle of ATP
+    assert_eq!(next(CellVitality::Dormant, 2.0, 0), CellVitality::Dormant);
+    assert_eq!(
+        next(CellVitality::Dormant, rules.recovery_atp, 0),
+        CellVitality::Stressed
+    );
+    assert_eq!(next(CellVitality::Dormant, 0.0, rules.death_ticks), CellVitality::Dead);
+    assert_eq!(next(CellVitality::Dead, 100.0, 0), CellVitality::Dead);
+}
+
+#[test]
+fn test_starving_cell_goes_dormant_then_recovers() {
+    let mut app = MetabolisticApp::new_headless();
+    app.update();
+    let rules = app.world().resource::<VitalityRules>().clone();
+    spawn_waste_producer(&mut app);
+    player_pools_mut(app.world_mut()).set(Currency::ATP, 0.0);
+
+    run_ticks(app.world_mut(), u64::from(rules.dormancy_ticks));
+    assert_eq!(*app.world().resource::<CellVitality>(), CellVitality::Dormant);
+
+    // Only every `dormant_interval`-th tick reaches the metabolic systems
+    for _ in 0..rules.dormant_interval * 2 {
+        let pyruvate = player_pools(app.world()).get(Currency::Pyruvate);
+        run_ticks(app.world_mut(), 1);
+        let tick = app.world().resource::<MetabolicTick>().0;
+        let consumed = player_pools(app.world()).get(Currency::Pyruvate) < pyruvate;
+        assert_eq!(consumed, tick.is_multiple_of(rules.dormant_interval), "tick {}", tick);
+    }
+
+    player_pools_mut(app.world_mut())
+        .set(Currency::ATP, rules.stressed_atp * 2.0);
+    run_ticks(app.world_mut(), 1);
+    assert_eq!(*app.world().resource::<CellVitality>(), CellVitality::Active);
+    assert_eq!(app.world().resource::<StarvationTicks>().0, 0);
+}
+
+#[test]
+fn test_prolonged_starvation_is_fatal() {
+    let mut app = MetabolisticApp::new_headless();
+    app.update();
+    spawn_waste_producer(&mut app);
+    app.world_mut().resource_mut::<VitalityRules>().death_ticks = 20;
+    player_pools_mut(app.world_mut()).set(Currency::ATP, 0.0);
+
+    run_ticks(app.world_mut(), 20);
+    assert_eq!(*app.world().resource::<CellVitality>(), CellVitality::Dead);
+
+    // Metabolism has stopped for good, even with ATP back
+    player_pools_mut(app.world_mut()).set(Currency::ATP, 100.0);
+    let before = player_pools(app.world()).pools.clone();
+    run_ticks(app.world_mut(), 8);
+    assert_eq!(*app.world().resource::<CellVitality>(), CellVitality::Dead);
+    assert_eq!(player_pools(app.world()).pools, before);
+
+    // Death survives a save and load
+    let snapshot = SimulationSnapshot::capture(app.world_mut());
+    let mut restored = MetabolisticApp::new_headless();
+    restored.update();
+    snapshot.apply(restored.world_mut()).unwrap();
+    assert_eq!(*restored.world().resource::<CellVitality>(), CellVitality::Dead);
+}